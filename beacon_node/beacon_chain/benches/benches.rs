@@ -0,0 +1,111 @@
+use beacon_chain::test_utils::{
+    AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+};
+use beacon_chain::{
+    signature_verify_chain_segment, signature_verify_chain_segments, WhenSlotSkipped,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::sync::Arc;
+use types::{
+    test_utils::generate_deterministic_keypairs, EthSpec, Hash256, MainnetEthSpec,
+    SignedBeaconBlock, Slot,
+};
+
+type E = MainnetEthSpec;
+
+const VALIDATOR_COUNT: usize = 64;
+/// The number of chain segments to verify, each from its own epoch.
+const SEGMENT_COUNT: usize = 4;
+/// The number of blocks in each chain segment.
+const SEGMENT_LENGTH: usize = 8;
+
+type ChainSegment = Vec<(Hash256, Arc<SignedBeaconBlock<E>>)>;
+
+/// Produce a harness along with `SEGMENT_COUNT` disjoint chain segments from consecutive epochs.
+///
+/// Every block is imported, so that the parent of the first block in each segment is known.
+fn get_chain_segments(
+    runtime: &tokio::runtime::Runtime,
+) -> (
+    BeaconChainHarness<EphemeralHarnessType<E>>,
+    Vec<ChainSegment>,
+) {
+    runtime.block_on(async {
+        let harness = BeaconChainHarness::builder(E::default())
+            .default_spec()
+            .keypairs(generate_deterministic_keypairs(VALIDATOR_COUNT))
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+
+        let slots_per_epoch = E::slots_per_epoch();
+        harness
+            .extend_chain(
+                (SEGMENT_COUNT + 1) * slots_per_epoch as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        let mut segments = Vec::with_capacity(SEGMENT_COUNT);
+        for epoch in 1..=SEGMENT_COUNT as u64 {
+            let start_slot = epoch * slots_per_epoch;
+            let mut segment = Vec::with_capacity(SEGMENT_LENGTH);
+            for slot in start_slot..start_slot + SEGMENT_LENGTH as u64 {
+                let block_root = harness
+                    .chain
+                    .block_root_at_slot(Slot::new(slot), WhenSlotSkipped::None)
+                    .expect("should read block root")
+                    .expect("slot should not be skipped");
+                let block = harness
+                    .chain
+                    .get_block(&block_root)
+                    .await
+                    .expect("should read block")
+                    .expect("block should exist");
+                segment.push((block_root, Arc::new(block)));
+            }
+            segments.push(segment);
+        }
+
+        (harness, segments)
+    })
+}
+
+fn chain_segment_signatures(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("should start runtime");
+    let (harness, segments) = get_chain_segments(&runtime);
+
+    c.bench_function(
+        &format!(
+            "verify {} chain segments of {} blocks (sequential)",
+            SEGMENT_COUNT, SEGMENT_LENGTH
+        ),
+        |b| {
+            b.iter(|| {
+                for segment in &segments {
+                    let result = signature_verify_chain_segment(segment.clone(), &harness.chain);
+                    assert!(black_box(result).is_ok());
+                }
+            })
+        },
+    );
+
+    c.bench_function(
+        &format!(
+            "verify {} chain segments of {} blocks (batched)",
+            SEGMENT_COUNT, SEGMENT_LENGTH
+        ),
+        |b| {
+            b.iter(|| {
+                let results = signature_verify_chain_segments(segments.clone(), &harness.chain)
+                    .expect("should read pubkey cache");
+                assert!(black_box(results).iter().all(Result::is_ok));
+            })
+        },
+    );
+}
+
+criterion_group!(benches, chain_segment_signatures);
+criterion_main!(benches);
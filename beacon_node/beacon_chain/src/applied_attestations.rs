@@ -0,0 +1,122 @@
+//! Provides the `AppliedAttestations` struct which records the attestations from blocks that have
+//! been applied to fork choice whilst importing a chain segment.
+//!
+//! Consecutive blocks frequently re-include aggregates for the same vote. Applying such an
+//! attestation to fork choice a second time has no effect, so it can be skipped.
+//!
+//! ## Correctness
+//!
+//! An attestation is only skipped if *every* one of its attesting indices has already been applied
+//! to fork choice with an identical `(slot, beacon_block_root, target)`. Attestations which were
+//! rejected by fork choice are never recorded, so an attestation is never skipped because of an
+//! earlier attestation that had no effect.
+
+use std::collections::{HashMap, HashSet};
+use types::{Checkpoint, EthSpec, Hash256, IndexedAttestation, Slot};
+
+/// Identifies a fork choice vote.
+type VoteKey = (Slot, Hash256, Checkpoint);
+
+/// Records attestations which have been applied to fork choice.
+///
+/// This is intended to live for the duration of a single chain segment import and is not pruned.
+#[derive(Default)]
+pub struct AppliedAttestations {
+    applied: HashMap<VoteKey, HashSet<u64>>,
+}
+
+impl AppliedAttestations {
+    /// Returns `true` if all of the attesting indices of `indexed_attestation` have already been
+    /// applied to fork choice with an identical vote.
+    pub fn is_redundant<E: EthSpec>(&self, indexed_attestation: &IndexedAttestation<E>) -> bool {
+        self.applied
+            .get(&vote_key(indexed_attestation))
+            .map_or(false, |indices| {
+                indexed_attestation
+                    .attesting_indices
+                    .iter()
+                    .all(|index| indices.contains(index))
+            })
+    }
+
+    /// Record that `indexed_attestation` has been applied to fork choice.
+    pub fn insert<E: EthSpec>(&mut self, indexed_attestation: &IndexedAttestation<E>) {
+        self.applied
+            .entry(vote_key(indexed_attestation))
+            .or_default()
+            .extend(indexed_attestation.attesting_indices.iter().copied());
+    }
+}
+
+fn vote_key<E: EthSpec>(indexed_attestation: &IndexedAttestation<E>) -> VoteKey {
+    let data = &indexed_attestation.data;
+    (data.slot, data.beacon_block_root, data.target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{AggregateSignature, AttestationData, Epoch, MainnetEthSpec, VariableList};
+
+    type E = MainnetEthSpec;
+
+    fn indexed_attestation(
+        slot: u64,
+        root: u64,
+        target_epoch: u64,
+        indices: &[u64],
+    ) -> IndexedAttestation<E> {
+        IndexedAttestation {
+            attesting_indices: VariableList::new(indices.to_vec()).unwrap(),
+            data: AttestationData {
+                slot: Slot::new(slot),
+                index: 0,
+                beacon_block_root: Hash256::from_low_u64_be(root),
+                source: Checkpoint::default(),
+                target: Checkpoint {
+                    epoch: Epoch::new(target_epoch),
+                    root: Hash256::from_low_u64_be(root),
+                },
+            },
+            signature: AggregateSignature::empty(),
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let applied = AppliedAttestations::default();
+        assert!(!applied.is_redundant(&indexed_attestation(1, 1, 0, &[1, 2])));
+    }
+
+    #[test]
+    fn subsets_are_redundant() {
+        let mut applied = AppliedAttestations::default();
+        applied.insert(&indexed_attestation(1, 1, 0, &[1, 2, 3]));
+
+        assert!(applied.is_redundant(&indexed_attestation(1, 1, 0, &[1, 2, 3])));
+        assert!(applied.is_redundant(&indexed_attestation(1, 1, 0, &[2])));
+        assert!(
+            !applied.is_redundant(&indexed_attestation(1, 1, 0, &[3, 4])),
+            "partially overlapping attestations must be applied"
+        );
+    }
+
+    #[test]
+    fn indices_accumulate_across_attestations() {
+        let mut applied = AppliedAttestations::default();
+        applied.insert(&indexed_attestation(1, 1, 0, &[1, 2]));
+        applied.insert(&indexed_attestation(1, 1, 0, &[3]));
+
+        assert!(applied.is_redundant(&indexed_attestation(1, 1, 0, &[1, 3])));
+    }
+
+    #[test]
+    fn different_votes_are_not_redundant() {
+        let mut applied = AppliedAttestations::default();
+        applied.insert(&indexed_attestation(1, 1, 0, &[1, 2]));
+
+        assert!(!applied.is_redundant(&indexed_attestation(2, 1, 0, &[1, 2])));
+        assert!(!applied.is_redundant(&indexed_attestation(1, 2, 0, &[1, 2])));
+        assert!(!applied.is_redundant(&indexed_attestation(1, 1, 1, &[1, 2])));
+    }
+}
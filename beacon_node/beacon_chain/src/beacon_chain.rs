@@ -1,3 +1,4 @@
+use crate::applied_attestations::AppliedAttestations;
 use crate::attestation_verification::{
     batch_verify_aggregated_attestations, batch_verify_unaggregated_attestations,
     Error as AttestationError, VerifiedAggregatedAttestation, VerifiedAttestation,
@@ -5,16 +6,19 @@ use crate::attestation_verification::{
 };
 use crate::attester_cache::{AttesterCache, AttesterCacheKey};
 use crate::beacon_block_streamer::{BeaconBlockStreamer, CheckEarlyAttesterCache};
-use crate::beacon_proposer_cache::compute_proposer_duties_from_head;
 use crate::beacon_proposer_cache::BeaconProposerCache;
+use crate::beacon_proposer_cache::{compute_proposer_duties_from_head, ensure_state_is_in_epoch};
 use crate::block_times_cache::BlockTimesCache;
 use crate::block_verification::{
-    check_block_is_finalized_checkpoint_or_descendant, check_block_relevancy, get_block_root,
-    signature_verify_chain_segment, BlockError, ExecutionPendingBlock, GossipVerifiedBlock,
-    IntoExecutionPendingBlock, PayloadVerificationOutcome, POS_PANDA_BANNER,
+    check_block_is_finalized_checkpoint_or_descendant, check_block_relevancy,
+    check_chain_segment_linearity, expected_proposer_for_block, get_block_root,
+    signature_verify_chain_segment, verify_block_header_proposal, BlockError, DryRunVerifiedBlock,
+    ExecutionPendingBlock, ExecutionPendingHook, GossipVerifiedBlock, IntoExecutionPendingBlock,
+    PayloadVerificationOutcome, ProposerFilter, SignatureVerifiedBlock, POS_PANDA_BANNER,
 };
 pub use crate::canonical_head::{CanonicalHead, CanonicalHeadRwLock};
 use crate::chain_config::ChainConfig;
+use crate::committee_state_cache::CommitteeStateCache;
 use crate::early_attester_cache::EarlyAttesterCache;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
@@ -43,9 +47,12 @@ use crate::observed_attesters::{
 };
 use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
+use crate::pending_payload_verifications::PendingPayloadVerifications;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
+use crate::proposer_rate_limiter::ProposerRateLimiter;
+use crate::rejected_block_cache::RejectedBlockCache;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::{BlockProductionPreState, SnapshotCache};
 use crate::sync_committee_verification::{
@@ -68,6 +75,7 @@ use fork_choice::{
     InvalidationOperation, PayloadVerificationStatus, ResetPayloadStatuses,
 };
 use futures::channel::mpsc::Sender;
+use futures::stream::{self, StreamExt};
 use itertools::process_results;
 use itertools::Itertools;
 use operation_pool::{AttestationRef, OperationPool, PersistedOperationPool, ReceivedPreCapella};
@@ -103,7 +111,10 @@ use store::{
     DatabaseBlock, Error as DBError, HotColdDB, KeyValueStore, KeyValueStoreOp, StoreItem, StoreOp,
 };
 use task_executor::{ShutdownReason, TaskExecutor};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Semaphore;
 use tokio_stream::Stream;
+use tokio_util::sync::CancellationToken;
 use tree_hash::TreeHash;
 use types::beacon_state::CloneConfig;
 use types::*;
@@ -151,6 +162,13 @@ const PREPARE_PROPOSER_HISTORIC_EPOCHS: u64 = 4;
 /// impact whilst having 8 epochs without a block is a comfortable grace period.
 const MAX_PER_SLOT_FORK_CHOICE_DISTANCE: u64 = 256;
 
+/// The maximum number of chain segments which `BeaconChain::process_chain_segments_concurrently`
+/// will process at once.
+///
+/// Each segment holds its own states whilst it is being processed, so this bounds the memory and
+/// CPU which a single batch of blocks can claim.
+const MAX_CONCURRENT_CHAIN_SEGMENTS: usize = 4;
+
 /// Reported to the user when the justified block has an invalid execution payload.
 pub const INVALID_JUSTIFIED_PAYLOAD_SHUTDOWN_REASON: &str =
     "Justified block has an invalid execution payload.";
@@ -180,6 +198,65 @@ pub enum ChainSegmentResult<T: EthSpec> {
         imported_blocks: usize,
         error: BlockError<T>,
     },
+    /// Processing this chain segment was cancelled via its `CancellationToken` before all of the
+    /// blocks were imported. The blocks imported prior to cancellation remain imported.
+    Cancelled { imported_blocks: usize },
+}
+
+/// The outcome of a single block of a chain segment, as sent on a `ChainSegmentProgressSender`.
+#[derive(Debug)]
+pub enum ChainSegmentBlockOutcome<T: EthSpec> {
+    /// The block was imported.
+    Imported,
+    /// The block was filtered out of the segment and not imported, for the given reason (e.g.,
+    /// `BlockError::BlockIsAlreadyKnown`). Processing of the segment continued regardless.
+    NotImported(BlockError<T>),
+    /// Processing of the segment failed at this block. The error is returned in
+    /// `ChainSegmentResult::Failed`, since `BlockError` cannot be cloned.
+    Failed,
+    /// The block was verified as part of a batch which failed as a whole (e.g., a batch containing
+    /// an invalid signature, or a segment which is not a chain). The block itself is not
+    /// necessarily invalid. The error is returned in `ChainSegmentResult::Failed`.
+    BatchFailed,
+    /// The block was not processed, since processing of the segment stopped at an earlier block or
+    /// was cancelled.
+    NotProcessed,
+}
+
+/// Receives the outcome of every block of a chain segment during its processing.
+pub type ChainSegmentProgressSender<T> = UnboundedSender<(Hash256, ChainSegmentBlockOutcome<T>)>;
+
+/// Sends `outcome` on `progress_tx`, if any. A dropped receiver is ignored.
+fn send_chain_segment_progress<T: EthSpec>(
+    progress_tx: Option<&ChainSegmentProgressSender<T>>,
+    block_root: Hash256,
+    outcome: ChainSegmentBlockOutcome<T>,
+) {
+    if let Some(progress_tx) = progress_tx {
+        let _ = progress_tx.send((block_root, outcome));
+    }
+}
+
+/// Sends `ChainSegmentBlockOutcome::NotProcessed` for each of `block_roots` on `progress_tx`, if
+/// any.
+fn send_chain_segment_not_processed<T: EthSpec>(
+    progress_tx: Option<&ChainSegmentProgressSender<T>>,
+    block_roots: impl IntoIterator<Item = Hash256>,
+) {
+    for block_root in block_roots {
+        send_chain_segment_progress(
+            progress_tx,
+            block_root,
+            ChainSegmentBlockOutcome::NotProcessed,
+        );
+    }
+}
+
+/// Returns the roots of `blocks`.
+fn hash_block_roots<T: EthSpec>(
+    blocks: &[HashBlockTuple<T>],
+) -> impl Iterator<Item = Hash256> + '_ {
+    blocks.iter().map(|(block_root, _)| *block_root)
 }
 
 /// Configure the signature verification of produced blocks.
@@ -405,6 +482,9 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub eth1_finalization_cache: TimeoutRwLock<Eth1FinalizationCache>,
     /// Caches the beacon block proposer shuffling for a given epoch and shuffling key root.
     pub beacon_proposer_cache: Mutex<BeaconProposerCache>,
+    /// Caches states which have been cheaply advanced to obtain committees during block
+    /// verification.
+    pub(crate) committee_state_cache: CommitteeStateCache<T::EthSpec>,
     /// Caches a map of `validator_index -> validator_pubkey`.
     pub(crate) validator_pubkey_cache: TimeoutRwLock<ValidatorPubkeyCache<T>>,
     /// A cache used when producing attestations.
@@ -415,6 +495,11 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
+    /// Tracks execution payloads which are awaiting verification by the execution engine.
+    pub(crate) pending_payload_verifications: PendingPayloadVerifications,
+    /// Limits the number of payloads being verified by the execution engine at once, see
+    /// `ChainConfig::max_concurrent_payload_verifications`.
+    pub(crate) payload_verification_permits: Semaphore,
     /// Sender given to tasks, so that if they encounter a state in which execution cannot
     /// continue they can request that everything shuts down.
     pub shutdown_sender: Sender<ShutdownReason>,
@@ -424,6 +509,14 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) graffiti: Graffiti,
     /// Optional slasher.
     pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
+    /// Optional local policy used to refuse gossip blocks from specific proposers.
+    pub proposer_filter: Option<ProposerFilter>,
+    /// Optional callback run on each block which passes full verification, prior to import.
+    pub on_execution_pending: Option<ExecutionPendingHook<T>>,
+    /// Optional limit on the number of distinct gossip blocks accepted from each proposer.
+    pub(crate) proposer_rate_limiter: Option<ProposerRateLimiter>,
+    /// Recently rejected gossip blocks, which are refused without being re-verified.
+    pub(crate) rejected_block_cache: RejectedBlockCache,
     /// Provides monitoring of a set of explicitly defined validators.
     pub validator_monitor: RwLock<ValidatorMonitor<T::EthSpec>>,
     /// The slot at which blocks are downloaded back to.
@@ -1111,6 +1204,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(self.store.get_blinded_block(block_root)?)
     }
 
+    /// Load the block stored under `block_root` and check that its root, as recomputed by
+    /// `get_block_root`, is equal to `block_root`.
+    ///
+    /// Returns `None` if there is no such block. Full and blinded blocks have the same root, so
+    /// the block is checked in whichever form it is stored.
+    ///
+    /// This is a diagnostic for database corruption, the block is not otherwise verified.
+    pub fn reverify_block_root(&self, block_root: &Hash256) -> Result<Option<bool>, Error> {
+        let computed_root = match self.store.try_get_full_block(block_root)? {
+            Some(DatabaseBlock::Full(block)) => get_block_root(&block),
+            Some(DatabaseBlock::Blinded(block)) => get_block_root(&block),
+            None => return Ok(None),
+        };
+        Ok(Some(computed_root == *block_root))
+    }
+
     /// Returns the state at the given root, if any.
     ///
     /// ## Errors
@@ -2429,60 +2538,97 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn filter_chain_segment(
         self: &Arc<Self>,
         chain_segment: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+    ) -> Result<Vec<HashBlockTuple<T::EthSpec>>, ChainSegmentResult<T::EthSpec>> {
+        self.filter_chain_segment_with_progress(chain_segment, None)
+    }
+
+    /// As per `Self::filter_chain_segment`, but the outcome of each block which is filtered out of
+    /// the segment is sent on `progress_tx`. If filtering fails, the outcome of every block is
+    /// sent.
+    fn filter_chain_segment_with_progress(
+        self: &Arc<Self>,
+        chain_segment: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+        progress_tx: Option<&ChainSegmentProgressSender<T::EthSpec>>,
     ) -> Result<Vec<HashBlockTuple<T::EthSpec>>, ChainSegmentResult<T::EthSpec>> {
         // This function will never import any blocks.
         let imported_blocks = 0;
         let mut filtered_chain_segment = Vec::with_capacity(chain_segment.len());
 
-        // Produce a list of the parent root and slot of the child of each block.
-        //
-        // E.g., `children[0] == (chain_segment[1].parent_root(), chain_segment[1].slot())`
-        let children = chain_segment
-            .iter()
-            .skip(1)
-            .map(|block| (block.parent_root(), block.slot()))
+        let chain_segment = chain_segment
+            .into_iter()
+            .map(|block| (get_block_root(&block), block))
             .collect::<Vec<_>>();
 
-        for (i, block) in chain_segment.into_iter().enumerate() {
-            // Ensure the block is the correct structure for the fork at `block.slot()`.
-            if let Err(e) = block.fork_name(&self.spec) {
-                return Err(ChainSegmentResult::Failed {
-                    imported_blocks,
-                    error: BlockError::InconsistentFork(e),
-                });
+        // Ensure that each block is the parent of the next and that the slots are strictly
+        // increasing throughout the chain segment.
+        //
+        // Without this check it would be possible to have a block verified using the incorrect
+        // shuffling. That would be bad, mmkay.
+        if let Err(error) = check_chain_segment_linearity(
+            chain_segment
+                .iter()
+                .map(|(block_root, block)| (*block_root, block.as_ref())),
+        ) {
+            for (block_root, _) in &chain_segment {
+                send_chain_segment_progress(
+                    progress_tx,
+                    *block_root,
+                    ChainSegmentBlockOutcome::BatchFailed,
+                );
             }
+            return Err(ChainSegmentResult::Failed {
+                imported_blocks,
+                error,
+            });
+        }
 
-            let block_root = get_block_root(&block);
-
-            if let Some((child_parent_root, child_slot)) = children.get(i) {
-                // If this block has a child in this chain segment, ensure that its parent root matches
-                // the root of this block.
-                //
-                // Without this check it would be possible to have a block verified using the
-                // incorrect shuffling. That would be bad, mmkay.
-                if block_root != *child_parent_root {
-                    return Err(ChainSegmentResult::Failed {
-                        imported_blocks,
-                        error: BlockError::NonLinearParentRoots,
-                    });
-                }
+        // Reports the outcome of every block when filtering fails at `block_root`.
+        let filtering_failed = |filtered_chain_segment: &[HashBlockTuple<T::EthSpec>],
+                                block_root: Hash256,
+                                remaining: &mut dyn Iterator<Item = HashBlockTuple<T::EthSpec>>,
+                                error: BlockError<T::EthSpec>| {
+            send_chain_segment_not_processed(progress_tx, hash_block_roots(filtered_chain_segment));
+            send_chain_segment_progress(progress_tx, block_root, ChainSegmentBlockOutcome::Failed);
+            send_chain_segment_not_processed(
+                progress_tx,
+                remaining.map(|(block_root, _)| block_root),
+            );
+            ChainSegmentResult::Failed {
+                imported_blocks,
+                error,
+            }
+        };
 
-                // Ensure that the slots are strictly increasing throughout the chain segment.
-                if *child_slot <= block.slot() {
-                    return Err(ChainSegmentResult::Failed {
-                        imported_blocks,
-                        error: BlockError::NonLinearSlots,
-                    });
-                }
+        let mut chain_segment = chain_segment.into_iter();
+        while let Some((block_root, block)) = chain_segment.next() {
+            // Ensure the block is the correct structure for the fork at `block.slot()`.
+            if let Err(e) = block.fork_name(&self.spec) {
+                return Err(filtering_failed(
+                    &filtered_chain_segment,
+                    block_root,
+                    &mut chain_segment,
+                    BlockError::InconsistentFork(e),
+                ));
             }
 
-            match check_block_relevancy(&block, block_root, self) {
+            // Reports a block which is filtered out of the segment.
+            let ignore = |error| {
+                send_chain_segment_progress(
+                    progress_tx,
+                    block_root,
+                    ChainSegmentBlockOutcome::NotImported(error),
+                )
+            };
+
+            match check_block_relevancy(&block, Some(block_root), self, None) {
                 // If the block is relevant, add it to the filtered chain segment.
-                Ok(_) => filtered_chain_segment.push((block_root, block)),
+                Ok(block_root) => filtered_chain_segment.push((block_root, block)),
                 // If the block is already known, simply ignore this block.
-                Err(BlockError::BlockIsAlreadyKnown) => continue,
+                Err(e @ BlockError::BlockIsAlreadyKnown) => ignore(e),
+                // If the block is the finalized block it is also known, ignore it.
+                Err(e @ BlockError::BlockAtFinalizedSlot { .. }) => ignore(e),
                 // If the block is the genesis block, simply ignore this block.
-                Err(BlockError::GenesisBlock) => continue,
+                Err(e @ BlockError::GenesisBlock) => ignore(e),
                 // If the block is is for a finalized slot, simply ignore this block.
                 //
                 // The block is either:
@@ -2496,29 +2642,41 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 // In the case of (2), skipping the block is valid since we should never import it.
                 // However, we will potentially get a `ParentUnknown` on a later block. The sync
                 // protocol will need to ensure this is handled gracefully.
-                Err(BlockError::WouldRevertFinalizedSlot { .. }) => continue,
+                Err(e @ BlockError::WouldRevertFinalizedSlot { .. }) => ignore(e),
                 // The block has a known parent that does not descend from the finalized block.
                 // There is no need to process this block or any children.
-                Err(BlockError::NotFinalizedDescendant { block_parent_root }) => {
-                    return Err(ChainSegmentResult::Failed {
-                        imported_blocks,
-                        error: BlockError::NotFinalizedDescendant { block_parent_root },
-                    });
+                Err(e @ BlockError::NotFinalizedDescendant { .. }) => {
+                    return Err(filtering_failed(
+                        &filtered_chain_segment,
+                        block_root,
+                        &mut chain_segment,
+                        e,
+                    ));
                 }
                 // If there was an error whilst determining if the block was invalid, return that
                 // error.
-                Err(BlockError::BeaconChainError(e)) => {
-                    return Err(ChainSegmentResult::Failed {
-                        imported_blocks,
-                        error: BlockError::BeaconChainError(e),
-                    });
+                Err(e @ BlockError::BeaconChainError(_)) => {
+                    return Err(filtering_failed(
+                        &filtered_chain_segment,
+                        block_root,
+                        &mut chain_segment,
+                        e,
+                    ));
                 }
                 // If the block was decided to be irrelevant for any other reason, don't include
                 // this block or any of it's children in the filtered chain segment.
-                _ => break,
+                Err(e) => {
+                    ignore(e);
+                    break;
+                }
             }
         }
 
+        send_chain_segment_not_processed(
+            progress_tx,
+            chain_segment.map(|(block_root, _)| block_root),
+        );
+
         Ok(filtered_chain_segment)
     }
 
@@ -2537,26 +2695,123 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         chain_segment: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
         notify_execution_layer: NotifyExecutionLayer,
     ) -> ChainSegmentResult<T::EthSpec> {
+        self.process_chain_segment_with_progress(chain_segment, notify_execution_layer, None, None)
+            .await
+    }
+
+    /// As per `Self::process_chain_segment`, except that `blocks` are ordered from the highest slot
+    /// to the lowest (e.g., when walking back from a known block via parent roots).
+    ///
+    /// The blocks are imported lowest-first. As with `Self::process_chain_segment`, each block
+    /// must be the parent of the block before it in `blocks`, otherwise
+    /// `BlockError::NonLinearParentRoots` is returned without importing any blocks.
+    pub async fn process_blocks_reverse(
+        self: &Arc<Self>,
+        mut blocks: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> ChainSegmentResult<T::EthSpec> {
+        blocks.reverse();
+        self.process_chain_segment(blocks, notify_execution_layer)
+            .await
+    }
+
+    /// Verifies the signatures of each of `blocks` independently, returning a result for each block
+    /// in the same order as `blocks`.
+    ///
+    /// Unlike `signature_verify_chain_segment`, an invalid block does not cause the others to fail:
+    /// each block is verified against its own parent with its own signature batch. The parent of
+    /// each block must already be imported, since none of `blocks` are imported.
+    ///
+    /// Each block must pass `check_block_relevancy` (e.g., it must not already be known). Parents
+    /// are cloned from the snapshot cache, as for a dry run, so that verification leaves the cache
+    /// unchanged.
+    ///
+    /// This is much slower than verifying a chain segment and is intended as a diagnostic API
+    /// (e.g., for assessing a batch of blocks from many peers). It must not be used for sync.
+    pub fn verify_blocks_independently(
+        &self,
+        blocks: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+    ) -> Vec<Result<SignatureVerifiedBlock<T>, BlockError<T::EthSpec>>> {
+        blocks
+            .into_iter()
+            .map(|block| {
+                let block_root = check_block_relevancy(&block, None, self, None)?;
+                SignatureVerifiedBlock::new_for_dry_run(block, block_root, self)
+            })
+            .collect()
+    }
+
+    /// As per `Self::process_chain_segment`, but the outcome of each block of `chain_segment` is
+    /// also sent on `progress_tx` as soon as it is known.
+    ///
+    /// Every block is reported exactly once, including blocks which are filtered out of the
+    /// segment, blocks in a batch which fails signature verification and blocks which are not
+    /// processed due to an earlier failure or cancellation (see `ChainSegmentBlockOutcome`). A
+    /// dropped receiver does not interrupt processing.
+    ///
+    /// If `cancellation_token` is cancelled, processing stops before the next block and
+    /// `ChainSegmentResult::Cancelled` is returned. The token is only checked between blocks, so
+    /// the import of the current block (including the verification of its execution payload) is
+    /// always completed first.
+    pub async fn process_chain_segment_with_progress(
+        self: &Arc<Self>,
+        chain_segment: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        progress_tx: Option<ChainSegmentProgressSender<T::EthSpec>>,
+        cancellation_token: Option<CancellationToken>,
+    ) -> ChainSegmentResult<T::EthSpec> {
+        let is_cancelled = || {
+            cancellation_token
+                .as_ref()
+                .map_or(false, CancellationToken::is_cancelled)
+        };
+        let progress_tx = progress_tx.as_ref();
+
         let mut imported_blocks = 0;
 
+        // Avoid re-applying the same attestations to fork choice for each block in the segment.
+        let applied_attestations = Arc::new(Mutex::new(AppliedAttestations::default()));
+
         // Filter uninteresting blocks from the chain segment in a blocking task.
+        //
+        // The blocks are retained so that they can be reported if the task fails.
+        let unfiltered_chain_segment = progress_tx.map(|_| chain_segment.clone());
         let chain = self.clone();
+        let filter_progress_tx = progress_tx.cloned();
         let filtered_chain_segment_future = self.spawn_blocking_handle(
-            move || chain.filter_chain_segment(chain_segment),
+            move || {
+                chain.filter_chain_segment_with_progress(chain_segment, filter_progress_tx.as_ref())
+            },
             "filter_chain_segment",
         );
         let mut filtered_chain_segment = match filtered_chain_segment_future.await {
             Ok(Ok(filtered_segment)) => filtered_segment,
             Ok(Err(segment_result)) => return segment_result,
             Err(error) => {
+                send_chain_segment_not_processed(
+                    progress_tx,
+                    unfiltered_chain_segment
+                        .iter()
+                        .flatten()
+                        .map(|block| get_block_root(block)),
+                );
                 return ChainSegmentResult::Failed {
                     imported_blocks,
                     error: BlockError::BeaconChainError(error),
-                }
+                };
             }
         };
 
         while let Some((_root, block)) = filtered_chain_segment.first() {
+            // Avoid signature verifying blocks which will not be imported.
+            if is_cancelled() {
+                send_chain_segment_not_processed(
+                    progress_tx,
+                    hash_block_roots(&filtered_chain_segment),
+                );
+                return ChainSegmentResult::Cancelled { imported_blocks };
+            }
+
             // Determine the epoch of the first block in the remaining segment.
             let start_epoch = block.slot().epoch(T::EthSpec::slots_per_epoch());
 
@@ -2573,6 +2828,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
             let mut blocks = filtered_chain_segment.split_off(last_index);
             std::mem::swap(&mut blocks, &mut filtered_chain_segment);
+            let block_roots = blocks.iter().map(|(root, _)| *root).collect::<Vec<_>>();
 
             let chain = self.clone();
             let signature_verification_future = self.spawn_blocking_handle(
@@ -2584,12 +2840,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             let signature_verified_blocks = match signature_verification_future.await {
                 Ok(Ok(blocks)) => blocks,
                 Ok(Err(error)) => {
+                    for block_root in block_roots {
+                        send_chain_segment_progress(
+                            progress_tx,
+                            block_root,
+                            ChainSegmentBlockOutcome::BatchFailed,
+                        );
+                    }
+                    send_chain_segment_not_processed(
+                        progress_tx,
+                        hash_block_roots(&filtered_chain_segment),
+                    );
                     return ChainSegmentResult::Failed {
                         imported_blocks,
                         error,
                     };
                 }
                 Err(error) => {
+                    send_chain_segment_not_processed(
+                        progress_tx,
+                        block_roots
+                            .into_iter()
+                            .chain(hash_block_roots(&filtered_chain_segment)),
+                    );
                     return ChainSegmentResult::Failed {
                         imported_blocks,
                         error: BlockError::BeaconChainError(error),
@@ -2598,18 +2871,68 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             };
 
             // Import the blocks into the chain.
-            for signature_verified_block in signature_verified_blocks {
+            //
+            // The proposals are observed in a single batch once their blocks have been imported,
+            // rather than for all of the blocks up-front. Otherwise, if the import of an earlier
+            // block fails, the later blocks would remain observed without being imported and
+            // copies of them from gossip would be ignored.
+            let mut imported_proposals = Vec::with_capacity(signature_verified_blocks.len());
+            let mut signature_verified_blocks = signature_verified_blocks.into_iter();
+            while let Some(signature_verified_block) = signature_verified_blocks.next() {
+                if is_cancelled() {
+                    self.observe_imported_proposals(&imported_proposals);
+                    send_chain_segment_not_processed(
+                        progress_tx,
+                        std::iter::once(signature_verified_block.block_root())
+                            .chain(
+                                signature_verified_blocks
+                                    .as_slice()
+                                    .iter()
+                                    .map(SignatureVerifiedBlock::block_root),
+                            )
+                            .chain(hash_block_roots(&filtered_chain_segment)),
+                    );
+                    return ChainSegmentResult::Cancelled { imported_blocks };
+                }
+
+                let block_root = signature_verified_block.block_root();
+                let block = signature_verified_block.block_cloned();
+                let signature_verified_block = signature_verified_block
+                    .with_applied_attestations(applied_attestations.clone())
+                    .without_proposal_observation();
                 match self
                     .process_block(
-                        signature_verified_block.block_root(),
+                        block_root,
                         signature_verified_block,
                         notify_execution_layer,
                         || Ok(()),
                     )
                     .await
                 {
-                    Ok(_) => imported_blocks += 1,
+                    Ok(_) => {
+                        imported_blocks += 1;
+                        imported_proposals.push((block_root, block));
+                        send_chain_segment_progress(
+                            progress_tx,
+                            block_root,
+                            ChainSegmentBlockOutcome::Imported,
+                        );
+                    }
                     Err(error) => {
+                        self.observe_imported_proposals(&imported_proposals);
+                        send_chain_segment_progress(
+                            progress_tx,
+                            block_root,
+                            ChainSegmentBlockOutcome::Failed,
+                        );
+                        send_chain_segment_not_processed(
+                            progress_tx,
+                            signature_verified_blocks
+                                .as_slice()
+                                .iter()
+                                .map(SignatureVerifiedBlock::block_root)
+                                .chain(hash_block_roots(&filtered_chain_segment)),
+                        );
                         return ChainSegmentResult::Failed {
                             imported_blocks,
                             error,
@@ -2617,11 +2940,241 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     }
                 }
             }
+            self.observe_imported_proposals(&imported_proposals);
         }
 
         ChainSegmentResult::Successful { imported_blocks }
     }
 
+    /// Registers the proposals of blocks imported by `Self::process_chain_segment_with_progress`
+    /// with `observed_block_producers`, taking the lock only once.
+    fn observe_imported_proposals(
+        &self,
+        imported_proposals: &[(Hash256, Arc<SignedBeaconBlock<T::EthSpec>>)],
+    ) {
+        if imported_proposals.is_empty() {
+            return;
+        }
+
+        let proposals = imported_proposals
+            .iter()
+            .map(|(block_root, block)| (*block_root, block.message()))
+            .collect::<Vec<_>>();
+        let results = self
+            .observed_block_producers
+            .write()
+            .observe_proposals_batch(&proposals);
+
+        for ((block_root, _), result) in imported_proposals.iter().zip(results) {
+            if let Err(e) = result {
+                error!(
+                    self.log,
+                    "Failed to observe imported proposal";
+                    "block_root" => ?block_root,
+                    "error" => ?e,
+                );
+            }
+        }
+    }
+
+    /// Splits `chain_segment` into independent sub-chains which may be processed concurrently by
+    /// `Self::process_chain_segments_concurrently`.
+    ///
+    /// Each sub-chain is linear, in the order it appeared in `chain_segment`, and its first block
+    /// has a parent which is already known to fork choice. Therefore, no sub-chain contains an
+    /// ancestor of a block in another sub-chain.
+    ///
+    /// Returns `None` if `chain_segment` does not contain more than one such sub-chain. This
+    /// includes segments where the forks diverge at a block within the segment, rather than at an
+    /// imported block, since the sub-chains then depend upon one another. Such segments should be
+    /// processed by `Self::process_chain_segment`, as usual.
+    ///
+    /// Block roots are only computed if more than one block in `chain_segment` has a known
+    /// parent, so this is cheap for an ordinary linear segment.
+    pub fn partition_chain_segment(
+        &self,
+        chain_segment: &[Arc<SignedBeaconBlock<T::EthSpec>>],
+    ) -> Option<Vec<Vec<Arc<SignedBeaconBlock<T::EthSpec>>>>> {
+        let has_known_parent = {
+            let fork_choice = self.canonical_head.fork_choice_read_lock();
+            chain_segment
+                .iter()
+                .map(|block| fork_choice.contains_block(&block.parent_root()))
+                .collect::<Vec<_>>()
+        };
+        if has_known_parent.iter().filter(|known| **known).count() < 2 {
+            return None;
+        }
+
+        let mut sub_chains: Vec<Vec<Arc<SignedBeaconBlock<T::EthSpec>>>> = vec![];
+        // The root of the last block in each of the `sub_chains`.
+        let mut tips = vec![];
+        let mut block_roots = HashSet::with_capacity(chain_segment.len());
+
+        for (block, known_parent) in chain_segment.iter().zip(has_known_parent) {
+            let block_root = get_block_root(block);
+
+            // A block which appears twice could otherwise be imported by two sub-chains at once.
+            if !block_roots.insert(block_root) {
+                return None;
+            }
+
+            if let Some(i) = tips.iter().position(|tip| *tip == block.parent_root()) {
+                sub_chains[i].push(block.clone());
+                tips[i] = block_root;
+            } else if known_parent {
+                sub_chains.push(vec![block.clone()]);
+                tips.push(block_root);
+            } else {
+                // The parent is neither imported nor the tip of a sub-chain, so the segment cannot
+                // be split into independent sub-chains.
+                return None;
+            }
+        }
+
+        (sub_chains.len() > 1).then_some(sub_chains)
+    }
+
+    /// Verifies and imports each of the `chain_segments` concurrently, each as per
+    /// `Self::process_chain_segment_with_progress`.
+    ///
+    /// The `chain_segments` must be independent of one another, as returned by
+    /// `Self::partition_chain_segment`. The returned `imported_blocks` is the total across all of
+    /// the segments and, if any segment failed, the error is that of the first segment to fail
+    /// (in the order of `chain_segments`). As with `Self::process_chain_segment`, this operation
+    /// is not atomic: a failure in one segment does not prevent the import of the others.
+    ///
+    /// Unlike `Self::process_chain_segment`, the imported blocks are not necessarily a prefix of
+    /// the input. Callers must identify the failed block by its root (e.g., from `progress_tx`)
+    /// rather than by `imported_blocks`.
+    ///
+    /// ## Concurrency
+    ///
+    /// Each segment is processed on its own task, so the costly stages of block verification
+    /// (signature verification, state transitions and execution payload verification) run in
+    /// parallel across segments. At most `MAX_CONCURRENT_CHAIN_SEGMENTS` segments are processed at
+    /// once. The blocks within each segment are still imported one at a time, in order. This is
+    /// safe because:
+    ///
+    /// - The segments are disjoint and each descends from a block which was imported before
+    ///   processing started. The parent of every block is therefore either already imported or an
+    ///   earlier block in the same segment, so no block depends upon the progress of another
+    ///   segment.
+    /// - Prior to import, verification only reads from fork choice and the shared caches (e.g.,
+    ///   the snapshot, shuffling and proposer caches), each of which is protected by its own lock.
+    ///   This is the same concurrency which already occurs between gossip blocks, which the
+    ///   beacon processor verifies in parallel. Contention over the caches only affects
+    ///   performance, e.g. one segment may take the state of a shared ancestor from the snapshot
+    ///   cache, leaving the other to load it from the database.
+    /// - Fork choice is only mutated whilst holding the fork choice write lock in
+    ///   `Self::import_block`, so the fork choice mutations from each segment are serialized, just
+    ///   as for concurrently imported gossip blocks. Each segment tracks its applied attestations
+    ///   separately, so an attestation included in more than one segment may be applied to fork
+    ///   choice more than once. This is harmless, since fork choice ignores an attestation which is
+    ///   not newer than the latest message of each of its validators.
+    ///
+    /// The head is not recomputed, the caller should do so once all segments are complete.
+    pub async fn process_chain_segments_concurrently(
+        self: &Arc<Self>,
+        chain_segments: Vec<Vec<Arc<SignedBeaconBlock<T::EthSpec>>>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        progress_tx: Option<ChainSegmentProgressSender<T::EthSpec>>,
+        cancellation_token: Option<CancellationToken>,
+    ) -> ChainSegmentResult<T::EthSpec> {
+        // Spawn up to `MAX_CONCURRENT_CHAIN_SEGMENTS` segments at a time, whilst still collecting
+        // their results in the order of `chain_segments`.
+        let results = stream::iter(chain_segments)
+            .map(|chain_segment| {
+                let chain = self.clone();
+                let progress_tx = progress_tx.clone();
+                let cancellation_token = cancellation_token.clone();
+                let handle = self.task_executor.spawn_handle(
+                    async move {
+                        chain
+                            .process_chain_segment_with_progress(
+                                chain_segment,
+                                notify_execution_layer,
+                                progress_tx,
+                                cancellation_token,
+                            )
+                            .await
+                    },
+                    "process_chain_segment_concurrently",
+                );
+                async move {
+                    match handle {
+                        Some(handle) => match handle.await {
+                            Ok(Some(result)) => result,
+                            Ok(None) => ChainSegmentResult::Failed {
+                                imported_blocks: 0,
+                                error: BlockError::BeaconChainError(Error::RuntimeShutdown),
+                            },
+                            Err(e) => ChainSegmentResult::Failed {
+                                imported_blocks: 0,
+                                error: BlockError::BeaconChainError(Error::TokioJoin(e)),
+                            },
+                        },
+                        None => ChainSegmentResult::Failed {
+                            imported_blocks: 0,
+                            error: BlockError::BeaconChainError(Error::RuntimeShutdown),
+                        },
+                    }
+                }
+            })
+            .buffered(MAX_CONCURRENT_CHAIN_SEGMENTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut imported_blocks = 0;
+        let mut first_error = None;
+        let mut cancelled = false;
+
+        for result in results {
+            match result {
+                ChainSegmentResult::Successful {
+                    imported_blocks: segment_imported_blocks,
+                } => imported_blocks += segment_imported_blocks,
+                ChainSegmentResult::Failed {
+                    imported_blocks: segment_imported_blocks,
+                    error,
+                } => {
+                    imported_blocks += segment_imported_blocks;
+                    first_error.get_or_insert(error);
+                }
+                ChainSegmentResult::Cancelled {
+                    imported_blocks: segment_imported_blocks,
+                } => {
+                    imported_blocks += segment_imported_blocks;
+                    cancelled = true;
+                }
+            }
+        }
+
+        // An error takes precedence over cancellation, since it may require the peer to be
+        // penalized.
+        match first_error {
+            Some(error) => ChainSegmentResult::Failed {
+                imported_blocks,
+                error,
+            },
+            None if cancelled => ChainSegmentResult::Cancelled { imported_blocks },
+            None => ChainSegmentResult::Successful { imported_blocks },
+        }
+    }
+
+    /// Returns the index of the proposer expected to have produced `block`, according to the
+    /// proposer shuffling.
+    ///
+    /// This does not verify any signatures, nor check that `block` was actually produced by the
+    /// expected proposer. The parent of `block` must be known to fork choice. The parent is not
+    /// removed from the snapshot cache.
+    pub fn expected_proposer_for_block(
+        &self,
+        block: &SignedBeaconBlock<T::EthSpec>,
+    ) -> Result<u64, BlockError<T::EthSpec>> {
+        expected_proposer_for_block(self, block)
+    }
+
     /// Returns `Ok(GossipVerifiedBlock)` if the supplied `block` should be forwarded onto the
     /// gossip network. The block is not imported into the chain, it is just partially verified.
     ///
@@ -2676,6 +3229,66 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map_err(BeaconChainError::TokioJoin)?
     }
 
+    /// Fully verifies `block` against the current chain without importing it or mutating any
+    /// caches, returning the post-state and block reward on success.
+    ///
+    /// See `SignatureVerifiedBlock::into_dry_run_verified_block` for the side-effects which are
+    /// skipped. The caller must await the returned `payload_verification_handle` to learn the
+    /// outcome of execution payload verification.
+    pub async fn verify_block_dry_run(
+        self: &Arc<Self>,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> Result<DryRunVerifiedBlock<T>, BlockError<T::EthSpec>> {
+        let chain = self.clone();
+        self.task_executor
+            .clone()
+            .spawn_blocking_handle(
+                move || {
+                    let block_root = check_block_relevancy(&block, None, &chain, None)?;
+                    SignatureVerifiedBlock::new_for_dry_run(block, block_root, &chain)?
+                        .into_dry_run_verified_block(&chain, notify_execution_layer)
+                },
+                "verify_block_dry_run",
+            )
+            .ok_or(BeaconChainError::RuntimeShutdown)?
+            .await
+            .map_err(BeaconChainError::TokioJoin)?
+    }
+
+    /// As per `Self::verify_block_dry_run`, except that `block` is verified against the block with
+    /// root `parent_root` as loaded from the database, rather than the parent known to fork choice.
+    ///
+    /// This is intended for analysing blocks against alternative forks, see
+    /// `SignatureVerifiedBlock::new_with_parent_root`. Live paths should always use fork choice's
+    /// view of the parent.
+    pub async fn verify_block_against_parent(
+        self: &Arc<Self>,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        parent_root: Hash256,
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> Result<DryRunVerifiedBlock<T>, BlockError<T::EthSpec>> {
+        let chain = self.clone();
+        self.task_executor
+            .clone()
+            .spawn_blocking_handle(
+                move || {
+                    let block_root = get_block_root(&block);
+                    SignatureVerifiedBlock::new_with_parent_root(
+                        block,
+                        block_root,
+                        parent_root,
+                        &chain,
+                    )?
+                    .into_dry_run_verified_block(&chain, notify_execution_layer)
+                },
+                "verify_block_against_parent",
+            )
+            .ok_or(BeaconChainError::RuntimeShutdown)?
+            .await
+            .map_err(BeaconChainError::TokioJoin)?
+    }
+
     /// Returns `Ok(block_root)` if the given `unverified_block` was successfully verified and
     /// imported into the chain.
     ///
@@ -2764,6 +3377,39 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Fully verifies and imports `block`, returning its root.
+    ///
+    /// This is a convenience for tooling which doesn't need to publish the block or otherwise act
+    /// between verification and import. It is equivalent to calling `Self::process_block` with a
+    /// no-op `publish_fn`.
+    pub async fn verify_and_import_block(
+        self: &Arc<Self>,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> Result<Hash256, BlockError<T::EthSpec>> {
+        let block_root = get_block_root(&block);
+        self.process_block(block_root, block, notify_execution_layer, || Ok(()))
+            .await
+    }
+
+    /// Verifies that `header` was signed by the proposer expected at its slot, returning the index
+    /// of that proposer.
+    ///
+    /// This is much cheaper than `GossipVerifiedBlock::new` since the body of the block is not
+    /// required, which is useful for checking that a block root corresponds to a validly-signed
+    /// proposal (e.g., before attesting to it). It is **not** a substitute for gossip verification:
+    /// only the proposer and their signature are checked. The parent of the block must be known to
+    /// fork choice, otherwise `BeaconChainError::MissingBeaconBlock` is returned.
+    ///
+    /// The proposer is read from the proposer cache where possible, otherwise the parent state is
+    /// loaded and advanced, so this may be long-running and should not run on the core executor.
+    pub fn verify_gossip_block_header_only(
+        &self,
+        header: &SignedBeaconBlockHeader,
+    ) -> Result<u64, BlockError<T::EthSpec>> {
+        verify_block_header_proposal(self, header)
+    }
+
     /// Accepts a fully-verified block and imports it into the chain without performing any
     /// additional verification.
     ///
@@ -2773,6 +3419,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self: Arc<Self>,
         execution_pending_block: ExecutionPendingBlock<T>,
     ) -> Result<Hash256, BlockError<T::EthSpec>> {
+        let execution_pending_block = execution_pending_block
+            .resolve_payload_verification()
+            .await?;
         let ExecutionPendingBlock {
             block,
             block_root,
@@ -2782,15 +3431,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             payload_verification_handle,
             parent_eth1_finalization_data,
             consensus_context,
+            skipped_slots: _,
+            intermediate_state_roots: _,
+            parent_execution_status: _,
+            pre_block_state: _,
+            deferred_fork_choice_updates: _,
+            fork_choice_attestations_applied: _,
+            attester_slashings_applied: _,
+            imported_optimistically,
         } = execution_pending_block;
 
         let PayloadVerificationOutcome {
             payload_verification_status,
             is_valid_merge_transition_block,
-        } = payload_verification_handle
-            .await
-            .map_err(BeaconChainError::TokioJoin)?
-            .ok_or(BeaconChainError::RuntimeShutdown)??;
+        } = payload_verification_handle.outcome().await?;
 
         // Log the PoS pandas if a merge transition just occurred.
         if is_valid_merge_transition_block {
@@ -2822,6 +3476,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             );
         }
 
+        if imported_optimistically {
+            debug!(
+                self.log,
+                "Importing block optimistically";
+                "block_root" => ?block_root,
+                "slot" => block.slot(),
+            );
+        }
+
         let chain = self.clone();
         let block_hash = self
             .spawn_blocking_handle(
@@ -3864,6 +4527,92 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Some(pre_state)
     }
 
+    /// Computes the proposer shuffling for `epoch` and inserts it into the
+    /// `beacon_proposer_cache`, so that the first gossip block of `epoch` does not need to load
+    /// and advance a state to determine its proposer.
+    ///
+    /// The `decision_block` is the block at `end_slot(epoch - 1)`, as described in the
+    /// `beacon_proposer_cache` module.
+    ///
+    /// Returns `Ok(true)` if the cache was primed. Returns `Ok(false)` if the shuffling was
+    /// already cached, or if the `decision_block` or its state is not yet available.
+    pub fn prime_proposer_shuffling(
+        &self,
+        decision_block: Hash256,
+        epoch: Epoch,
+    ) -> Result<bool, Error> {
+        if self
+            .beacon_proposer_cache
+            .lock()
+            .get_epoch::<T::EthSpec>(decision_block, epoch)
+            .is_some()
+        {
+            return Ok(false);
+        }
+
+        let decision_block_state_root = if let Some(block) = self
+            .canonical_head
+            .fork_choice_read_lock()
+            .get_block(&decision_block)
+        {
+            block.state_root
+        } else {
+            debug!(
+                self.log,
+                "Unable to prime proposer shuffling";
+                "reason" => "decision block unknown",
+                "decision_block" => ?decision_block,
+                "epoch" => epoch,
+            );
+            return Ok(false);
+        };
+
+        let target_slot = epoch.start_slot(T::EthSpec::slots_per_epoch());
+        let (state_root, mut state) = if let Some(state) = self.store.get_advanced_hot_state(
+            decision_block,
+            target_slot,
+            decision_block_state_root,
+        )? {
+            state
+        } else {
+            debug!(
+                self.log,
+                "Unable to prime proposer shuffling";
+                "reason" => "decision block state unavailable",
+                "decision_block" => ?decision_block,
+                "epoch" => epoch,
+            );
+            return Ok(false);
+        };
+
+        ensure_state_is_in_epoch(&mut state, state_root, epoch, &self.spec)?;
+
+        // Protect against a `decision_block` which does not determine the shuffling of `epoch`,
+        // otherwise we would poison the cache.
+        let decision_root = state.proposer_shuffling_decision_root(self.genesis_block_root)?;
+        if decision_root != decision_block {
+            return Err(Error::ProposerShufflingDecisionRootMismatch {
+                expected: decision_block,
+                actual: decision_root,
+                epoch,
+            });
+        }
+
+        let proposers = state.get_beacon_proposer_indices(&self.spec)?;
+        self.beacon_proposer_cache
+            .lock()
+            .insert(epoch, decision_block, proposers, state.fork())?;
+
+        debug!(
+            self.log,
+            "Primed proposer shuffling cache";
+            "decision_block" => ?decision_block,
+            "epoch" => epoch,
+        );
+
+        Ok(true)
+    }
+
     /// Get the proposer index and `prev_randao` value for a proposal at slot `proposal_slot`.
     ///
     /// The `proposer_head` may be the head block of `cached_head` or its parent. An error will
@@ -4749,6 +5498,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             );
         }
 
+        // Stop verifying any blocks which descend from the invalidated blocks.
+        self.cancel_irrelevant_payload_verifications();
+
         // Run fork choice since it's possible that the payload invalidation might result in a new
         // head.
         self.recompute_head_at_current_slot().await;
@@ -4800,6 +5552,38 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Cancel the in-flight payload verifications of blocks which can no longer be imported.
+    ///
+    /// A block is irrelevant if it is not later than the finalized slot or if its parent has an
+    /// invalid execution payload.
+    pub fn cancel_irrelevant_payload_verifications(&self) {
+        let fork_choice = self.canonical_head.fork_choice_read_lock();
+        let finalized_slot = fork_choice
+            .finalized_checkpoint()
+            .epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+
+        let cancelled = self
+            .pending_payload_verifications
+            .cancel_where(|_, pending| {
+                pending.slot <= finalized_slot
+                    || fork_choice
+                        .get_block_execution_status(&pending.parent_root)
+                        .map_or(false, |status| status.is_invalid())
+            });
+        drop(fork_choice);
+
+        for block_root in cancelled {
+            metrics::inc_counter(&metrics::BLOCK_PROCESSING_PAYLOAD_VERIFICATIONS_CANCELLED);
+            debug!(
+                self.log,
+                "Cancelling payload verification";
+                "reason" => "block is no longer relevant",
+                "block_root" => ?block_root,
+            );
+        }
+    }
+
     pub fn block_is_known_to_fork_choice(&self, root: &Hash256) -> bool {
         self.canonical_head
             .fork_choice_read_lock()
@@ -5274,6 +6058,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Returns the roots of all blocks in fork choice which were imported optimistically and are
+    /// still awaiting verification of their execution payloads (i.e., have
+    /// `ExecutionStatus::Optimistic`), in no particular order.
+    ///
+    /// Blocks which have since been found to be invalid are not included.
+    pub fn list_optimistic_blocks(&self) -> Vec<Hash256> {
+        self.canonical_head
+            .fork_choice_read_lock()
+            .proto_array()
+            .core_proto_array()
+            .nodes
+            .iter()
+            .filter(|node| node.execution_status.is_strictly_optimistic())
+            .map(|node| node.root)
+            .collect()
+    }
+
     /// Returns the value of `execution_optimistic` for `block`.
     ///
     /// Returns `Ok(false)` if the block is pre-Bellatrix, or has `ExecutionStatus::Valid`.
@@ -5975,6 +6776,9 @@ impl<T: EthSpec> ChainSegmentResult<T> {
     pub fn into_block_error(self) -> Result<(), BlockError<T>> {
         match self {
             ChainSegmentResult::Failed { error, .. } => Err(error),
+            ChainSegmentResult::Cancelled { .. } => {
+                Err(BlockError::BeaconChainError(Error::ChainSegmentCancelled))
+            }
             ChainSegmentResult::Successful { .. } => Ok(()),
         }
     }
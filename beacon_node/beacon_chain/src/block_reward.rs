@@ -1,13 +1,54 @@
-use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes, ExecutionPendingBlock};
 use eth2::lighthouse::{AttestationRewards, BlockReward, BlockRewardMeta};
 use operation_pool::{AttMaxCover, MaxCover, RewardCache, SplitAttestation};
 use state_processing::{
     common::get_attesting_indices_from_state,
     per_block_processing::altair::sync_committee::compute_sync_aggregate_rewards,
+    state_advance::complete_state_advance,
 };
 use types::{AbstractExecPayload, BeaconBlockRef, BeaconState, EthSpec, Hash256};
 
 impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Computes the reward for a verified (but not necessarily imported) block, regardless of
+    /// whether there are any subscribers to block reward events.
+    ///
+    /// The reward is computed against `ExecutionPendingBlock::pre_block_state`, exactly as it is
+    /// for block reward events during verification. If the pre-block state was not retained (see
+    /// `ChainConfig::retain_pre_block_state`) it is reconstructed by loading the parent state from
+    /// the database and advancing it to the slot of the block, which is considerably slower.
+    pub fn compute_execution_pending_block_reward(
+        &self,
+        execution_pending_block: &ExecutionPendingBlock<T>,
+    ) -> Result<BlockReward, BeaconChainError> {
+        let mut state = if let Some(state) = &execution_pending_block.pre_block_state {
+            state.clone()
+        } else {
+            let parent_block = &execution_pending_block.parent_block;
+            let parent_state_root = parent_block.state_root();
+            let mut state = self
+                .get_state(&parent_state_root, Some(parent_block.slot()))?
+                .ok_or(BeaconChainError::MissingBeaconState(parent_state_root))?;
+            complete_state_advance(
+                &mut state,
+                Some(parent_state_root),
+                execution_pending_block.block.slot(),
+                &self.spec,
+            )?;
+            state
+        };
+        // The caches are not retained on the pre-block state.
+        state.build_caches(&self.spec)?;
+
+        let mut reward_cache = Default::default();
+        self.compute_block_reward(
+            execution_pending_block.block.message(),
+            execution_pending_block.block_root,
+            &state,
+            &mut reward_cache,
+            true,
+        )
+    }
+
     pub fn compute_block_reward<Payload: AbstractExecPayload<T::EthSpec>>(
         &self,
         block: BeaconBlockRef<'_, T::EthSpec, Payload>,
@@ -47,12 +47,15 @@
 // returned alongside.
 #![allow(clippy::result_large_err)]
 
+use crate::applied_attestations::AppliedAttestations;
+use crate::committee_state_cache::CommitteeStateCache;
 use crate::eth1_finalization_cache::Eth1FinalizationData;
 use crate::execution_payload::{
     is_optimistic_candidate_block, validate_execution_payload_for_gossip, validate_merge_block,
-    AllowOptimisticImport, NotifyExecutionLayer, PayloadNotifier,
+    AllowOptimisticImport, KnownPayloadStatuses, NotifyExecutionLayer, PayloadNotifier,
 };
 use crate::observed_block_producers::SeenBlock;
+use crate::rejected_block_cache::CachedBlockRejection;
 use crate::snapshot_cache::PreProcessingSnapshot;
 use crate::validator_monitor::HISTORIC_EPOCHS as VALIDATOR_MONITOR_HISTORIC_EPOCHS;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
@@ -64,36 +67,45 @@ use crate::{
     metrics, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use derivative::Derivative;
-use eth2::types::EventKind;
-use execution_layer::PayloadStatus;
+use eth2::lighthouse::BlockReward;
+use eth2::types::{EventKind, SseBlockEquivocation, SseOptimisticBlockRejected};
+use execution_layer::{engines::EngineError, PayloadStatus};
 use fork_choice::{AttestationFromBlock, PayloadVerificationStatus};
-use parking_lot::RwLockReadGuard;
-use proto_array::Block as ProtoBlock;
+use futures::future::{self, BoxFuture};
+use parking_lot::{Mutex, RwLockReadGuard};
+use proto_array::{Block as ProtoBlock, ExecutionStatus};
 use safe_arith::ArithError;
 use slog::{debug, error, warn, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
-use state_processing::per_block_processing::{errors::IntoWithIndex, is_merge_transition_block};
+use state_processing::per_block_processing::{
+    errors::IntoWithIndex, is_merge_transition_block, verify_deposit_signature,
+};
 use state_processing::{
-    block_signature_verifier::{BlockSignatureVerifier, Error as BlockSignatureVerifierError},
+    block_signature_verifier::{
+        BlockSignatureVerifier, Error as BlockSignatureVerifierError, ParallelSignatureSets,
+    },
     per_block_processing, per_slot_processing,
     state_advance::partial_state_advance,
     BlockProcessingError, BlockSignatureStrategy, ConsensusContext, SlotProcessingError,
     StateProcessingStrategy, VerifyBlockRoot,
 };
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fs;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use store::{Error as DBError, HotStateSummary, KeyValueStore, StoreOp};
 use task_executor::JoinHandle;
 use tree_hash::TreeHash;
 use types::ExecPayload;
 use types::{
-    BeaconBlockRef, BeaconState, BeaconStateError, BlindedPayload, ChainSpec, CloneConfig, Epoch,
-    EthSpec, ExecutionBlockHash, Hash256, InconsistentFork, PublicKey, PublicKeyBytes,
-    RelativeEpoch, SignedBeaconBlock, SignedBeaconBlockHeader, Slot,
+    AbstractExecPayload, AttesterSlashing, BeaconBlockRef, BeaconState, BeaconStateError,
+    BlindedPayload, ChainSpec, Checkpoint, CloneConfig, Epoch, EthSpec, ExecutionBlockHash, Fork,
+    Hash256, InconsistentFork, IndexedAttestation, PublicKey, PublicKeyBytes, RelativeEpoch,
+    SignedBeaconBlock, SignedBeaconBlockHeader, Slot,
 };
 
 pub const POS_PANDA_BANNER: &str = r#"
@@ -124,7 +136,8 @@ pub const POS_PANDA_BANNER: &str = r#"
 const MAXIMUM_BLOCK_SLOT_NUMBER: u64 = 4_294_967_296; // 2^32
 
 /// If true, everytime a block is processed the pre-state, post-state and block are written to SSZ
-/// files in the temp directory.
+/// files in the temp directory. Writing can also be enabled at runtime, using a specific directory,
+/// via `ChainConfig::block_processing_ssz_dir`.
 ///
 /// Only useful for testing.
 const WRITE_BLOCK_PROCESSING_SSZ: bool = cfg!(feature = "write_ssz_files");
@@ -144,12 +157,16 @@ pub enum BlockError<T: EthSpec> {
     ParentUnknown(Arc<SignedBeaconBlock<T>>),
     /// The block slot is greater than the present slot.
     ///
+    /// The `present_slot` is determined after allowing for the clock disparity `tolerance`. See
+    /// `ChainConfig::future_block_tolerance` for details on how the tolerance is chosen.
+    ///
     /// ## Peer scoring
     ///
     /// Assuming the local clock is correct, the peer has sent an invalid message.
     FutureSlot {
         present_slot: Slot,
         block_slot: Slot,
+        tolerance: Duration,
     },
     /// The block state_root does not match the generated state.
     ///
@@ -169,6 +186,16 @@ pub enum BlockError<T: EthSpec> {
         block_slot: Slot,
         finalized_slot: Slot,
     },
+    /// The block is the finalized block: it is at the first slot of the finalized epoch and its
+    /// root is the finalized root. No need to import.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The block is valid and is already known to us, it is not an attempt to revert finality.
+    BlockAtFinalizedSlot {
+        block_root: Hash256,
+        block_slot: Slot,
+    },
     /// The block conflicts with finalization, no need to propagate.
     ///
     /// ## Peer scoring
@@ -197,6 +224,21 @@ pub enum BlockError<T: EthSpec> {
     ///
     /// The block is invalid and the peer is faulty.
     IncorrectBlockProposer { block: u64, local_shuffling: u64 },
+    /// The block was proposed by a validator which is rejected by the `proposer_filter` of the
+    /// `BeaconChain`.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The block may be valid, we are refusing it due to local policy. The peer is not faulty.
+    ProposerFiltered { proposer_index: u64 },
+    /// The proposer of the block has exceeded the number of distinct blocks permitted by the
+    /// `gossip_proposer_rate_limit` of the `ChainConfig`.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The block may be valid, however an honest proposer will not exceed any sensible limit. The
+    /// peer is penalized slightly, since it is forwarding spam.
+    ProposerRateLimited { proposer_index: u64 },
     /// The proposal signature in invalid.
     ///
     /// ## Peer scoring
@@ -209,12 +251,15 @@ pub enum BlockError<T: EthSpec> {
     ///
     /// The block is invalid and the peer is faulty.
     UnknownValidator(u64),
-    /// A signature in the block is invalid (exactly which is unknown).
+    /// A signature in the block is invalid.
+    ///
+    /// The `kind` is determined by re-verifying each kind of signature individually after batch
+    /// verification has failed.
     ///
     /// ## Peer scoring
     ///
     /// The block is invalid and the peer is faulty.
-    InvalidSignature,
+    InvalidSignature { kind: SignatureKind },
     /// The provided block is from an later slot than its parent.
     ///
     /// ## Peer scoring
@@ -277,6 +322,62 @@ pub enum BlockError<T: EthSpec> {
     /// problems to worry about than losing peers, and we're doing the network a favour by
     /// disconnecting.
     ParentExecutionPayloadInvalid { parent_root: Hash256 },
+    /// The parent block is post-merge, however its execution payload is not reflected in the state
+    /// that was loaded for it.
+    ///
+    /// The parent block is only ever loaded in its blinded form, which is sufficient since the
+    /// block's payload is verified against the `latest_execution_payload_header` of the parent's
+    /// state. If that header is missing (e.g., the state was reconstructed without access to the
+    /// parent's payload) then the block cannot be verified.
+    ///
+    /// ## Peer scoring
+    ///
+    /// This is an issue with our local database, the peer is not necessarily faulty.
+    ParentPayloadUnavailable { parent_root: Hash256 },
+    /// An error occurred whilst advancing the parent state through `slot` in order to reach the
+    /// slot of the block with `block_root`.
+    ///
+    /// ## Peer scoring
+    ///
+    /// This is an internal error, the peer is not necessarily faulty.
+    SlotProcessingFailed {
+        slot: Slot,
+        block_root: Hash256,
+        source: SlotProcessingError,
+    },
+    /// The state used to obtain the committees for the block had already been advanced beyond the
+    /// slot of the block.
+    ///
+    /// This is distinct from `Self::BlockIsNotLaterThanParent` since the parent block may well be
+    /// earlier than the block.
+    ///
+    /// ## Peer scoring
+    ///
+    /// This is an internal error, the peer is not necessarily faulty.
+    PreStateSlotTooHigh { block_slot: Slot, state_slot: Slot },
+    /// The number of slots skipped between the block and its parent exceeds
+    /// `ChainConfig::block_max_skip_slots`. Such a block would require an excessive amount of work
+    /// to advance the parent state.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The block is not necessarily invalid, but we refuse to process it. The peer is not
+    /// penalized.
+    TooManySkipSlots {
+        block_slot: Slot,
+        parent_slot: Slot,
+        max: u64,
+    },
+    /// A block with `block_root` was recently rejected during gossip verification, it was refused
+    /// again without being re-verified.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer is penalized as for the original error, see `CachedBlockRejection`.
+    RecentlyRejected {
+        block_root: Hash256,
+        rejection: CachedBlockRejection,
+    },
     /// The block is a slashable equivocation from the proposer.
     ///
     /// ## Peer scoring
@@ -286,6 +387,39 @@ pub enum BlockError<T: EthSpec> {
     Slashable,
 }
 
+/// The kind of signature which caused a block to fail signature verification.
+///
+/// Deposit signatures are not included, since a block containing a deposit with an invalid
+/// signature is still valid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignatureKind {
+    BlockProposal,
+    RandaoReveal,
+    ProposerSlashing,
+    AttesterSlashing,
+    Attestation,
+    VoluntaryExit,
+    SyncAggregate,
+    BlsToExecutionChange,
+    /// Batch verification failed, but each kind of signature was valid when verified on its own.
+    Unknown,
+}
+
+impl SignatureKind {
+    /// All kinds of signature which are included in batch verification, in the order in which
+    /// they are re-verified.
+    const ALL: [SignatureKind; 8] = [
+        SignatureKind::BlockProposal,
+        SignatureKind::RandaoReveal,
+        SignatureKind::ProposerSlashing,
+        SignatureKind::AttesterSlashing,
+        SignatureKind::Attestation,
+        SignatureKind::VoluntaryExit,
+        SignatureKind::SyncAggregate,
+        SignatureKind::BlsToExecutionChange,
+    ];
+}
+
 /// Returned when block validation failed due to some issue verifying
 /// the execution payload.
 #[derive(Debug)]
@@ -308,6 +442,16 @@ pub enum ExecutionPayloadError {
     ///
     /// The block is invalid and the peer is faulty
     RejectedByExecutionEngine { status: PayloadStatus },
+    /// The execution engine returned INVALID_BLOCK_HASH for the payload.
+    ///
+    /// The block hash computed by the execution engine did not match the block hash of the
+    /// payload. Unlike `Self::RejectedByExecutionEngine`, this is often due to a bug in the
+    /// execution engine rather than an invalid block.
+    ///
+    /// ## Peer scoring
+    ///
+    /// As per `Self::RejectedByExecutionEngine`.
+    BlockHashRejectedByExecutionEngine { status: PayloadStatus },
     /// The execution payload timestamp does not match the slot
     ///
     /// ## Peer scoring
@@ -348,6 +492,28 @@ pub enum ExecutionPayloadError {
     ///
     /// The peer is not necessarily invalid.
     UnverifiedNonOptimisticCandidate,
+    /// The block became irrelevant (e.g., it conflicts with finality or its parent was
+    /// invalidated) whilst the execution engine was verifying its payload.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The block was relevant when the peer sent it, do not penalize them.
+    VerificationCancelled,
+    /// The block is the merge transition block, which cannot be verified because the execution
+    /// layer is absent or offline. Unlike other blocks, the transition block is never imported
+    /// without the execution layer checking its terminal PoW block.
+    ///
+    /// ## Peer scoring
+    ///
+    /// As this is our fault, do not penalize the peer.
+    MergeTransitionExecutionLayerOffline,
+    /// The caller supplied `PayloadVerificationStatus::Irrelevant` for a payload which is subject
+    /// to execution verification.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The status was supplied locally, do not penalize the peer.
+    IrrelevantKnownPayloadStatus { block_hash: ExecutionBlockHash },
 }
 
 impl ExecutionPayloadError {
@@ -363,6 +529,8 @@ impl ExecutionPayloadError {
             // An honest optimistic node may propagate blocks which are rejected by an EE, do not
             // penalize them.
             ExecutionPayloadError::RejectedByExecutionEngine { .. } => false,
+            // As per `Self::RejectedByExecutionEngine`.
+            ExecutionPayloadError::BlockHashRejectedByExecutionEngine { .. } => false,
             // This is a trivial gossip validation condition, there is no reason for an honest peer
             // to propagate a block with an invalid payload time stamp.
             ExecutionPayloadError::InvalidPayloadTimestamp { .. } => true,
@@ -378,6 +546,338 @@ impl ExecutionPayloadError {
             ExecutionPayloadError::InvalidTerminalBlockHash { .. } => false,
             // Do not penalize the peer since it's not their fault that *we're* optimistic.
             ExecutionPayloadError::UnverifiedNonOptimisticCandidate => false,
+            // The block became irrelevant after it was received.
+            ExecutionPayloadError::VerificationCancelled => false,
+            // The peer has nothing to do with this error, do not penalize them.
+            ExecutionPayloadError::MergeTransitionExecutionLayerOffline => false,
+            // The peer has nothing to do with this error, do not penalize them.
+            ExecutionPayloadError::IrrelevantKnownPayloadStatus { .. } => false,
+        }
+    }
+}
+
+/// Returns `true` if `error` indicates that the execution layer is absent or could not be
+/// reached, rather than that it was unable to verify a payload.
+fn is_execution_layer_connection_error<E: EthSpec>(error: &BlockError<E>) -> bool {
+    match error {
+        BlockError::ExecutionPayloadError(ExecutionPayloadError::NoExecutionConnection) => true,
+        BlockError::ExecutionPayloadError(ExecutionPayloadError::RequestFailed(
+            execution_layer::Error::EngineError(e),
+        )) => e.is_offline(),
+        _ => false,
+    }
+}
+
+/// As per `is_execution_layer_connection_error`, except that a failed request is also attributed
+/// to the execution layer being offline if the engine's last known state is offline.
+async fn is_execution_layer_offline_error<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    error: &BlockError<T::EthSpec>,
+) -> bool {
+    if is_execution_layer_connection_error(error) {
+        return true;
+    }
+    match (error, chain.execution_layer.as_ref()) {
+        (
+            BlockError::ExecutionPayloadError(ExecutionPayloadError::RequestFailed(
+                execution_layer::Error::EngineError(_),
+            )),
+            Some(execution_layer),
+        ) => execution_layer.is_offline().await,
+        _ => false,
+    }
+}
+
+/// A predicate on the proposer index of a block, returning `false` if blocks from that proposer
+/// should be refused.
+///
+/// See `BeaconChain::proposer_filter`.
+pub type ProposerFilter = Arc<dyn Fn(u64) -> bool + Send + Sync>;
+
+/// A callback which observes each block as it becomes an `ExecutionPendingBlock`.
+///
+/// The callback is run synchronously on the block verification path, so it must return quickly
+/// and must never block (e.g., on a lock held during import or on I/O). Expensive work should be
+/// sent to another thread. It is not run for dry runs or for blocks which fail verification.
+///
+/// See `BeaconChain::on_execution_pending`.
+pub type ExecutionPendingHook<T> = Arc<dyn Fn(&ExecutionPendingBlock<T>) + Send + Sync>;
+
+/// The penalty that should be applied to a peer which sent us a block that failed verification.
+///
+/// This mirrors `lighthouse_network::PeerAction`, which is not available here since this crate
+/// does not depend on the networking stack.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PeerPenalty {
+    Fatal,
+    LowToleranceError,
+    MidToleranceError,
+    HighToleranceError,
+}
+
+/// A coarse classification of a `BlockError`, suitable for choosing a response to the submitter
+/// of a block (e.g., an HTTP status code).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockRejectionClass {
+    /// The block is invalid.
+    Invalid,
+    /// We encountered an error whilst verifying the block, it may or may not be valid.
+    Internal,
+    /// The block has already been imported.
+    AlreadyKnown,
+    /// The block may be valid, but it cannot or should not be imported (e.g., it conflicts with
+    /// finality or was refused due to local policy).
+    NotRelevant,
+}
+
+impl BlockRejectionClass {
+    /// Returns the HTTP status code which is conventionally returned for this class of rejection.
+    pub fn http_status_hint(&self) -> u16 {
+        match self {
+            BlockRejectionClass::Invalid | BlockRejectionClass::NotRelevant => 400,
+            BlockRejectionClass::Internal => 500,
+            BlockRejectionClass::AlreadyKnown => 202,
+        }
+    }
+}
+
+impl<T: EthSpec> BlockError<T> {
+    /// Returns the penalty that should be applied to the peer which sent us a block that produced
+    /// this error, or `None` if the peer should not be penalized.
+    pub fn penalize_peer(&self) -> Option<PeerPenalty> {
+        // This match statement should never have a default case so that we are
+        // always forced to consider here whether or not to penalize a peer when
+        // we add a new error condition.
+        match self {
+            // Blocks should be sequential and all parents should exist, peers are faulty if they
+            // send non-sequential blocks.
+            BlockError::ParentUnknown(_) => Some(PeerPenalty::LowToleranceError),
+            // Peers are faulty if they send blocks from the future.
+            BlockError::FutureSlot { .. } => Some(PeerPenalty::LowToleranceError),
+            // An honest peer will have verified the signatures of a block before sending it to us,
+            // and an invalid signature can't be caused by a fault on our side.
+            BlockError::ProposalSignatureInvalid => Some(PeerPenalty::LowToleranceError),
+            BlockError::InvalidSignature { .. } => Some(PeerPenalty::LowToleranceError),
+            // Responses to range and parent lookup requests must form a single chain, an honest
+            // peer will never send blocks which do not.
+            BlockError::NonLinearParentRoots => Some(PeerPenalty::LowToleranceError),
+            BlockError::NonLinearSlots => Some(PeerPenalty::LowToleranceError),
+            // The following conditions indicate an invalid block. We presently don't penalize
+            // peers for them and instead rely on the block being re-requested from another peer.
+            BlockError::StateRootMismatch { .. } => None,
+            BlockError::BlockSlotLimitReached => None,
+            BlockError::IncorrectBlockProposer { .. } => None,
+            BlockError::UnknownValidator(_) => None,
+            BlockError::BlockIsNotLaterThanParent { .. } => None,
+            BlockError::PerBlockProcessingError(_) => None,
+            BlockError::WeakSubjectivityConflict => None,
+            BlockError::InconsistentFork(_) => None,
+            BlockError::Slashable => None,
+            // The block was refused due to local policy, the peer has done nothing wrong.
+            BlockError::ProposerFiltered { .. } => None,
+            // Honest peers may forward a spammy proposer's blocks, so don't penalize too harshly.
+            BlockError::ProposerRateLimited { .. } => Some(PeerPenalty::MidToleranceError),
+            // The genesis block is known to everyone, there's no harm in a peer sending it.
+            BlockError::GenesisBlock => None,
+            // Finalized and already-known blocks are commonly downloaded during sync (e.g., by
+            // head syncs or parent lookups racing range sync), they are not a sign of a faulty
+            // peer.
+            BlockError::WouldRevertFinalizedSlot { .. } => None,
+            BlockError::BlockAtFinalizedSlot { .. } => None,
+            BlockError::BlockIsAlreadyKnown => None,
+            // The block conflicts with finality, but an honest peer may still be following a
+            // non-finalized fork. Gossip blocks are classified by their age instead, see
+            // `Self::penalize_gossip_peer`.
+            BlockError::NotFinalizedDescendant { .. } => None,
+            // Do not penalize peers for internal errors.
+            BlockError::BeaconChainError(_) => None,
+            // Defer to the scoring rules for the execution payload error. Errors which do not
+            // penalize the peer indicate an issue with our execution layer.
+            BlockError::ExecutionPayloadError(e) => {
+                if e.penalize_peer() {
+                    Some(PeerPenalty::LowToleranceError)
+                } else {
+                    None
+                }
+            }
+            // We need to penalise harshly in case this represents an actual attack. In case of a
+            // faulty EL it will usually require manual intervention to fix anyway, so it's not too
+            // bad if we drop most of our peers.
+            BlockError::ParentExecutionPayloadInvalid { .. } => {
+                Some(PeerPenalty::LowToleranceError)
+            }
+            // Our database is missing data, this is not the peer's fault.
+            BlockError::ParentPayloadUnavailable { .. } => None,
+            // Failing to advance our own state is not the peer's fault.
+            BlockError::SlotProcessingFailed { .. } => None,
+            // We provided the state, not the peer.
+            BlockError::PreStateSlotTooHigh { .. } => None,
+            // The block may be valid, the limit is a local DoS protection.
+            BlockError::TooManySkipSlots { .. } => None,
+            // The original error has already been scored.
+            BlockError::RecentlyRejected { rejection, .. } => rejection.penalty,
+        }
+    }
+
+    /// Returns the class of this rejection, see `BlockRejectionClass`.
+    pub fn rejection_class(&self) -> BlockRejectionClass {
+        // This match statement should never have a default case so that every new error condition
+        // is explicitly classified.
+        match self {
+            BlockError::FutureSlot { .. }
+            | BlockError::StateRootMismatch { .. }
+            | BlockError::BlockSlotLimitReached
+            | BlockError::IncorrectBlockProposer { .. }
+            | BlockError::ProposalSignatureInvalid
+            | BlockError::UnknownValidator(_)
+            | BlockError::InvalidSignature { .. }
+            | BlockError::BlockIsNotLaterThanParent { .. }
+            | BlockError::NonLinearParentRoots
+            | BlockError::NonLinearSlots
+            | BlockError::PerBlockProcessingError(_)
+            | BlockError::InconsistentFork(_)
+            | BlockError::ParentExecutionPayloadInvalid { .. }
+            | BlockError::Slashable => BlockRejectionClass::Invalid,
+            BlockError::ParentUnknown(_)
+            | BlockError::GenesisBlock
+            | BlockError::WouldRevertFinalizedSlot { .. }
+            | BlockError::NotFinalizedDescendant { .. }
+            | BlockError::WeakSubjectivityConflict
+            | BlockError::ProposerFiltered { .. }
+            | BlockError::ProposerRateLimited { .. }
+            | BlockError::TooManySkipSlots { .. } => BlockRejectionClass::NotRelevant,
+            BlockError::BlockIsAlreadyKnown | BlockError::BlockAtFinalizedSlot { .. } => {
+                BlockRejectionClass::AlreadyKnown
+            }
+            BlockError::BeaconChainError(_)
+            | BlockError::ParentPayloadUnavailable { .. }
+            | BlockError::SlotProcessingFailed { .. }
+            | BlockError::PreStateSlotTooHigh { .. } => BlockRejectionClass::Internal,
+            // Payload errors which would penalize a peer indicate an invalid payload, the
+            // remainder indicate an issue with our execution layer.
+            BlockError::ExecutionPayloadError(e) => {
+                if e.penalize_peer() {
+                    BlockRejectionClass::Invalid
+                } else {
+                    BlockRejectionClass::Internal
+                }
+            }
+            BlockError::RecentlyRejected { rejection, .. } => rejection.class,
+        }
+    }
+
+    /// As per `Self::penalize_peer`, but for a block at `block_slot` which was received via gossip
+    /// at `current_slot`.
+    ///
+    /// A `NotFinalizedDescendant` block within `grace_slots` of the `current_slot` may be a valid
+    /// competing block which was sent to us as finalization advanced, so the peer is not
+    /// penalized. Older blocks which conflict with finality are penalized slightly, to discourage
+    /// peers from repeatedly sending them.
+    pub fn penalize_gossip_peer(
+        &self,
+        block_slot: Slot,
+        current_slot: Slot,
+        grace_slots: u64,
+    ) -> Option<PeerPenalty> {
+        match self {
+            BlockError::NotFinalizedDescendant { .. } => {
+                if block_slot.saturating_add(grace_slots) >= current_slot {
+                    None
+                } else {
+                    Some(PeerPenalty::HighToleranceError)
+                }
+            }
+            _ => self.penalize_peer(),
+        }
+    }
+
+    /// Returns the HTTP status code which is conventionally returned for this error.
+    ///
+    /// See `BlockRejectionClass::http_status_hint`.
+    pub fn http_status_hint(&self) -> u16 {
+        self.rejection_class().http_status_hint()
+    }
+
+    /// Returns a short, stable identifier for this error which is suitable for inclusion in API
+    /// responses.
+    ///
+    /// Unlike the `Debug` and `Display` representations, these values will not change when the
+    /// contents of an error change, so clients may safely branch upon them.
+    pub fn as_static_str(&self) -> &'static str {
+        // This match statement should never have a default case so that every new error condition
+        // is given its own identifier.
+        match self {
+            BlockError::ParentUnknown(_) => "parent_unknown",
+            BlockError::FutureSlot { .. } => "future_slot",
+            BlockError::StateRootMismatch { .. } => "state_root_mismatch",
+            BlockError::GenesisBlock => "genesis_block",
+            BlockError::WouldRevertFinalizedSlot { .. } => "would_revert_finalized_slot",
+            BlockError::BlockAtFinalizedSlot { .. } => "block_at_finalized_slot",
+            BlockError::NotFinalizedDescendant { .. } => "not_finalized_descendant",
+            BlockError::BlockIsAlreadyKnown => "block_is_already_known",
+            BlockError::BlockSlotLimitReached => "block_slot_limit_reached",
+            BlockError::IncorrectBlockProposer { .. } => "incorrect_block_proposer",
+            BlockError::ProposerFiltered { .. } => "proposer_filtered",
+            BlockError::ProposerRateLimited { .. } => "proposer_rate_limited",
+            BlockError::ProposalSignatureInvalid => "proposal_signature_invalid",
+            BlockError::UnknownValidator(_) => "unknown_validator",
+            BlockError::InvalidSignature { .. } => "invalid_signature",
+            BlockError::BlockIsNotLaterThanParent { .. } => "block_is_not_later_than_parent",
+            BlockError::NonLinearParentRoots => "non_linear_parent_roots",
+            BlockError::NonLinearSlots => "non_linear_slots",
+            BlockError::PerBlockProcessingError(_) => "per_block_processing_error",
+            BlockError::BeaconChainError(_) => "beacon_chain_error",
+            BlockError::WeakSubjectivityConflict => "weak_subjectivity_conflict",
+            BlockError::InconsistentFork(_) => "inconsistent_fork",
+            BlockError::ExecutionPayloadError(_) => "execution_payload_error",
+            BlockError::ParentExecutionPayloadInvalid { .. } => "parent_execution_payload_invalid",
+            BlockError::ParentPayloadUnavailable { .. } => "parent_payload_unavailable",
+            BlockError::SlotProcessingFailed { .. } => "slot_processing_failed",
+            BlockError::PreStateSlotTooHigh { .. } => "pre_state_slot_too_high",
+            BlockError::TooManySkipSlots { .. } => "too_many_skip_slots",
+            BlockError::RecentlyRejected { .. } => "recently_rejected",
+            BlockError::Slashable => "slashable",
+        }
+    }
+
+    /// Returns the name of this error's variant, e.g. `"ParentUnknown"`.
+    ///
+    /// This is intended for labelling metrics. Prefer `Self::as_static_str` for anything which is
+    /// exposed via the API.
+    pub fn variant_name(&self) -> &'static str {
+        // This match statement should never have a default case so that every new variant is
+        // given its own label.
+        match self {
+            BlockError::ParentUnknown(_) => "ParentUnknown",
+            BlockError::FutureSlot { .. } => "FutureSlot",
+            BlockError::StateRootMismatch { .. } => "StateRootMismatch",
+            BlockError::GenesisBlock => "GenesisBlock",
+            BlockError::WouldRevertFinalizedSlot { .. } => "WouldRevertFinalizedSlot",
+            BlockError::BlockAtFinalizedSlot { .. } => "BlockAtFinalizedSlot",
+            BlockError::NotFinalizedDescendant { .. } => "NotFinalizedDescendant",
+            BlockError::BlockIsAlreadyKnown => "BlockIsAlreadyKnown",
+            BlockError::BlockSlotLimitReached => "BlockSlotLimitReached",
+            BlockError::IncorrectBlockProposer { .. } => "IncorrectBlockProposer",
+            BlockError::ProposerFiltered { .. } => "ProposerFiltered",
+            BlockError::ProposerRateLimited { .. } => "ProposerRateLimited",
+            BlockError::ProposalSignatureInvalid => "ProposalSignatureInvalid",
+            BlockError::UnknownValidator(_) => "UnknownValidator",
+            BlockError::InvalidSignature { .. } => "InvalidSignature",
+            BlockError::BlockIsNotLaterThanParent { .. } => "BlockIsNotLaterThanParent",
+            BlockError::NonLinearParentRoots => "NonLinearParentRoots",
+            BlockError::NonLinearSlots => "NonLinearSlots",
+            BlockError::PerBlockProcessingError(_) => "PerBlockProcessingError",
+            BlockError::BeaconChainError(_) => "BeaconChainError",
+            BlockError::WeakSubjectivityConflict => "WeakSubjectivityConflict",
+            BlockError::InconsistentFork(_) => "InconsistentFork",
+            BlockError::ExecutionPayloadError(_) => "ExecutionPayloadError",
+            BlockError::ParentExecutionPayloadInvalid { .. } => "ParentExecutionPayloadInvalid",
+            BlockError::ParentPayloadUnavailable { .. } => "ParentPayloadUnavailable",
+            BlockError::SlotProcessingFailed { .. } => "SlotProcessingFailed",
+            BlockError::PreStateSlotTooHigh { .. } => "PreStateSlotTooHigh",
+            BlockError::TooManySkipSlots { .. } => "TooManySkipSlots",
+            BlockError::RecentlyRejected { .. } => "RecentlyRejected",
+            BlockError::Slashable => "Slashable",
         }
     }
 }
@@ -406,6 +906,15 @@ impl<T: EthSpec> std::fmt::Display for BlockError<T> {
             BlockError::ParentUnknown(block) => {
                 write!(f, "ParentUnknown(parent_root:{})", block.parent_root())
             }
+            BlockError::InconsistentFork(InconsistentFork {
+                fork_at_slot,
+                object_fork,
+            }) => write!(
+                f,
+                "InconsistentFork(block structure is for fork {} but the spec expects fork {} at \
+                 the block's slot)",
+                object_fork, fork_at_slot
+            ),
             other => write!(f, "{:?}", other),
         }
     }
@@ -466,22 +975,25 @@ pub struct PayloadVerificationOutcome {
 
 /// Information about invalid blocks which might still be slashable despite being invalid.
 #[allow(clippy::enum_variant_names)]
-pub enum BlockSlashInfo<TErr> {
+pub enum BlockSlashInfo<E: EthSpec> {
     /// The block is invalid, but its proposer signature wasn't checked.
-    SignatureNotChecked(SignedBeaconBlockHeader, TErr),
+    ///
+    /// The whole block is retained so that its proposer signature can be checked against the
+    /// fork of its parent state.
+    SignatureNotChecked(Arc<SignedBeaconBlock<E>>, BlockError<E>),
     /// The block's proposer signature is invalid, so it will never be slashable.
-    SignatureInvalid(TErr),
+    SignatureInvalid(BlockError<E>),
     /// The signature is valid but the attestation is invalid in some other way.
-    SignatureValid(SignedBeaconBlockHeader, TErr),
+    SignatureValid(SignedBeaconBlockHeader, BlockError<E>),
 }
 
-impl<E: EthSpec> BlockSlashInfo<BlockError<E>> {
-    pub fn from_early_error(header: SignedBeaconBlockHeader, e: BlockError<E>) -> Self {
+impl<E: EthSpec> BlockSlashInfo<E> {
+    pub fn from_early_error(block: Arc<SignedBeaconBlock<E>>, e: BlockError<E>) -> Self {
         match e {
             BlockError::ProposalSignatureInvalid => BlockSlashInfo::SignatureInvalid(e),
             // `InvalidSignature` could indicate any signature in the block, so we want
             // to recheck the proposer signature alone.
-            _ => BlockSlashInfo::SignatureNotChecked(header, e),
+            _ => BlockSlashInfo::SignatureNotChecked(block, e),
         }
     }
 }
@@ -491,13 +1003,13 @@ impl<E: EthSpec> BlockSlashInfo<BlockError<E>> {
 /// If no slasher is configured, this is a no-op.
 fn process_block_slash_info<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
-    slash_info: BlockSlashInfo<BlockError<T::EthSpec>>,
+    slash_info: BlockSlashInfo<T::EthSpec>,
 ) -> BlockError<T::EthSpec> {
     if let Some(slasher) = chain.slasher.as_ref() {
         let (verified_header, error) = match slash_info {
-            BlockSlashInfo::SignatureNotChecked(header, e) => {
-                if verify_header_signature(chain, &header).is_ok() {
-                    (header, e)
+            BlockSlashInfo::SignatureNotChecked(block, e) => {
+                if verify_slashable_block_signature(chain, &block) {
+                    (block.signed_block_header(), e)
                 } else {
                     return e;
                 }
@@ -517,11 +1029,156 @@ fn process_block_slash_info<T: BeaconChainTypes>(
     }
 }
 
+/// Returns `true` if the proposer signature of the invalid `block` is valid, so that it should be
+/// supplied to the slasher.
+///
+/// The signature is checked against the fork of the parent state, as per
+/// `verify_proposal_signature_only`. The parent is cloned from the snapshot cache so that the
+/// check has no side-effects. If the parent cannot be loaded (e.g., because it is unknown) the
+/// signature is checked against the fork at the head instead.
+fn verify_slashable_block_signature<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &Arc<SignedBeaconBlock<T::EthSpec>>,
+) -> bool {
+    let block_root = get_block_root(block);
+    verify_proposal_signature_only(
+        chain,
+        block,
+        block_root,
+        &mut None,
+        SnapshotCacheAccess::for_chain(chain, true),
+    )
+    .unwrap_or_else(|_| verify_header_signature(chain, &block.signed_block_header()).is_ok())
+}
+
+/// Verify that each block in `chain_segment` is the parent of the next, and that their slots are
+/// strictly increasing.
+///
+/// This is a cheap check which can be used to reject a chain segment before verifying signatures.
+/// It does not consult the database, so the parent of the first block is not checked.
+pub fn validate_chain_segment_linearity<E: EthSpec>(
+    chain_segment: &[Arc<SignedBeaconBlock<E>>],
+) -> Result<(), BlockError<E>> {
+    check_chain_segment_linearity(
+        chain_segment
+            .iter()
+            .map(|block| (get_block_root(block), block.as_ref())),
+    )
+}
+
+/// As per `validate_chain_segment_linearity`, but for a `chain_segment` whose block roots have
+/// already been computed.
+pub(crate) fn check_chain_segment_linearity<'a, E: EthSpec>(
+    chain_segment: impl IntoIterator<Item = (Hash256, &'a SignedBeaconBlock<E>)>,
+) -> Result<(), BlockError<E>> {
+    let mut parent: Option<(Hash256, Slot)> = None;
+
+    for (block_root, block) in chain_segment {
+        if let Some((parent_root, parent_slot)) = parent {
+            if block.parent_root() != parent_root {
+                return Err(BlockError::NonLinearParentRoots);
+            }
+
+            if block.slot() <= parent_slot {
+                return Err(BlockError::NonLinearSlots);
+            }
+        }
+
+        parent = Some((block_root, block.slot()));
+    }
+
+    Ok(())
+}
+
+/// Prepares `blocks` (e.g., as received from peers) for `signature_verify_chain_segment`.
+///
+/// The root of each block is computed, any duplicate blocks are removed and the remaining blocks
+/// are sorted by slot. The result must then be linear, as per `validate_chain_segment_linearity`,
+/// otherwise `BlockError::NonLinearParentRoots` or `BlockError::NonLinearSlots` is returned. In
+/// particular, blocks are never reordered to repair their parent root linkage and a missing block
+/// is an error.
+pub fn prepare_chain_segment<E: EthSpec>(
+    blocks: Vec<Arc<SignedBeaconBlock<E>>>,
+) -> Result<Vec<(Hash256, Arc<SignedBeaconBlock<E>>)>, BlockError<E>> {
+    let mut seen_roots = HashSet::with_capacity(blocks.len());
+    let mut chain_segment = blocks
+        .into_iter()
+        .map(|block| (get_block_root(&block), block))
+        .filter(|(block_root, _)| seen_roots.insert(*block_root))
+        .collect::<Vec<_>>();
+    chain_segment.sort_by_key(|(_, block)| block.slot());
+
+    check_chain_segment_linearity(
+        chain_segment
+            .iter()
+            .map(|(block_root, block)| (*block_root, block.as_ref())),
+    )?;
+
+    Ok(chain_segment)
+}
+
+/// Verify the proposer signature of each of the `headers`, returning an error for the first header
+/// with an invalid signature.
+///
+/// This is a cheap check which can be used to reject a chain segment containing a forged header
+/// before running per-block processing or payload verification. It does not verify any other
+/// signatures, nor does it check that each header is from the correct proposer.
+///
+/// Each signature is verified against the fork at the head. If that fails, the signature is
+/// verified against the fork which the spec schedules for the header's epoch, so that segments
+/// which span a fork boundary are not rejected. Hence `BlockError::ProposalSignatureInvalid` means
+/// that the header is forged, and the peer which sent it may be penalized.
+pub fn verify_chain_segment_headers<T: BeaconChainTypes>(
+    headers: &[SignedBeaconBlockHeader],
+    chain: &BeaconChain<T>,
+) -> Result<(), BlockError<T::EthSpec>> {
+    let head_fork = chain.canonical_head.cached_head().head_fork();
+    for header in headers {
+        match verify_header_signature_with_fork(chain, header, &head_fork) {
+            Err(BlockError::ProposalSignatureInvalid) => {
+                let header_fork = chain
+                    .spec
+                    .fork_at_epoch(header.message.slot.epoch(T::EthSpec::slots_per_epoch()));
+                if header_fork == head_fork {
+                    return Err(BlockError::ProposalSignatureInvalid);
+                }
+                verify_header_signature_with_fork(chain, header, &header_fork)?;
+            }
+            result => result?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies the signature of each deposit in `block`, returning the index of each deposit within
+/// the block alongside whether or not its signature is valid.
+///
+/// ## Notes
+///
+/// This is **not** part of block verification and has no bearing on the validity of the block. A
+/// deposit with an invalid signature (or invalid BLS bytes) is valid as far as the block is
+/// concerned, it is simply ignored by `process_deposit` rather than creating a validator. This
+/// function exists so that such deposits can be identified, e.g., by deposit monitoring tools.
+pub fn verify_deposit_signatures<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    block: &SignedBeaconBlock<E, Payload>,
+    spec: &ChainSpec,
+) -> Vec<(usize, bool)> {
+    block
+        .message()
+        .body()
+        .deposits()
+        .iter()
+        .enumerate()
+        .map(|(i, deposit)| (i, verify_deposit_signature(&deposit.data, spec).is_ok()))
+        .collect()
+}
+
 /// Verify all signatures (except deposit signatures) on all blocks in the `chain_segment`. If all
 /// signatures are valid, the `chain_segment` is mapped to a `Vec<SignatureVerifiedBlock>` that can
 /// later be transformed into a `ExecutionPendingBlock` without re-checking the signatures. If any
-/// signature in the block is invalid, an `Err` is returned (it is not possible to known _which_
-/// signature was invalid).
+/// signature in the block is invalid, an `Err` is returned which identifies the kind of the first
+/// invalid signature.
 ///
 /// ## Errors
 ///
@@ -549,6 +1206,7 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
         &mut parent.pre_state,
         parent.beacon_state_root,
         highest_slot,
+        None,
         &chain.spec,
     )?;
 
@@ -561,7 +1219,9 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
         let mut consensus_context =
             ConsensusContext::new(block.slot()).set_current_block_root(*block_root);
 
-        signature_verifier.include_all_signatures(block, &mut consensus_context)?;
+        signature_verifier
+            .include_all_signatures(block, &mut consensus_context)
+            .map_err(|e| signature_verifier_error(&chain.log, *block_root, block, e))?;
 
         // Save the block and its consensus context. The context will have had its proposer index
         // and attesting indices filled in, which can be used to accelerate later block processing.
@@ -570,11 +1230,26 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
             block_root: *block_root,
             parent: None,
             consensus_context,
+            applied_attestations: None,
+            observe_proposal: true,
         });
     }
 
     if signature_verifier.verify().is_err() {
-        return Err(BlockError::InvalidSignature);
+        let kind = identify_invalid_signature(
+            &mut signature_verified_blocks,
+            &state,
+            &pubkey_cache,
+            &chain.spec,
+        );
+        log_invalid_signature(
+            &chain.log,
+            kind,
+            chain_segment
+                .iter()
+                .map(|(block_root, block)| (*block_root, block.as_ref())),
+        );
+        return Err(BlockError::InvalidSignature { kind });
     }
 
     drop(pubkey_cache);
@@ -586,83 +1261,591 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
     Ok(signature_verified_blocks)
 }
 
-/// A wrapper around a `SignedBeaconBlock` that indicates it has been approved for re-gossiping on
-/// the p2p network.
-#[derive(Derivative)]
-#[derivative(Debug(bound = "T: BeaconChainTypes"))]
-pub struct GossipVerifiedBlock<T: BeaconChainTypes> {
-    pub block: Arc<SignedBeaconBlock<T::EthSpec>>,
-    pub block_root: Hash256,
-    parent: Option<PreProcessingSnapshot<T::EthSpec>>,
-    consensus_context: ConsensusContext<T::EthSpec>,
-}
-
-/// A wrapper around a `SignedBeaconBlock` that indicates that all signatures (except the deposit
-/// signatures) have been verified.
-pub struct SignatureVerifiedBlock<T: BeaconChainTypes> {
-    block: Arc<SignedBeaconBlock<T::EthSpec>>,
-    block_root: Hash256,
-    parent: Option<PreProcessingSnapshot<T::EthSpec>>,
-    consensus_context: ConsensusContext<T::EthSpec>,
-}
-
-/// Used to await the result of executing payload with a remote EE.
-type PayloadVerificationHandle<E> =
-    JoinHandle<Option<Result<PayloadVerificationOutcome, BlockError<E>>>>;
-
-/// A wrapper around a `SignedBeaconBlock` that indicates that this block is fully verified and
-/// ready to import into the `BeaconChain`. The validation includes:
+/// Verify all signatures (except deposit signatures) on all blocks in each of the
+/// `chain_segments`, using a single batch BLS verification for all of the segments.
 ///
-/// - Parent is known
-/// - Signatures
-/// - State root check
-/// - Per block processing
+/// Segments which share a parent and an epoch (e.g., competing forks built upon a common ancestor)
+/// are verified against a single pre-state. Each pre-state is released as soon as the signatures of
+/// its segments have been included, prior to the batch verification.
 ///
-/// Note: a `ExecutionPendingBlock` is not _forever_ valid to be imported, it may later become invalid
-/// due to finality or some other event. A `ExecutionPendingBlock` should be imported into the
-/// `BeaconChain` immediately after it is instantiated.
-pub struct ExecutionPendingBlock<T: BeaconChainTypes> {
-    pub block: Arc<SignedBeaconBlock<T::EthSpec>>,
-    pub block_root: Hash256,
-    pub state: BeaconState<T::EthSpec>,
-    pub parent_block: SignedBeaconBlock<T::EthSpec, BlindedPayload<T::EthSpec>>,
-    pub parent_eth1_finalization_data: Eth1FinalizationData,
-    pub confirmed_state_roots: Vec<Hash256>,
-    pub consensus_context: ConsensusContext<T::EthSpec>,
-    pub payload_verification_handle: PayloadVerificationHandle<T::EthSpec>,
-}
-
-pub trait IntoGossipVerifiedBlock<T: BeaconChainTypes>: Sized {
-    fn into_gossip_verified_block(
-        self,
-        chain: &BeaconChain<T>,
-    ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>>;
-    fn inner(&self) -> Arc<SignedBeaconBlock<T::EthSpec>>;
-}
-
-impl<T: BeaconChainTypes> IntoGossipVerifiedBlock<T> for GossipVerifiedBlock<T> {
-    fn into_gossip_verified_block(
-        self,
-        _chain: &BeaconChain<T>,
-    ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>> {
-        Ok(self)
+/// A result is returned for each of the `chain_segments`, in the order they were provided. If the
+/// batch is invalid, each segment is verified on its own so that an invalid signature in one
+/// segment does not cause the others to fail.
+///
+/// ## Errors
+///
+/// Each segment must contain only blocks from the same epoch, as per
+/// `signature_verify_chain_segment`.
+///
+/// The parent of the first block in each segment must already be imported. The proposer shuffling
+/// for an epoch depends upon the blocks in the prior epoch, so it is not possible to verify a
+/// segment against a state that was advanced through an earlier, un-imported segment.
+///
+/// The outer `Err` is only returned if the validator pubkey cache could not be read, in which case
+/// none of the segments were verified.
+#[allow(clippy::type_complexity)]
+pub fn signature_verify_chain_segments<T: BeaconChainTypes>(
+    chain_segments: Vec<Vec<(Hash256, Arc<SignedBeaconBlock<T::EthSpec>>)>>,
+    chain: &BeaconChain<T>,
+) -> Result<
+    Vec<Result<Vec<SignatureVerifiedBlock<T>>, BlockError<T::EthSpec>>>,
+    BlockError<T::EthSpec>,
+> {
+    // Group the segments by the parent of their first block and the epoch of their last block.
+    let mut groups: Vec<((Hash256, Epoch), Vec<usize>)> = vec![];
+    for (index, segment) in chain_segments.iter().enumerate() {
+        if let (Some((_, first_block)), Some((_, last_block))) = (segment.first(), segment.last()) {
+            let key = (
+                first_block.parent_root(),
+                last_block.slot().epoch(T::EthSpec::slots_per_epoch()),
+            );
+            match groups.iter_mut().find(|(group_key, _)| *group_key == key) {
+                Some((_, indices)) => indices.push(index),
+                None => groups.push((key, vec![index])),
+            }
+        }
     }
 
-    fn inner(&self) -> Arc<SignedBeaconBlock<T::EthSpec>> {
-        self.block.clone()
-    }
-}
+    // Load the parents prior to taking the pubkey cache lock, since they may be read from disk.
+    let parents = groups
+        .iter()
+        .map(|(_, indices)| {
+            let (first_root, first_block) = chain_segments[indices[0]][0].clone();
+            load_parent(first_root, first_block, chain).map(|(parent, _)| parent)
+        })
+        .collect::<Vec<_>>();
 
-impl<T: BeaconChainTypes> IntoGossipVerifiedBlock<T> for Arc<SignedBeaconBlock<T::EthSpec>> {
-    fn into_gossip_verified_block(
-        self,
-        chain: &BeaconChain<T>,
-    ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>> {
-        GossipVerifiedBlock::new(self, chain)
-    }
+    // Empty segments are left as `None`, they are trivially valid.
+    let mut results = chain_segments.iter().map(|_| None).collect::<Vec<_>>();
+    // Segments which must be verified on their own, after the batch.
+    let mut fallback_indices = vec![];
 
-    fn inner(&self) -> Arc<SignedBeaconBlock<T::EthSpec>> {
-        self.clone()
+    let pubkey_cache = get_validator_pubkey_cache(chain)?;
+    let mut batch = Vec::with_capacity(chain_segments.len());
+
+    for ((_, indices), parent) in groups.iter().zip(parents) {
+        let mut parent = match parent {
+            Ok(parent) => parent,
+            Err(e) => {
+                results[indices[0]] = Some(Err(e));
+                fallback_indices.extend_from_slice(&indices[1..]);
+                continue;
+            }
+        };
+
+        let highest_slot = indices
+            .iter()
+            .filter_map(|&index| chain_segments[index].last())
+            .map(|(_, block)| block.slot())
+            .max()
+            .unwrap_or_else(|| parent.pre_state.slot());
+
+        let state = match cheap_state_advance_to_obtain_committees(
+            &mut parent.pre_state,
+            parent.beacon_state_root,
+            highest_slot,
+            None,
+            &chain.spec,
+        ) {
+            Ok(state) => state,
+            Err(e) => {
+                results[indices[0]] = Some(Err(e));
+                fallback_indices.extend_from_slice(&indices[1..]);
+                continue;
+            }
+        };
+
+        for &index in indices {
+            match include_chain_segment_signatures(
+                &chain_segments[index],
+                &state,
+                &pubkey_cache,
+                chain,
+            ) {
+                Ok((signature_verified_blocks, signature_sets)) => {
+                    batch.push((index, signature_verified_blocks, signature_sets))
+                }
+                Err(e) => results[index] = Some(Err(e)),
+            }
+        }
+
+        // The signature sets do not borrow from the state, so it can be released immediately.
+        drop(state);
+
+        // Retain the parent for the import of the first segment in the group.
+        if let Some(signature_verified_block) = batch
+            .iter_mut()
+            .find(|(index, _, _)| indices.contains(index))
+            .and_then(|(_, signature_verified_blocks, _)| signature_verified_blocks.first_mut())
+        {
+            signature_verified_block.parent = Some(parent);
+        }
+    }
+
+    let batch_is_valid = ParallelSignatureSets::verify_all(
+        batch.iter().map(|(_, _, signature_sets)| signature_sets),
+    );
+
+    for (index, signature_verified_blocks, signature_sets) in batch {
+        if batch_is_valid || ParallelSignatureSets::verify_all(std::iter::once(&signature_sets)) {
+            results[index] = Some(Ok(signature_verified_blocks));
+        } else {
+            fallback_indices.push(index);
+        }
+    }
+
+    drop(pubkey_cache);
+
+    // Verifying the segment on its own identifies (and logs) the kind of the invalid signature.
+    for index in fallback_indices {
+        results[index] = Some(signature_verify_chain_segment(
+            chain_segments[index].clone(),
+            chain,
+        ));
+    }
+
+    Ok(results
+        .into_iter()
+        .map(|result| result.unwrap_or_else(|| Ok(vec![])))
+        .collect())
+}
+
+/// Include the signatures of all the blocks in `chain_segment` in a new set of signature sets,
+/// using `state` to obtain the committees and proposers.
+fn include_chain_segment_signatures<'a, T: BeaconChainTypes>(
+    chain_segment: &'a [(Hash256, Arc<SignedBeaconBlock<T::EthSpec>>)],
+    state: &BeaconState<T::EthSpec>,
+    pubkey_cache: &'a ValidatorPubkeyCache<T>,
+    chain: &'a BeaconChain<T>,
+) -> Result<(Vec<SignatureVerifiedBlock<T>>, ParallelSignatureSets<'a>), BlockError<T::EthSpec>> {
+    let mut signature_verifier = get_signature_verifier(state, pubkey_cache, &chain.spec);
+    let mut signature_verified_blocks = Vec::with_capacity(chain_segment.len());
+
+    for (block_root, block) in chain_segment {
+        let mut consensus_context =
+            ConsensusContext::new(block.slot()).set_current_block_root(*block_root);
+
+        signature_verifier
+            .include_all_signatures(block, &mut consensus_context)
+            .map_err(|e| signature_verifier_error(&chain.log, *block_root, block, e))?;
+
+        signature_verified_blocks.push(SignatureVerifiedBlock {
+            block: block.clone(),
+            block_root: *block_root,
+            parent: None,
+            consensus_context,
+            applied_attestations: None,
+            observe_proposal: true,
+        });
+    }
+
+    Ok((
+        signature_verified_blocks,
+        signature_verifier.into_signature_sets(),
+    ))
+}
+
+/// A major stage of verifying a single block, reported via `VerificationProgress`.
+///
+/// Stages are listed in the order in which they are reached. Stages prior to the one at which
+/// verification started are not reported (e.g., `GossipOk` is only reported for a
+/// `GossipVerifiedBlock`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerificationStage {
+    /// The block passed gossip verification.
+    GossipOk,
+    /// All signatures in the block were verified.
+    SignaturesOk,
+    /// Verification of the execution payload has started, it runs in parallel with the remaining
+    /// stages.
+    PayloadSubmitted,
+    /// The parent state was advanced to the slot of the block.
+    CatchupDone,
+    /// `per_block_processing` completed.
+    PerBlockDone,
+    /// The post-state root was computed and checked against the block.
+    StateRootOk,
+}
+
+/// An optional callback which is invoked with each `VerificationStage` reached whilst verifying a
+/// block, along with the time elapsed since the callback was created.
+///
+/// The default does nothing.
+#[derive(Default)]
+pub struct VerificationProgress<'a> {
+    callback: Option<(Instant, &'a dyn Fn(VerificationStage, Duration))>,
+}
+
+impl<'a> VerificationProgress<'a> {
+    pub fn new(callback: &'a dyn Fn(VerificationStage, Duration)) -> Self {
+        Self {
+            callback: Some((Instant::now(), callback)),
+        }
+    }
+
+    fn report(&self, stage: VerificationStage) {
+        if let Some((start, callback)) = &self.callback {
+            callback(stage, start.elapsed())
+        }
+    }
+}
+
+/// Control verification of the post-state root against the `state_root` of the block.
+///
+/// ## Warning
+///
+/// `VerifyStateRoot::False` allows blocks with an incorrect state root to be imported, in which
+/// case the post-state is stored under a root that does not match its contents. It is only used
+/// by `ExecutionPendingBlock::from_signature_verified_components_unchecked_state_root`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VerifyStateRoot {
+    True,
+    False,
+}
+
+/// Options for `ExecutionPendingBlock::verify_components`.
+///
+/// The `Default` options are those used for blocks which are being verified for import by the
+/// live node. Each entry point only sets the fields which it changes.
+struct VerifyComponentsOptions<'a> {
+    /// If `Some`, the EL is not notified of payloads whose status is already known. See
+    /// `ExecutionPendingBlock::from_signature_verified_components`.
+    known_payload_statuses: Option<&'a KnownPayloadStatuses>,
+    /// If `true` then the verification will not have any side-effects on the chain, aside from
+    /// notifying the EL. The block reward is always computed during a dry run.
+    dry_run: bool,
+    /// If `false` then the parent is not required to be known to fork choice. See
+    /// `DryRunVerifiedBlock::from_components_with_parent_state`.
+    check_fork_choice: bool,
+    /// If `false` then payload verification is deferred until the handle is awaited. See
+    /// `ExecutionPendingBlock::from_signature_verified_components_inline`.
+    spawn_payload_verification: bool,
+    /// Attestations which are redundant according to `applied_attestations` are not applied to
+    /// fork choice.
+    applied_attestations: Option<&'a Mutex<AppliedAttestations>>,
+    /// If `false` then the proposal is not registered with `observed_block_producers`, even if
+    /// this is not a dry run. See `SignatureVerifiedBlock::without_proposal_observation`.
+    observe_proposal: bool,
+    /// If `false` then the block's attestations and attester slashings are returned in
+    /// `deferred_fork_choice_updates` rather than applied. See
+    /// `ExecutionPendingBlock::from_signature_verified_components_with_deferred_fork_choice`.
+    apply_attestations_to_fork_choice: bool,
+    /// Must be `VerifyStateRoot::True` for all entry points except
+    /// `ExecutionPendingBlock::from_signature_verified_components_unchecked_state_root`.
+    verify_state_root: VerifyStateRoot,
+    /// Must be `StateProcessingStrategy::Accurate` for all blocks which did not come from explicit
+    /// tooling. The state root is verified regardless of the strategy.
+    state_processing_strategy: StateProcessingStrategy,
+}
+
+impl<'a> Default for VerifyComponentsOptions<'a> {
+    fn default() -> Self {
+        Self {
+            known_payload_statuses: None,
+            dry_run: false,
+            check_fork_choice: true,
+            spawn_payload_verification: true,
+            applied_attestations: None,
+            observe_proposal: true,
+            apply_attestations_to_fork_choice: true,
+            verify_state_root: VerifyStateRoot::True,
+            state_processing_strategy: StateProcessingStrategy::Accurate,
+        }
+    }
+}
+
+/// Determines whether the proposal signature is verified during gossip verification.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ProposalSignatureCheck {
+    /// Verify the proposal signature.
+    Verify,
+    /// Assume the proposal signature is valid, since it has already been verified elsewhere.
+    AssumeValid,
+}
+
+/// Determines whether gossip verification records the proposal in `observed_block_producers`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ProposalObservation {
+    /// Record the proposal, so that later blocks from the same proposer and slot are rejected.
+    Record,
+    /// Check the proposal against prior observations without recording it. A block which has
+    /// already been observed is not rejected. This must only be used for replaying blocks.
+    ReadOnly,
+}
+
+/// A wrapper around a `SignedBeaconBlock` that indicates it has been approved for re-gossiping on
+/// the p2p network.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "T: BeaconChainTypes"))]
+pub struct GossipVerifiedBlock<T: BeaconChainTypes> {
+    pub block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    pub block_root: Hash256,
+    parent: Option<PreProcessingSnapshot<T::EthSpec>>,
+    consensus_context: ConsensusContext<T::EthSpec>,
+}
+
+/// A wrapper around a `SignedBeaconBlock` that indicates that all signatures (except the deposit
+/// signatures) have been verified.
+pub struct SignatureVerifiedBlock<T: BeaconChainTypes> {
+    block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    block_root: Hash256,
+    parent: Option<PreProcessingSnapshot<T::EthSpec>>,
+    consensus_context: ConsensusContext<T::EthSpec>,
+    /// Attestations already applied to fork choice by earlier blocks in the same chain segment.
+    applied_attestations: Option<Arc<Mutex<AppliedAttestations>>>,
+    /// If `false`, the proposal is not registered with `observed_block_producers` during
+    /// verification and must instead be observed once the block has been imported.
+    observe_proposal: bool,
+}
+
+/// Used to await the result of executing payload with a remote EE.
+pub enum PayloadVerificationHandle<E: EthSpec> {
+    /// Verification is running in a spawned task, in parallel with the remainder of block
+    /// verification.
+    Spawned(JoinHandle<Option<Result<PayloadVerificationOutcome, BlockError<E>>>>),
+    /// Verification has not started and will run inline when the outcome is awaited.
+    Deferred(BoxFuture<'static, Result<PayloadVerificationOutcome, BlockError<E>>>),
+}
+
+impl<E: EthSpec> PayloadVerificationHandle<E> {
+    /// Wait for the payload verification to complete, returning its outcome.
+    pub async fn outcome(self) -> Result<PayloadVerificationOutcome, BlockError<E>> {
+        match self {
+            Self::Spawned(handle) => match handle.await {
+                Ok(outcome) => outcome.ok_or(BeaconChainError::RuntimeShutdown)?,
+                // The task is only aborted when the executor is shutting down. Irrelevant blocks
+                // are cancelled within the task, which then returns `VerificationCancelled`.
+                Err(e) if e.is_cancelled() => Err(BeaconChainError::RuntimeShutdown.into()),
+                // The task panicked.
+                Err(e) => Err(BeaconChainError::TokioJoin(e).into()),
+            },
+            Self::Deferred(future) => future.await,
+        }
+    }
+}
+
+/// A wrapper around a `SignedBeaconBlock` that indicates that this block is fully verified and
+/// ready to import into the `BeaconChain`. The validation includes:
+///
+/// - Parent is known
+/// - Signatures
+/// - State root check
+/// - Per block processing
+///
+/// Note: a `ExecutionPendingBlock` is not _forever_ valid to be imported, it may later become invalid
+/// due to finality or some other event. A `ExecutionPendingBlock` should be imported into the
+/// `BeaconChain` immediately after it is instantiated.
+pub struct ExecutionPendingBlock<T: BeaconChainTypes> {
+    pub block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    pub block_root: Hash256,
+    pub state: BeaconState<T::EthSpec>,
+    pub parent_block: SignedBeaconBlock<T::EthSpec, BlindedPayload<T::EthSpec>>,
+    pub parent_eth1_finalization_data: Eth1FinalizationData,
+    /// The roots of the intermediate states which were stored during verification.
+    ///
+    /// States which were not already in the database are stored with a temporary flag, which is
+    /// deleted when the block is imported. If the block is never imported (e.g., its payload
+    /// verification is aborted because the runtime is shutting down) the temporary states are left
+    /// in the database and are garbage collected the next time the database is opened.
+    ///
+    /// The states are not deleted eagerly on the abort path, since a concurrent verification of
+    /// another block from the same parent may find them in the database and rely upon them
+    /// without storing its own copy.
+    pub confirmed_state_roots: Vec<Hash256>,
+    pub consensus_context: ConsensusContext<T::EthSpec>,
+    pub payload_verification_handle: PayloadVerificationHandle<T::EthSpec>,
+    /// The number of skipped slots between the parent block and this block.
+    ///
+    /// This does not depend upon whether the parent state had already been advanced (e.g., by the
+    /// state advance timer).
+    pub skipped_slots: u64,
+    /// The roots of the states computed whilst advancing the parent state to the block's slot, in
+    /// slot order.
+    pub intermediate_state_roots: Vec<Hash256>,
+    /// The execution status of the parent block, as known to fork choice during verification.
+    ///
+    /// This is `None` if fork choice was not consulted (e.g., for blocks built by an
+    /// `ExecutionPendingBlockBuilder`).
+    pub parent_execution_status: Option<ExecutionStatus>,
+    /// The state after advancing the parent state to the block's slot, but prior to applying the
+    /// block (i.e., the input to `per_block_processing`).
+    ///
+    /// This is only retained if `ChainConfig::retain_pre_block_state` is enabled, since it
+    /// requires a copy of the state (without its caches) for every block being verified.
+    pub pre_block_state: Option<BeaconState<T::EthSpec>>,
+    /// The attestations and attester slashings from the block which are yet to be applied to fork
+    /// choice.
+    ///
+    /// This is only `Some` for blocks verified via
+    /// `Self::from_signature_verified_components_with_deferred_fork_choice`.
+    pub deferred_fork_choice_updates: Option<DeferredForkChoiceUpdates<T::EthSpec>>,
+    /// The number of the block's attestations which were applied to fork choice during
+    /// verification.
+    ///
+    /// Attestations which fork choice ignored as invalid (e.g., because the block is old), or
+    /// which were redundant with an earlier block in the same chain segment, are not counted. This
+    /// is zero if the fork choice updates were deferred.
+    pub fork_choice_attestations_applied: usize,
+    /// The number of the block's attester slashings which were applied to fork choice during
+    /// verification. This is zero if the fork choice updates were deferred.
+    pub attester_slashings_applied: usize,
+    /// Whether the execution payload was accepted optimistically, i.e., the execution engine has
+    /// not yet confirmed its validity.
+    ///
+    /// This is always `false` until the payload verification has resolved, see
+    /// `Self::resolve_payload_verification`.
+    pub imported_optimistically: bool,
+}
+
+/// The fork choice updates from a block's body which were not applied during verification.
+///
+/// See `ExecutionPendingBlock::from_signature_verified_components_with_deferred_fork_choice`.
+pub struct DeferredForkChoiceUpdates<E: EthSpec> {
+    pub attester_slashings: Vec<AttesterSlashing<E>>,
+    pub indexed_attestations: Vec<IndexedAttestation<E>>,
+}
+
+impl<E: EthSpec> DeferredForkChoiceUpdates<E> {
+    /// Applies the attester slashings and attestations to the fork choice of `chain`, exactly as
+    /// they would have been applied during verification.
+    pub fn apply<T: BeaconChainTypes<EthSpec = E>>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<(), BlockError<E>> {
+        let current_slot = chain.slot()?;
+        let mut fork_choice = chain.canonical_head.fork_choice_write_lock();
+
+        for attester_slashing in &self.attester_slashings {
+            fork_choice.on_attester_slashing(attester_slashing);
+        }
+
+        for indexed_attestation in &self.indexed_attestations {
+            match fork_choice.on_attestation(
+                current_slot,
+                indexed_attestation,
+                AttestationFromBlock::True,
+            ) {
+                Ok(()) => Ok(()),
+                // Ignore invalid attestations, as per block verification.
+                Err(ForkChoiceError::InvalidAttestation(_)) => Ok(()),
+                Err(e) => Err(BlockError::BeaconChainError(e.into())),
+            }?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The result of fully verifying a block without importing it or otherwise mutating the
+/// `BeaconChain`. See `SignatureVerifiedBlock::into_dry_run_verified_block`.
+///
+/// Unlike an `ExecutionPendingBlock`, this type cannot be imported since the intermediate states
+/// between the parent and the block were not stored.
+pub struct DryRunVerifiedBlock<T: BeaconChainTypes> {
+    pub block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    pub block_root: Hash256,
+    /// The state after applying `block`.
+    pub state: BeaconState<T::EthSpec>,
+    pub block_reward: BlockReward,
+    pub payload_verification_handle: PayloadVerificationHandle<T::EthSpec>,
+    /// See `ExecutionPendingBlock::skipped_slots`.
+    pub skipped_slots: u64,
+    /// See `ExecutionPendingBlock::intermediate_state_roots`.
+    pub intermediate_state_roots: Vec<Hash256>,
+    /// The execution status of the parent block, as known to fork choice during verification.
+    ///
+    /// This is `None` if fork choice was not consulted (see
+    /// `Self::from_components_with_parent_state`).
+    pub parent_execution_status: Option<ExecutionStatus>,
+}
+
+impl<T: BeaconChainTypes> DryRunVerifiedBlock<T> {
+    /// Fully verifies `block` against a `parent` which is supplied directly by the caller, rather
+    /// than having been loaded via `load_parent`.
+    ///
+    /// This is useful for simulation and fuzzing, where the `parent` may be a synthetic state.
+    /// Verification is always a dry run (see `SignatureVerifiedBlock::into_dry_run_verified_block`),
+    /// so a synthetic parent cannot affect the proposers observed by the chain, the database or
+    /// fork choice. Block signatures are not verified.
+    ///
+    /// If `check_fork_choice` is `false`, the parent of `block` is not required to be known to
+    /// fork choice, nor is its execution payload checked for validity.
+    pub fn from_components_with_parent_state(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        parent: PreProcessingSnapshot<T::EthSpec>,
+        consensus_context: ConsensusContext<T::EthSpec>,
+        chain: &Arc<BeaconChain<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        check_fork_choice: bool,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        let (execution_pending, block_reward) = ExecutionPendingBlock::verify_components(
+            block,
+            block_root,
+            parent,
+            consensus_context,
+            chain,
+            notify_execution_layer,
+            VerifyComponentsOptions {
+                dry_run: true,
+                check_fork_choice,
+                ..Default::default()
+            },
+            &VerificationProgress::default(),
+        )?;
+        Self::from_dry_run(execution_pending, block_reward)
+    }
+
+    /// Converts the result of a dry run of `ExecutionPendingBlock::verify_components`.
+    fn from_dry_run(
+        execution_pending: ExecutionPendingBlock<T>,
+        block_reward: Option<BlockReward>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        Ok(Self {
+            block: execution_pending.block,
+            block_root: execution_pending.block_root,
+            state: execution_pending.state,
+            // The block reward is always computed during a dry run.
+            block_reward: block_reward.ok_or(BeaconChainError::DryRunBlockRewardMissing)?,
+            payload_verification_handle: execution_pending.payload_verification_handle,
+            skipped_slots: execution_pending.skipped_slots,
+            intermediate_state_roots: execution_pending.intermediate_state_roots,
+            parent_execution_status: execution_pending.parent_execution_status,
+        })
+    }
+}
+
+pub trait IntoGossipVerifiedBlock<T: BeaconChainTypes>: Sized {
+    fn into_gossip_verified_block(
+        self,
+        chain: &BeaconChain<T>,
+    ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>>;
+    fn inner(&self) -> Arc<SignedBeaconBlock<T::EthSpec>>;
+}
+
+impl<T: BeaconChainTypes> IntoGossipVerifiedBlock<T> for GossipVerifiedBlock<T> {
+    fn into_gossip_verified_block(
+        self,
+        _chain: &BeaconChain<T>,
+    ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>> {
+        Ok(self)
+    }
+
+    fn inner(&self) -> Arc<SignedBeaconBlock<T::EthSpec>> {
+        self.block.clone()
+    }
+}
+
+impl<T: BeaconChainTypes> IntoGossipVerifiedBlock<T> for Arc<SignedBeaconBlock<T::EthSpec>> {
+    fn into_gossip_verified_block(
+        self,
+        chain: &BeaconChain<T>,
+    ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>> {
+        GossipVerifiedBlock::new(self, chain)
+    }
+
+    fn inner(&self) -> Arc<SignedBeaconBlock<T::EthSpec>> {
+        self.clone()
     }
 }
 
@@ -676,15 +1859,37 @@ pub trait IntoExecutionPendingBlock<T: BeaconChainTypes>: Sized {
         chain: &Arc<BeaconChain<T>>,
         notify_execution_layer: NotifyExecutionLayer,
     ) -> Result<ExecutionPendingBlock<T>, BlockError<T::EthSpec>> {
-        self.into_execution_pending_block_slashable(block_root, chain, notify_execution_layer)
-            .map(|execution_pending| {
-                // Supply valid block to slasher.
-                if let Some(slasher) = chain.slasher.as_ref() {
-                    slasher.accept_block_header(execution_pending.block.signed_block_header());
-                }
-                execution_pending
-            })
-            .map_err(|slash_info| process_block_slash_info(chain, slash_info))
+        self.into_execution_pending_block_with_progress(
+            block_root,
+            chain,
+            notify_execution_layer,
+            &VerificationProgress::default(),
+        )
+    }
+
+    /// As per `Self::into_execution_pending_block`, except that `progress` is informed as each
+    /// `VerificationStage` is reached.
+    fn into_execution_pending_block_with_progress(
+        self,
+        block_root: Hash256,
+        chain: &Arc<BeaconChain<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        progress: &VerificationProgress,
+    ) -> Result<ExecutionPendingBlock<T>, BlockError<T::EthSpec>> {
+        self.into_execution_pending_block_slashable(
+            block_root,
+            chain,
+            notify_execution_layer,
+            progress,
+        )
+        .map(|execution_pending| {
+            // Supply valid block to slasher.
+            if let Some(slasher) = chain.slasher.as_ref() {
+                slasher.accept_block_header(execution_pending.block.signed_block_header());
+            }
+            execution_pending
+        })
+        .map_err(|slash_info| process_block_slash_info(chain, slash_info))
     }
 
     /// Convert the block to fully-verified form while producing data to aid checking slashability.
@@ -693,7 +1898,8 @@ pub trait IntoExecutionPendingBlock<T: BeaconChainTypes>: Sized {
         block_root: Hash256,
         chain: &Arc<BeaconChain<T>>,
         notify_execution_layer: NotifyExecutionLayer,
-    ) -> Result<ExecutionPendingBlock<T>, BlockSlashInfo<BlockError<T::EthSpec>>>;
+        progress: &VerificationProgress,
+    ) -> Result<ExecutionPendingBlock<T>, BlockSlashInfo<T::EthSpec>>;
 
     fn block(&self) -> &SignedBeaconBlock<T::EthSpec>;
 }
@@ -707,45 +1913,195 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
         chain: &BeaconChain<T>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::new_with_spec_override(block, chain, None)
+    }
+
+    /// As for `new`, but the block is verified against `spec_override` rather than `chain.spec`,
+    /// if it is supplied. This allows a block to be judged under a different fork schedule.
+    ///
+    /// When an override is supplied the proposer and committee caches are neither read nor
+    /// primed, since their contents are only valid under `chain.spec`. Such a block is not
+    /// destined for import, so its proposal is neither recorded in the `observed_block_producers`
+    /// cache nor supplied to the slasher, and its parent is left in the snapshot cache.
+    pub fn new_with_spec_override(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        chain: &BeaconChain<T>,
+        spec_override: Option<&ChainSpec>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        if spec_override.is_some() {
+            return Self::new_without_slasher_checks(
+                block,
+                chain,
+                ProposalSignatureCheck::Verify,
+                ProposalObservation::ReadOnly,
+                spec_override,
+            );
+        }
+
         // If the block is valid for gossip we don't supply it to the slasher here because
         // we assume it will be transformed into a fully verified block. We *do* need to supply
         // it to the slasher if an error occurs, because that's the end of this block's journey,
         // and it could be a repeat proposal (a likely cause for slashing!).
-        let header = block.signed_block_header();
-        Self::new_without_slasher_checks(block, chain).map_err(|e| {
-            process_block_slash_info(chain, BlockSlashInfo::from_early_error(header, e))
+        let slash_block = block.clone();
+        Self::new_without_slasher_checks(
+            block,
+            chain,
+            ProposalSignatureCheck::Verify,
+            ProposalObservation::Record,
+            None,
+        )
+        .map_err(|e| {
+            process_block_slash_info(chain, BlockSlashInfo::from_early_error(slash_block, e))
+        })
+    }
+
+    /// As for `new`, but the proposal signature of `block` is assumed to be valid rather than
+    /// being verified. All other gossip checks are performed and the proposal is still recorded in
+    /// the `observed_block_producers` cache.
+    ///
+    /// ## Warning
+    ///
+    /// This function must only be used when the caller has *already* verified the proposal
+    /// signature (e.g., the block was signed by this node). Supplying a block with an invalid
+    /// proposal signature will cause it to be treated as valid for gossip.
+    pub fn new_assume_signature_valid(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        let slash_block = block.clone();
+        Self::new_without_slasher_checks(
+            block,
+            chain,
+            ProposalSignatureCheck::AssumeValid,
+            ProposalObservation::Record,
+            None,
+        )
+        .map_err(|e| {
+            process_block_slash_info(chain, BlockSlashInfo::from_early_error(slash_block, e))
         })
     }
 
+    /// As for `new`, but the proposal is *not* recorded in the `observed_block_producers` cache
+    /// and the block is not passed to the slasher. This allows a captured stream of gossip blocks
+    /// to be verified repeatedly with the same result.
+    ///
+    /// A block which equivocates with a previously *recorded* proposal is still rejected, but a
+    /// block which has already been observed is not.
+    ///
+    /// ## Warning
+    ///
+    /// This function is intended for replaying gossip traffic in testing and tooling. It must never
+    /// be used for live gossip, since it disables equivocation protection for the blocks it
+    /// verifies.
+    pub fn new_for_replay(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::new_without_slasher_checks(
+            block,
+            chain,
+            ProposalSignatureCheck::Verify,
+            ProposalObservation::ReadOnly,
+            None,
+        )
+    }
+
     /// As for new, but doesn't pass the block to the slasher.
+    ///
+    /// Blocks which were recently rejected are refused without being re-verified, see
+    /// `RejectedBlockCache`. The cache is bypassed when replaying blocks or verifying against an
+    /// overridden spec, since the original rejection may not apply.
     fn new_without_slasher_checks(
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
         chain: &BeaconChain<T>,
+        proposal_signature_check: ProposalSignatureCheck,
+        proposal_observation: ProposalObservation,
+        spec_override: Option<&ChainSpec>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
-        // Ensure the block is the correct structure for the fork at `block.slot()`.
-        block
-            .fork_name(&chain.spec)
-            .map_err(BlockError::InconsistentFork)?;
+        let block_root = get_block_root(&block);
 
-        // Do not gossip or process blocks from future slots.
-        let present_slot_with_tolerance = chain
-            .slot_clock
-            .now_with_future_tolerance(chain.spec.maximum_gossip_clock_disparity())
-            .ok_or(BeaconChainError::UnableToReadSlot)?;
-        if block.slot() > present_slot_with_tolerance {
-            return Err(BlockError::FutureSlot {
-                present_slot: present_slot_with_tolerance,
-                block_slot: block.slot(),
+        let use_rejected_block_cache =
+            proposal_observation == ProposalObservation::Record && spec_override.is_none();
+        if !use_rejected_block_cache {
+            return Self::verify_without_slasher_checks(
+                block,
+                block_root,
+                chain,
+                proposal_signature_check,
+                proposal_observation,
+                spec_override,
+            );
+        }
+
+        if let Some(rejection) = chain.rejected_block_cache.get(&block_root) {
+            metrics::inc_counter(&metrics::GOSSIP_BEACON_BLOCK_REJECTED_CACHE_HITS);
+            return Err(BlockError::RecentlyRejected {
+                block_root,
+                rejection,
             });
         }
 
-        let block_root = get_block_root(&block);
+        Self::verify_without_slasher_checks(
+            block,
+            block_root,
+            chain,
+            proposal_signature_check,
+            proposal_observation,
+            spec_override,
+        )
+        .map_err(|e| {
+            chain.rejected_block_cache.insert(block_root, &e);
+            e
+        })
+    }
+
+    /// Performs the gossip checks for `new_without_slasher_checks`.
+    fn verify_without_slasher_checks(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        chain: &BeaconChain<T>,
+        proposal_signature_check: ProposalSignatureCheck,
+        proposal_observation: ProposalObservation,
+        spec_override: Option<&ChainSpec>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        let spec = spec_override.unwrap_or(&chain.spec);
+
+        // Ensure the block is the correct structure for the fork at `block.slot()`.
+        check_block_fork(&block, chain, spec)?;
+
+        // This is an artificial (non-spec) restriction that provides some protection from overflow
+        // abuses. It is checked prior to the future slot check so that such blocks are rejected
+        // outright, rather than being ignored as blocks which may become valid later.
+        if block.slot() >= MAXIMUM_BLOCK_SLOT_NUMBER {
+            return Err(BlockError::BlockSlotLimitReached);
+        }
+
+        // Do not gossip or process blocks from future slots.
+        let tolerance = chain
+            .config
+            .future_block_tolerance
+            .unwrap_or_else(|| spec.maximum_gossip_clock_disparity());
+        check_block_against_present_slot(block.message(), tolerance, chain)
+            .map_err(|e| record_gossip_rejection("future_slot", e))?;
 
         // Disallow blocks that conflict with the anchor (weak subjectivity checkpoint), if any.
-        check_block_against_anchor_slot(block.message(), chain)?;
+        let anchor_check_timer = metrics::start_timer(&metrics::GOSSIP_BEACON_BLOCK_ANCHOR_CHECK);
+        check_block_against_anchor_slot(block.message(), chain)
+            .map_err(|e| record_gossip_rejection("anchor_slot", e))?;
+        metrics::stop_timer(anchor_check_timer);
 
         // Do not gossip a block from a finalized slot.
-        check_block_against_finalized_slot(block.message(), block_root, chain)?;
+        let finalized_check_timer =
+            metrics::start_timer(&metrics::GOSSIP_BEACON_BLOCK_FINALIZED_CHECK);
+        check_block_against_finalized_slot(
+            block.message(),
+            block_root,
+            chain,
+            PreFinalizationRejection::Record,
+            None,
+        )
+        .map_err(|e| record_gossip_rejection("finalized_slot", e))?;
+        metrics::stop_timer(finalized_check_timer);
 
         // Check if the block is already known. We know it is post-finalization, so it is
         // sufficient to check the fork choice.
@@ -754,19 +2110,27 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         // reboot if the `observed_block_producers` cache is empty. In that case, without this
         // check, we will load the parent and state from disk only to find out later that we
         // already know this block.
+        let fork_choice_check_timer =
+            metrics::start_timer(&metrics::GOSSIP_BEACON_BLOCK_FORK_CHOICE_CHECK);
         let fork_choice_read_lock = chain.canonical_head.fork_choice_read_lock();
         if fork_choice_read_lock.contains_block(&block_root) {
-            return Err(BlockError::BlockIsAlreadyKnown);
+            return Err(record_gossip_rejection(
+                "already_known",
+                BlockError::BlockIsAlreadyKnown,
+            ));
         }
 
         // Do not process a block that doesn't descend from the finalized root.
         //
         // We check this *before* we load the parent so that we can return a more detailed error.
-        check_block_is_finalized_checkpoint_or_descendant(chain, &fork_choice_read_lock, &block)?;
+        check_block_is_finalized_checkpoint_or_descendant(chain, &fork_choice_read_lock, &block)
+            .map_err(|e| record_gossip_rejection("not_finalized_descendant", e))?;
         drop(fork_choice_read_lock);
+        metrics::stop_timer(fork_choice_check_timer);
 
-        let block_epoch = block.slot().epoch(T::EthSpec::slots_per_epoch());
+        let parent_lookup_timer = metrics::start_timer(&metrics::GOSSIP_BEACON_BLOCK_PARENT_LOOKUP);
         let (parent_block, block) = verify_parent_block_is_known(chain, block)?;
+        metrics::stop_timer(parent_lookup_timer);
 
         // Track the number of skip slots between the block and its parent.
         metrics::set_gauge(
@@ -785,108 +2149,152 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         //
         // https://github.com/ethereum/eth2.0-specs/pull/2196
         if parent_block.slot >= block.slot() {
-            return Err(BlockError::BlockIsNotLaterThanParent {
-                block_slot: block.slot(),
-                parent_slot: parent_block.slot,
-            });
+            return Err(record_gossip_rejection(
+                "not_later_than_parent",
+                BlockError::BlockIsNotLaterThanParent {
+                    block_slot: block.slot(),
+                    parent_slot: parent_block.slot,
+                },
+            ));
         }
 
-        let proposer_shuffling_decision_block =
-            if parent_block.slot.epoch(T::EthSpec::slots_per_epoch()) == block_epoch {
-                parent_block
-                    .next_epoch_shuffling_id
-                    .shuffling_decision_block
-            } else {
-                parent_block.root
-            };
+        // Check the skipped slots before the parent state is loaded and advanced, which requires
+        // work proportional to the number of skipped slots.
+        check_block_skip_slots(chain, parent_block.slot, block.slot())?;
 
-        // We assign to a variable instead of using `if let Some` directly to ensure we drop the
-        // write lock before trying to acquire it again in the `else` clause.
-        let proposer_opt = chain
-            .beacon_proposer_cache
-            .lock()
-            .get_slot::<T::EthSpec>(proposer_shuffling_decision_block, block.slot());
-        let (expected_proposer, fork, parent, block) = if let Some(proposer) = proposer_opt {
-            // The proposer index was cached and we can return it without needing to load the
-            // parent.
-            (proposer.index, proposer.fork, None, block)
-        } else {
-            // The proposer index was *not* cached and we must load the parent in order to determine
-            // the proposer index.
-            let (mut parent, block) = load_parent(block_root, block, chain)?;
-
-            debug!(
-                chain.log,
-                "Proposer shuffling cache miss";
-                "parent_root" => ?parent.beacon_block_root,
-                "parent_slot" => parent.beacon_block.slot(),
-                "block_root" => ?block_root,
-                "block_slot" => block.slot(),
-            );
-
-            // The state produced is only valid for determining proposer/attester shuffling indices.
-            let state = cheap_state_advance_to_obtain_committees(
-                &mut parent.pre_state,
-                parent.beacon_state_root,
-                block.slot(),
-                &chain.spec,
-            )?;
-
-            let proposers = state.get_beacon_proposer_indices(&chain.spec)?;
-            let proposer_index = *proposers
-                .get(block.slot().as_usize() % T::EthSpec::slots_per_epoch() as usize)
-                .ok_or_else(|| BeaconChainError::NoProposerForSlot(block.slot()))?;
-
-            // Prime the proposer shuffling cache with the newly-learned value.
-            chain.beacon_proposer_cache.lock().insert(
-                block_epoch,
-                proposer_shuffling_decision_block,
-                proposers,
-                state.fork(),
-            )?;
+        // Reject an obviously-bogus proposer index before the proposer shuffling is computed, which
+        // may require a state to be loaded and advanced. The pubkey cache contains every validator
+        // in the parent state, so its length is a cheap upper bound on the parent's validator count.
+        let claimed_proposer_index = block.message().proposer_index();
+        if claimed_proposer_index >= get_validator_pubkey_cache(chain)?.len() as u64 {
+            return Err(BlockError::UnknownValidator(claimed_proposer_index));
+        }
 
-            (proposer_index, state.fork(), Some(parent), block)
-        };
+        let (expected_proposer, fork, parent, block) = expected_proposer(
+            chain,
+            block,
+            block_root,
+            &parent_block,
+            spec_override,
+            SnapshotCacheAccess::for_chain(chain, spec_override.is_some()),
+        )?;
 
         let signature_is_valid = {
+            let _signature_timer = metrics::start_timer(&metrics::GOSSIP_BEACON_BLOCK_SIGNATURE);
             let pubkey_cache = get_validator_pubkey_cache(chain)?;
             let pubkey = pubkey_cache
                 .get(block.message().proposer_index() as usize)
                 .ok_or_else(|| BlockError::UnknownValidator(block.message().proposer_index()))?;
-            block.verify_signature(
-                Some(block_root),
-                pubkey,
-                &fork,
-                chain.genesis_validators_root,
-                &chain.spec,
-            )
+            match proposal_signature_check {
+                ProposalSignatureCheck::Verify => block.verify_signature(
+                    Some(block_root),
+                    pubkey,
+                    &fork,
+                    chain.genesis_validators_root,
+                    spec,
+                ),
+                // The caller has guaranteed that the signature is valid.
+                ProposalSignatureCheck::AssumeValid => true,
+            }
         };
 
         if !signature_is_valid {
-            return Err(BlockError::ProposalSignatureInvalid);
+            return Err(record_gossip_rejection(
+                "invalid_signature",
+                BlockError::ProposalSignatureInvalid,
+            ));
         }
 
         // Now the signature is valid, store the proposal so we don't accept another from this
         // validator and slot.
         //
         // It's important to double-check that the proposer still hasn't been observed so we don't
-        // have a race-condition when verifying two blocks simultaneously.
-        match chain
-            .observed_block_producers
-            .write()
-            .observe_proposal(block_root, block.message())
-            .map_err(|e| BlockError::BeaconChainError(e.into()))?
-        {
-            SeenBlock::Slashable => return Err(BlockError::Slashable),
-            SeenBlock::Duplicate => return Err(BlockError::BlockIsAlreadyKnown),
-            SeenBlock::UniqueNonSlashable => {}
+        // have a race-condition when verifying two blocks simultaneously. The check and the
+        // observation happen under a single write lock, so of two equivocating blocks verified
+        // simultaneously exactly one is accepted and the other is `Slashable`.
+        let seen_block = match proposal_observation {
+            ProposalObservation::Record => chain
+                .observed_block_producers
+                .write()
+                .observe_proposal(block_root, block.message()),
+            ProposalObservation::ReadOnly => chain
+                .observed_block_producers
+                .read()
+                .proposer_has_been_observed(block.message(), block_root),
+        }
+        .map_err(|e| BlockError::BeaconChainError(e.into()))?;
+        match seen_block {
+            SeenBlock::Slashable => {
+                // Replayed equivocations have already been reported.
+                let event_handler = chain
+                    .event_handler
+                    .as_ref()
+                    .filter(|_| proposal_observation == ProposalObservation::Record);
+                if let Some(event_handler) = event_handler {
+                    if event_handler.has_block_equivocation_subscribers() {
+                        event_handler.register(EventKind::BlockEquivocation(
+                            SseBlockEquivocation {
+                                slot: block.slot(),
+                                block: block_root,
+                                proposer_index: block.message().proposer_index(),
+                            },
+                        ));
+                    }
+                }
+                return Err(record_gossip_rejection(
+                    "equivocation",
+                    BlockError::Slashable,
+                ));
+            }
+            SeenBlock::Duplicate if proposal_observation == ProposalObservation::Record => {
+                return Err(record_gossip_rejection(
+                    "already_known",
+                    BlockError::BlockIsAlreadyKnown,
+                ))
+            }
+            SeenBlock::Duplicate | SeenBlock::UniqueNonSlashable => {}
         };
 
-        if block.message().proposer_index() != expected_proposer as u64 {
-            return Err(BlockError::IncorrectBlockProposer {
-                block: block.message().proposer_index(),
-                local_shuffling: expected_proposer as u64,
-            });
+        if block.message().proposer_index() != expected_proposer as u64 {
+            // The parent is only loaded on a proposer cache miss, so the proposer came from the
+            // cache if it is absent.
+            if parent.is_none() && chain.config.recheck_cached_proposer_on_mismatch {
+                recheck_cached_proposer(chain, &block, block_root, expected_proposer, spec);
+            }
+            debug!(
+                chain.log,
+                "Block from incorrect proposer";
+                "block_root" => ?block_root,
+                "block_slot" => block.slot(),
+                "block_proposer" => block.message().proposer_index(),
+                "expected_proposer" => expected_proposer,
+            );
+            return Err(record_gossip_rejection(
+                "incorrect_proposer",
+                BlockError::IncorrectBlockProposer {
+                    block: block.message().proposer_index(),
+                    local_shuffling: expected_proposer as u64,
+                },
+            ));
+        }
+
+        // Apply any local policy regarding the proposer. This is not part of the spec, so we only
+        // consult it once the block is known to be from its claimed proposer.
+        if let Some(proposer_filter) = chain.proposer_filter.as_ref() {
+            let proposer_index = block.message().proposer_index();
+            if !proposer_filter(proposer_index) {
+                return Err(BlockError::ProposerFiltered { proposer_index });
+            }
+        }
+
+        // Limit the number of distinct blocks from each proposer. As with the filter, this is only
+        // checked once the proposer is known, so that a proposer cannot be limited by blocks which
+        // falsely claim to be from them.
+        if let Some(rate_limiter) = chain.proposer_rate_limiter.as_ref() {
+            let proposer_index = block.message().proposer_index();
+            if !rate_limiter.check_and_insert(proposer_index, block_root, chain.slot()?) {
+                return Err(BlockError::ProposerRateLimited { proposer_index });
+            }
         }
 
         // Validate the block's execution_payload (if any).
@@ -908,6 +2316,34 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
     pub fn block_root(&self) -> Hash256 {
         self.block_root
     }
+
+    /// Returns `Some(true)` if the block is the merge transition block (i.e., the first block with
+    /// a non-default execution payload), according to the parent's state.
+    ///
+    /// The parent state is only loaded during gossip verification when the proposer shuffling was
+    /// not cached. If it was not loaded, `None` is returned and the check is deferred until the
+    /// block is fully verified (see `PayloadVerificationOutcome::is_valid_merge_transition_block`).
+    pub fn is_merge_transition_candidate(&self) -> Option<bool> {
+        self.parent
+            .as_ref()
+            .map(|parent| is_merge_transition_block(&parent.pre_state, self.block.message().body()))
+    }
+
+    /// Verify only the proposal signature of the wrapped block.
+    ///
+    /// See `verify_proposal_signature_only` for details.
+    pub fn verify_proposal_signature_only(
+        &mut self,
+        chain: &BeaconChain<T>,
+    ) -> Result<bool, BlockError<T::EthSpec>> {
+        verify_proposal_signature_only(
+            chain,
+            &self.block,
+            self.block_root,
+            &mut self.parent,
+            SnapshotCacheAccess::for_chain(chain, false),
+        )
+    }
 }
 
 impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for GossipVerifiedBlock<T> {
@@ -917,13 +2353,16 @@ impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for GossipVerifiedBlock<T
         block_root: Hash256,
         chain: &Arc<BeaconChain<T>>,
         notify_execution_layer: NotifyExecutionLayer,
-    ) -> Result<ExecutionPendingBlock<T>, BlockSlashInfo<BlockError<T::EthSpec>>> {
+        progress: &VerificationProgress,
+    ) -> Result<ExecutionPendingBlock<T>, BlockSlashInfo<T::EthSpec>> {
+        progress.report(VerificationStage::GossipOk);
         let execution_pending =
             SignatureVerifiedBlock::from_gossip_verified_block_check_slashable(self, chain)?;
         execution_pending.into_execution_pending_block_slashable(
             block_root,
             chain,
             notify_execution_layer,
+            progress,
         )
     }
 
@@ -942,31 +2381,137 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         block_root: Hash256,
         chain: &BeaconChain<T>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::new_with_spec_override(block, block_root, chain, None)
+    }
+
+    /// As for `new`, but the block is verified against `spec_override` rather than `chain.spec`,
+    /// if it is supplied. See `GossipVerifiedBlock::new_with_spec_override`.
+    ///
+    /// When an override is supplied the parent is cloned from the snapshot cache rather than
+    /// removed from it, as for `Self::new_for_dry_run`.
+    pub fn new_with_spec_override(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        chain: &BeaconChain<T>,
+        spec_override: Option<&ChainSpec>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::new_observing_parent(block, block_root, chain, spec_override, &mut |_, _| {})
+    }
+
+    /// As for `new_with_spec_override`, but `on_parent_loaded` is called with the parent once it
+    /// has been loaded, along with `true` if it was read from the snapshot cache.
+    pub(crate) fn new_observing_parent(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        chain: &BeaconChain<T>,
+        spec_override: Option<&ChainSpec>,
+        on_parent_loaded: &mut dyn FnMut(&PreProcessingSnapshot<T::EthSpec>, bool),
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::new_with_snapshot_cache_access(
+            block,
+            block_root,
+            chain,
+            spec_override,
+            SnapshotCacheAccess::for_chain(chain, spec_override.is_some()),
+            on_parent_loaded,
+        )
+    }
+
+    /// As for `new`, but the parent is cloned from the snapshot cache rather than removed from it,
+    /// so that verifying the block leaves the snapshot cache unchanged.
+    ///
+    /// This must be used for blocks which will not be imported, e.g., before
+    /// `Self::into_dry_run_verified_block`.
+    pub fn new_for_dry_run(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::new_with_snapshot_cache_access(
+            block,
+            block_root,
+            chain,
+            None,
+            SnapshotCacheAccess::for_chain(chain, true),
+            &mut |_, _| {},
+        )
+    }
+
+    fn new_with_snapshot_cache_access(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        chain: &BeaconChain<T>,
+        spec_override: Option<&ChainSpec>,
+        snapshot_cache_access: SnapshotCacheAccess,
+        on_parent_loaded: &mut dyn FnMut(&PreProcessingSnapshot<T::EthSpec>, bool),
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        let spec = spec_override.unwrap_or(&chain.spec);
+
         // Ensure the block is the correct structure for the fork at `block.slot()`.
-        block
-            .fork_name(&chain.spec)
-            .map_err(BlockError::InconsistentFork)?;
+        check_block_fork(&block, chain, spec)?;
 
         // Check the anchor slot before loading the parent, to avoid spurious lookups.
         check_block_against_anchor_slot(block.message(), chain)?;
 
-        let (mut parent, block) = load_parent(block_root, block, chain)?;
+        let (parent, block, snapshot_cache_hit) =
+            load_parent_with_source(block_root, block, chain, snapshot_cache_access)?;
+        on_parent_loaded(&parent, snapshot_cache_hit);
+
+        Self::verify_with_parent(block, block_root, parent, chain, spec_override)
+    }
+
+    /// As for `new`, but the block is verified against the block with root `parent_root` (and its
+    /// state) as loaded from the database, rather than the parent selected by fork choice.
+    ///
+    /// This is intended for analysing whether a block would be valid on an alternative fork. Since
+    /// a block commits to its parent root, it is only ever valid with its own parent, however
+    /// verifying against another candidate reveals the resulting error. Returns
+    /// `BlockError::ParentUnknown` if `parent_root` is not in the database.
+    pub fn new_with_parent_root(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        parent_root: Hash256,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        check_block_fork(&block, chain, &chain.spec)?;
+        check_block_against_anchor_slot(block.message(), chain)?;
+
+        let (parent, block) = load_parent_by_root(parent_root, block, chain)?;
+
+        Self::verify_with_parent(block, block_root, parent, chain, None)
+    }
+
+    /// Verifies all of the signatures of `block` against the state of `parent`.
+    fn verify_with_parent(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        mut parent: PreProcessingSnapshot<T::EthSpec>,
+        chain: &BeaconChain<T>,
+        spec_override: Option<&ChainSpec>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        let spec = spec_override.unwrap_or(&chain.spec);
 
+        // States advanced under a `spec_override` must not be cached.
         let state = cheap_state_advance_to_obtain_committees(
             &mut parent.pre_state,
             parent.beacon_state_root,
             block.slot(),
-            &chain.spec,
+            spec_override
+                .is_none()
+                .then_some(&chain.committee_state_cache),
+            spec,
         )?;
 
         let pubkey_cache = get_validator_pubkey_cache(chain)?;
 
-        let mut signature_verifier = get_signature_verifier(&state, &pubkey_cache, &chain.spec);
+        let mut signature_verifier = get_signature_verifier(&state, &pubkey_cache, spec);
 
         let mut consensus_context =
             ConsensusContext::new(block.slot()).set_current_block_root(block_root);
 
-        signature_verifier.include_all_signatures(&block, &mut consensus_context)?;
+        signature_verifier
+            .include_all_signatures(&block, &mut consensus_context)
+            .map_err(|e| signature_verifier_error(&chain.log, block_root, &block, e))?;
 
         if signature_verifier.verify().is_ok() {
             Ok(Self {
@@ -974,10 +2519,59 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
                 block,
                 block_root,
                 parent: Some(parent),
+                applied_attestations: None,
+                observe_proposal: true,
             })
         } else {
-            Err(BlockError::InvalidSignature)
+            let kind = identify_invalid_block_signature(
+                &block,
+                block_root,
+                &mut consensus_context,
+                &state,
+                &pubkey_cache,
+                spec,
+            )
+            .unwrap_or(SignatureKind::Unknown);
+            log_invalid_signature(&chain.log, kind, [(block_root, block.as_ref())]);
+            Err(BlockError::InvalidSignature { kind })
+        }
+    }
+
+    /// Instantiates `Self` from a block whose signatures have *already* been verified by the
+    /// caller, without verifying any signatures.
+    ///
+    /// This allows BLS verification to be offloaded to an external service. The `parent` must be
+    /// the parent of `block` and the `consensus_context` must be for the slot of `block`, with its
+    /// current block root set to `block_root`. The proposer index and indexed attestations may also
+    /// be filled in advance, any which are absent will be computed during import.
+    ///
+    /// Returns `BlockError::NonLinearParentRoots` if the parent root of `block` is not the root of
+    /// `parent`.
+    ///
+    /// ## Warning
+    ///
+    /// This function is a trust boundary. The signatures of `block` are assumed to be valid and
+    /// the contents of `consensus_context` are assumed to be correct, a caller which supplies a
+    /// block with invalid signatures will cause it to be imported. It must never be used for blocks
+    /// which have not been verified by a trusted party, all other paths should use `Self::new`.
+    pub fn from_signatures_verified_externally(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        parent: PreProcessingSnapshot<T::EthSpec>,
+        consensus_context: ConsensusContext<T::EthSpec>,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        if block.parent_root() != parent.beacon_block_root {
+            return Err(BlockError::NonLinearParentRoots);
         }
+
+        Ok(Self {
+            block,
+            block_root,
+            parent: Some(parent),
+            consensus_context,
+            applied_attestations: None,
+            observe_proposal: true,
+        })
     }
 
     /// As for `new` above but producing `BlockSlashInfo`.
@@ -985,9 +2579,10 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
         block_root: Hash256,
         chain: &BeaconChain<T>,
-    ) -> Result<Self, BlockSlashInfo<BlockError<T::EthSpec>>> {
-        let header = block.signed_block_header();
-        Self::new(block, block_root, chain).map_err(|e| BlockSlashInfo::from_early_error(header, e))
+    ) -> Result<Self, BlockSlashInfo<T::EthSpec>> {
+        let slash_block = block.clone();
+        Self::new(block, block_root, chain)
+            .map_err(|e| BlockSlashInfo::from_early_error(slash_block, e))
     }
 
     /// Finishes signature verification on the provided `GossipVerifedBlock`. Does not re-verify
@@ -1006,6 +2601,7 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
             &mut parent.pre_state,
             parent.beacon_state_root,
             block.slot(),
+            Some(&chain.committee_state_cache),
             &chain.spec,
         )?;
 
@@ -1017,7 +2613,8 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         // signature.
         let mut consensus_context = from.consensus_context;
         signature_verifier
-            .include_all_signatures_except_proposal(&block, &mut consensus_context)?;
+            .include_all_signatures_except_proposal(&block, &mut consensus_context)
+            .map_err(|e| signature_verifier_error(&chain.log, from.block_root, &block, e))?;
 
         if signature_verifier.verify().is_ok() {
             Ok(Self {
@@ -1025,9 +2622,21 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
                 block_root: from.block_root,
                 parent: Some(parent),
                 consensus_context,
+                applied_attestations: None,
+                observe_proposal: true,
             })
         } else {
-            Err(BlockError::InvalidSignature)
+            let kind = identify_invalid_block_signature(
+                &block,
+                from.block_root,
+                &mut consensus_context,
+                &state,
+                &pubkey_cache,
+                &chain.spec,
+            )
+            .unwrap_or(SignatureKind::Unknown);
+            log_invalid_signature(&chain.log, kind, [(from.block_root, block.as_ref())]);
+            Err(BlockError::InvalidSignature { kind })
         }
     }
 
@@ -1035,15 +2644,97 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
     pub fn from_gossip_verified_block_check_slashable(
         from: GossipVerifiedBlock<T>,
         chain: &BeaconChain<T>,
-    ) -> Result<Self, BlockSlashInfo<BlockError<T::EthSpec>>> {
-        let header = from.block.signed_block_header();
+    ) -> Result<Self, BlockSlashInfo<T::EthSpec>> {
+        let slash_block = from.block.clone();
         Self::from_gossip_verified_block(from, chain)
-            .map_err(|e| BlockSlashInfo::from_early_error(header, e))
+            .map_err(|e| BlockSlashInfo::from_early_error(slash_block, e))
     }
 
     pub fn block_root(&self) -> Hash256 {
         self.block_root
     }
+
+    pub(crate) fn block_cloned(&self) -> Arc<SignedBeaconBlock<T::EthSpec>> {
+        self.block.clone()
+    }
+
+    /// Verify only the proposal signature of the wrapped block.
+    ///
+    /// See `verify_proposal_signature_only` for details.
+    pub fn verify_proposal_signature_only(
+        &mut self,
+        chain: &BeaconChain<T>,
+    ) -> Result<bool, BlockError<T::EthSpec>> {
+        verify_proposal_signature_only(
+            chain,
+            &self.block,
+            self.block_root,
+            &mut self.parent,
+            SnapshotCacheAccess::for_chain(chain, false),
+        )
+    }
+
+    /// Share `applied_attestations` with other blocks from the same chain segment, so that
+    /// attestations which have already been applied to fork choice by an earlier block are not
+    /// applied again.
+    pub(crate) fn with_applied_attestations(
+        mut self,
+        applied_attestations: Arc<Mutex<AppliedAttestations>>,
+    ) -> Self {
+        self.applied_attestations = Some(applied_attestations);
+        self
+    }
+
+    /// Do not register the proposal with `observed_block_producers` during verification. The
+    /// caller is responsible for observing the proposal if the block is imported, e.g. via
+    /// `ObservedBlockProducers::observe_proposals_batch`.
+    pub(crate) fn without_proposal_observation(mut self) -> Self {
+        self.observe_proposal = false;
+        self
+    }
+
+    /// Fully verifies the wrapped block, without importing it or causing any other side-effects.
+    ///
+    /// In particular, intermediate states are not stored in the database, the proposal is not
+    /// registered with `observed_block_producers` and the block's attestations are not applied to
+    /// fork choice. The EL will still be notified of the block's payload according to
+    /// `notify_execution_layer`.
+    ///
+    /// If the parent has not been loaded already, it is cloned from the snapshot cache. To leave
+    /// the snapshot cache unchanged, `self` should be created by `Self::new_for_dry_run`.
+    pub fn into_dry_run_verified_block(
+        self,
+        chain: &Arc<BeaconChain<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> Result<DryRunVerifiedBlock<T>, BlockError<T::EthSpec>> {
+        let (parent, block) = if let Some(parent) = self.parent {
+            (parent, self.block)
+        } else {
+            load_parent_with_source(
+                self.block_root,
+                self.block,
+                chain,
+                SnapshotCacheAccess::for_chain(chain, true),
+            )
+            .map(|(parent, block, _)| (parent, block))?
+        };
+
+        let (execution_pending, block_reward) = ExecutionPendingBlock::verify_components(
+            block,
+            self.block_root,
+            parent,
+            self.consensus_context,
+            chain,
+            notify_execution_layer,
+            VerifyComponentsOptions {
+                dry_run: true,
+                ..Default::default()
+            },
+            &VerificationProgress::default(),
+        )?;
+
+        DryRunVerifiedBlock::from_dry_run(execution_pending, block_reward)
+    }
 }
 
 impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for SignatureVerifiedBlock<T> {
@@ -1053,7 +2744,9 @@ impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for SignatureVerifiedBloc
         block_root: Hash256,
         chain: &Arc<BeaconChain<T>>,
         notify_execution_layer: NotifyExecutionLayer,
-    ) -> Result<ExecutionPendingBlock<T>, BlockSlashInfo<BlockError<T::EthSpec>>> {
+        progress: &VerificationProgress,
+    ) -> Result<ExecutionPendingBlock<T>, BlockSlashInfo<T::EthSpec>> {
+        progress.report(VerificationStage::SignaturesOk);
         let header = self.block.signed_block_header();
         let (parent, block) = if let Some(parent) = self.parent {
             (parent, self.block)
@@ -1062,14 +2755,21 @@ impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for SignatureVerifiedBloc
                 .map_err(|e| BlockSlashInfo::SignatureValid(header.clone(), e))?
         };
 
-        ExecutionPendingBlock::from_signature_verified_components(
+        ExecutionPendingBlock::verify_components(
             block,
             block_root,
             parent,
             self.consensus_context,
             chain,
             notify_execution_layer,
+            VerifyComponentsOptions {
+                applied_attestations: self.applied_attestations.as_deref(),
+                observe_proposal: self.observe_proposal,
+                ..Default::default()
+            },
+            progress,
         )
+        .map(|(execution_pending, _)| execution_pending)
         .map_err(|e| BlockSlashInfo::SignatureValid(header, e))
     }
 
@@ -1086,13 +2786,19 @@ impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for Arc<SignedBeaconBlock
         block_root: Hash256,
         chain: &Arc<BeaconChain<T>>,
         notify_execution_layer: NotifyExecutionLayer,
-    ) -> Result<ExecutionPendingBlock<T>, BlockSlashInfo<BlockError<T::EthSpec>>> {
+        progress: &VerificationProgress,
+    ) -> Result<ExecutionPendingBlock<T>, BlockSlashInfo<T::EthSpec>> {
         // Perform an early check to prevent wasting time on irrelevant blocks.
-        let block_root = check_block_relevancy(&self, block_root, chain)
-            .map_err(|e| BlockSlashInfo::SignatureNotChecked(self.signed_block_header(), e))?;
+        let block_root = check_block_relevancy(&self, Some(block_root), chain, None)
+            .map_err(|e| BlockSlashInfo::SignatureNotChecked(self.clone(), e))?;
 
         SignatureVerifiedBlock::check_slashable(self, block_root, chain)?
-            .into_execution_pending_block_slashable(block_root, chain, notify_execution_layer)
+            .into_execution_pending_block_slashable(
+                block_root,
+                chain,
+                notify_execution_layer,
+                progress,
+            )
     }
 
     fn block(&self) -> &SignedBeaconBlock<T::EthSpec> {
@@ -1101,58 +2807,272 @@ impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for Arc<SignedBeaconBlock
 }
 
 impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
+    /// Waits for the payload verification to complete and sets `Self::imported_optimistically`
+    /// from its outcome.
+    ///
+    /// The outcome is retained in `Self::payload_verification_handle`, so the block can still be
+    /// imported afterwards. If payload verification fails its error is returned and the block must
+    /// not be imported.
+    pub async fn resolve_payload_verification(mut self) -> Result<Self, BlockError<T::EthSpec>> {
+        let outcome = self.payload_verification_handle.outcome().await?;
+        self.imported_optimistically = outcome.payload_verification_status.is_optimistic();
+        self.payload_verification_handle =
+            PayloadVerificationHandle::Deferred(Box::pin(future::ready(Ok(outcome))));
+        Ok(self)
+    }
+
     /// Instantiates `Self`, a wrapper that indicates that the given `block` is fully valid. See
     /// the struct-level documentation for more information.
     ///
     /// Note: this function does not verify block signatures, it assumes they are valid. Signature
     /// verification must be done upstream (e.g., via a `SignatureVerifiedBlock`
     ///
+    /// If `ChainConfig::verify_consensus_context` is `true`, the `consensus_context` is checked
+    /// against `block` before any processing (see `check_consensus_context`).
+    ///
+    /// If the status of the block's payload is present in `known_payload_statuses`, the EL is not
+    /// notified and the known status is used instead. This is useful when replaying blocks with
+    /// payloads that have already been verified. A known status of
+    /// `PayloadVerificationStatus::Irrelevant` is rejected for payloads that require verification.
+    ///
     /// Returns an error if the block is invalid, or if the block was unable to be verified.
     pub fn from_signature_verified_components(
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
         block_root: Hash256,
         parent: PreProcessingSnapshot<T::EthSpec>,
-        mut consensus_context: ConsensusContext<T::EthSpec>,
+        consensus_context: ConsensusContext<T::EthSpec>,
         chain: &Arc<BeaconChain<T>>,
         notify_execution_layer: NotifyExecutionLayer,
+        known_payload_statuses: Option<&KnownPayloadStatuses>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
-        chain
-            .observed_block_producers
-            .write()
-            .observe_proposal(block_root, block.message())
-            .map_err(|e| BlockError::BeaconChainError(e.into()))?;
+        Self::verify_components(
+            block,
+            block_root,
+            parent,
+            consensus_context,
+            chain,
+            notify_execution_layer,
+            VerifyComponentsOptions {
+                known_payload_statuses,
+                ..Default::default()
+            },
+            &VerificationProgress::default(),
+        )
+        .map(|(execution_pending, _)| execution_pending)
+    }
 
-        if let Some(parent) = chain
-            .canonical_head
-            .fork_choice_read_lock()
-            .get_block(&block.parent_root())
-        {
-            // Reject any block where the parent has an invalid payload. It's impossible for a valid
-            // block to descend from an invalid parent.
-            if parent.execution_status.is_invalid() {
-                return Err(BlockError::ParentExecutionPayloadInvalid {
-                    parent_root: block.parent_root(),
-                });
+    /// As per `Self::from_signature_verified_components`, except that `per_block_processing` is
+    /// run with the given `state_processing_strategy`.
+    ///
+    /// ## Warning
+    ///
+    /// `StateProcessingStrategy::Inconsistent` skips parts of the state transition (e.g.,
+    /// withdrawals), so the post-state may not match the block's `state_root`. The state root is
+    /// still verified, so any block for which the skipped processing changes the post-state is
+    /// rejected with `BlockError::StateRootMismatch`. It must only be used by batch importers which
+    /// know the skipped processing to be irrelevant for their blocks, all live paths must use
+    /// `Self::from_signature_verified_components`.
+    pub fn from_signature_verified_components_with_strategy(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        parent: PreProcessingSnapshot<T::EthSpec>,
+        consensus_context: ConsensusContext<T::EthSpec>,
+        chain: &Arc<BeaconChain<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        state_processing_strategy: StateProcessingStrategy,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::verify_components(
+            block,
+            block_root,
+            parent,
+            consensus_context,
+            chain,
+            notify_execution_layer,
+            VerifyComponentsOptions {
+                state_processing_strategy,
+                ..Default::default()
+            },
+            &VerificationProgress::default(),
+        )
+        .map(|(execution_pending, _)| execution_pending)
+    }
+
+    /// As per `Self::from_signature_verified_components`, except that the execution payload
+    /// verification is not spawned as a task. Instead, it is awaited in place once the state
+    /// transition is complete, so an invalid payload is reported by the returned error. The
+    /// returned `payload_verification_handle` is already resolved and `imported_optimistically`
+    /// is set from its outcome.
+    ///
+    /// This avoids any dependence on the task executor (which fails once the runtime is shutting
+    /// down), making it suitable for tooling which verifies one block at a time. The trade-off is
+    /// that the EL is not consulted in parallel with the state transition and the application of
+    /// the block's attestations to fork choice, so verification takes longer. The live node should
+    /// use `Self::from_signature_verified_components`.
+    pub async fn from_signature_verified_components_inline(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        parent: PreProcessingSnapshot<T::EthSpec>,
+        consensus_context: ConsensusContext<T::EthSpec>,
+        chain: &Arc<BeaconChain<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        let (execution_pending, _) = Self::verify_components(
+            block,
+            block_root,
+            parent,
+            consensus_context,
+            chain,
+            notify_execution_layer,
+            VerifyComponentsOptions {
+                spawn_payload_verification: false,
+                ..Default::default()
+            },
+            &VerificationProgress::default(),
+        )?;
+        execution_pending.resolve_payload_verification().await
+    }
+
+    /// As per `Self::from_signature_verified_components`, except that the block's attestations and
+    /// attester slashings are not applied to fork choice. Instead, they are returned in
+    /// `deferred_fork_choice_updates`.
+    ///
+    /// This avoids taking the fork choice write lock during verification, which is useful for
+    /// tooling which only verifies blocks, or for pipelines which batch their fork choice updates.
+    /// Callers which import the block are responsible for applying the updates (e.g., via
+    /// `DeferredForkChoiceUpdates::apply`), otherwise fork choice will miss the block's votes.
+    pub fn from_signature_verified_components_with_deferred_fork_choice(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        parent: PreProcessingSnapshot<T::EthSpec>,
+        consensus_context: ConsensusContext<T::EthSpec>,
+        chain: &Arc<BeaconChain<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::verify_components(
+            block,
+            block_root,
+            parent,
+            consensus_context,
+            chain,
+            notify_execution_layer,
+            VerifyComponentsOptions {
+                apply_attestations_to_fork_choice: false,
+                ..Default::default()
+            },
+            &VerificationProgress::default(),
+        )
+        .map(|(execution_pending, _)| execution_pending)
+    }
+
+    /// As per `Self::from_signature_verified_components`, except that a mismatch between the
+    /// block's `state_root` and the computed post-state root is logged rather than returned as an
+    /// error.
+    ///
+    /// ## Warning
+    ///
+    /// This is **dangerous**. The returned block can be imported, in which case its post-state is
+    /// stored under a root that does not match its contents. It exists only for explicit tooling
+    /// importing trusted blocks with a known-benign mismatch (e.g., reproducing a historical bug).
+    /// It must never be used for blocks from gossip, RPC or sync.
+    pub fn from_signature_verified_components_unchecked_state_root(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        parent: PreProcessingSnapshot<T::EthSpec>,
+        consensus_context: ConsensusContext<T::EthSpec>,
+        chain: &Arc<BeaconChain<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> Result<Self, BlockError<T::EthSpec>> {
+        Self::verify_components(
+            block,
+            block_root,
+            parent,
+            consensus_context,
+            chain,
+            notify_execution_layer,
+            VerifyComponentsOptions {
+                verify_state_root: VerifyStateRoot::False,
+                ..Default::default()
+            },
+            &VerificationProgress::default(),
+        )
+        .map(|(execution_pending, _)| execution_pending)
+    }
+
+    /// Fully verifies `block`, returning `Self` and the block reward (if it was computed).
+    ///
+    /// See `VerifyComponentsOptions` for the ways in which verification can be modified.
+    ///
+    /// Each `VerificationStage` from `PayloadSubmitted` onwards is reported to `progress`.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_components(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+        parent: PreProcessingSnapshot<T::EthSpec>,
+        mut consensus_context: ConsensusContext<T::EthSpec>,
+        chain: &Arc<BeaconChain<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        options: VerifyComponentsOptions,
+        progress: &VerificationProgress,
+    ) -> Result<(Self, Option<BlockReward>), BlockError<T::EthSpec>> {
+        let VerifyComponentsOptions {
+            known_payload_statuses,
+            dry_run,
+            check_fork_choice,
+            spawn_payload_verification,
+            applied_attestations,
+            observe_proposal,
+            apply_attestations_to_fork_choice,
+            verify_state_root,
+            state_processing_strategy,
+        } = options;
+
+        if chain.config.verify_consensus_context {
+            check_consensus_context(&block, block_root, &consensus_context)?;
+        }
+
+        if !dry_run && observe_proposal {
+            chain
+                .observed_block_producers
+                .write()
+                .observe_proposal(block_root, block.message())
+                .map_err(|e| BlockError::BeaconChainError(e.into()))?;
+        }
+
+        let mut parent_execution_status = None;
+        if check_fork_choice {
+            if let Some(parent) = chain
+                .canonical_head
+                .fork_choice_read_lock()
+                .get_block(&block.parent_root())
+            {
+                // Reject any block where the parent has an invalid payload. It's impossible for a
+                // valid block to descend from an invalid parent.
+                if parent.execution_status.is_invalid() {
+                    return Err(BlockError::ParentExecutionPayloadInvalid {
+                        parent_root: block.parent_root(),
+                    });
+                }
+                parent_execution_status = Some(parent.execution_status);
+            } else {
+                // Reject any block if its parent is not known to fork choice.
+                //
+                // A block that is not in fork choice is either:
+                //
+                //  - Not yet imported: we should reject this block because we should only import a
+                //  child after its parent has been fully imported.
+                //  - Pre-finalized: if the parent block is _prior_ to finalization, we should
+                //  ignore it because it will revert finalization. Note that the finalized block is
+                //  stored in fork choice, so we will not reject any child of the finalized block
+                //  (this is relevant during genesis).
+                return Err(BlockError::ParentUnknown(block));
             }
-        } else {
-            // Reject any block if its parent is not known to fork choice.
-            //
-            // A block that is not in fork choice is either:
-            //
-            //  - Not yet imported: we should reject this block because we should only import a child
-            //  after its parent has been fully imported.
-            //  - Pre-finalized: if the parent block is _prior_ to finalization, we should ignore it
-            //  because it will revert finalization. Note that the finalized block is stored in fork
-            //  choice, so we will not reject any child of the finalized block (this is relevant during
-            //  genesis).
-            return Err(BlockError::ParentUnknown(block));
         }
 
         /*
          *  Perform cursory checks to see if the block is even worth processing.
          */
 
-        check_block_relevancy(&block, block_root, chain)?;
+        let block_root = check_block_relevancy(&block, Some(block_root), chain, None)?;
 
         // Define a future that will verify the execution payload with an execution engine.
         //
@@ -1161,11 +3081,16 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
         let payload_notifier = PayloadNotifier::new(
             chain.clone(),
             block.clone(),
+            block_root,
             &parent.pre_state,
             notify_execution_layer,
+            known_payload_statuses,
         )?;
         let is_valid_merge_transition_block =
             is_merge_transition_block(&parent.pre_state, block.message().body());
+        // The timer includes any time spent waiting for the future to be polled, since this is
+        // time that block import may spend blocked on the payload.
+        let payload_verification_start = Instant::now();
         let payload_verification_future = async move {
             let chain = payload_notifier.chain.clone();
             let block = payload_notifier.block.clone();
@@ -1181,7 +3106,22 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             // - Doing the check here means we can keep our fork-choice implementation "pure". I.e., no
             //   calls to remote servers.
             if is_valid_merge_transition_block {
-                validate_merge_block(&chain, block.message(), AllowOptimisticImport::Yes).await?;
+                if let Err(e) =
+                    validate_merge_block(&chain, block.message(), AllowOptimisticImport::Yes).await
+                {
+                    if !is_execution_layer_offline_error(&chain, &e).await {
+                        return Err(e);
+                    }
+                    warn!(
+                        chain.log,
+                        "Unable to verify merge transition block";
+                        "msg" => "the execution layer must be online to verify the merge \
+                            transition block",
+                        "block_root" => ?block_root,
+                        "error" => ?e,
+                    );
+                    return Err(ExecutionPayloadError::MergeTransitionExecutionLayerOffline.into());
+                }
             };
 
             // The specification declares that this should be run *inside* `per_block_processing`,
@@ -1207,6 +3147,18 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
                         "block_hash" => ?block_hash_opt,
                         "msg" => "the execution engine is not synced"
                     );
+                    metrics::inc_counter(&metrics::BLOCK_PROCESSING_OPTIMISTIC_REJECTIONS);
+                    if let Some(event_handler) = chain.event_handler.as_ref() {
+                        if event_handler.has_optimistic_block_rejected_subscribers() {
+                            event_handler.register(EventKind::OptimisticBlockRejected(
+                                SseOptimisticBlockRejected {
+                                    slot: block.slot(),
+                                    block: block_root,
+                                    execution_block_hash: block_hash_opt.ok(),
+                                },
+                            ));
+                        }
+                    }
                     return Err(ExecutionPayloadError::UnverifiedNonOptimisticCandidate.into());
                 }
             }
@@ -1216,16 +3168,38 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
                 is_valid_merge_transition_block,
             })
         };
-        // Spawn the payload verification future as a new task, but don't wait for it to complete.
-        // The `payload_verification_future` will be awaited later to ensure verification completed
-        // successfully.
-        let payload_verification_handle = chain
-            .task_executor
-            .spawn_handle(
-                payload_verification_future,
-                "execution_payload_verification",
-            )
-            .ok_or(BeaconChainError::RuntimeShutdown)?;
+        // Time the verification on every exit path, labelled by whether it succeeded.
+        let payload_verification_future = async move {
+            let result = payload_verification_future.await;
+            let outcome = if result.is_ok() { "success" } else { "failure" };
+            metrics::observe_timer_vec(
+                &metrics::BLOCK_PROCESSING_PAYLOAD_VERIFICATION_TIMES,
+                &[outcome],
+                payload_verification_start.elapsed(),
+            );
+            result
+        };
+        // Unless verification is deferred, spawn the payload verification future as a new task, but
+        // don't wait for it to complete. The `payload_verification_handle` will be awaited later to
+        // ensure verification completed successfully.
+        //
+        // This happens before any intermediate states are stored, so failing to spawn due to
+        // shutdown leaves nothing in the database. See
+        // `ExecutionPendingBlock::confirmed_state_roots` for the states which are left behind if
+        // verification is aborted later.
+        let payload_verification_handle = if spawn_payload_verification {
+            let handle = chain
+                .task_executor
+                .spawn_handle(
+                    payload_verification_future,
+                    "execution_payload_verification",
+                )
+                .ok_or(BeaconChainError::RuntimeShutdown)?;
+            PayloadVerificationHandle::Spawned(handle)
+        } else {
+            PayloadVerificationHandle::Deferred(Box::pin(payload_verification_future))
+        };
+        progress.report(VerificationStage::PayloadSubmitted);
 
         /*
          * Advance the given `parent.beacon_state` to the slot of the given `block`.
@@ -1245,6 +3219,9 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             });
         }
 
+        // Refuse to advance the parent state through an excessive number of slots.
+        check_block_skip_slots(chain, parent.beacon_block.slot(), block.slot())?;
+
         let mut summaries = vec![];
 
         // Transition the parent state to the block slot.
@@ -1271,7 +3248,13 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             eth1_deposit_index: state.eth1_deposit_index(),
         };
 
+        let skipped_slots = block
+            .slot()
+            .as_u64()
+            .saturating_sub(parent_slot.as_u64())
+            .saturating_sub(1);
         let distance = block.slot().as_u64().saturating_sub(state.slot().as_u64());
+        let mut intermediate_state_roots = vec![];
         for _ in 0..distance {
             let state_root = if parent.beacon_block.slot() == state.slot() {
                 // If it happens that `pre_state` has *not* already been advanced forward a single
@@ -1284,41 +3267,65 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
                 // Computing the state root here is time-equivalent to computing it during slot
                 // processing, but we get early access to it.
                 let state_root = state.update_tree_hash_cache()?;
-
-                // Store the state immediately, marking it as temporary, and staging the deletion
-                // of its temporary status as part of the larger atomic operation.
-                let txn_lock = chain.store.hot_db.begin_rw_transaction();
-                let state_already_exists =
-                    chain.store.load_hot_state_summary(&state_root)?.is_some();
-
-                let state_batch = if state_already_exists {
-                    // If the state exists, it could be temporary or permanent, but in neither case
-                    // should we rewrite it or store a new temporary flag for it. We *will* stage
-                    // the temporary flag for deletion because it's OK to double-delete the flag,
-                    // and we don't mind if another thread gets there first.
-                    vec![]
-                } else {
-                    vec![
-                        if state.slot() % T::EthSpec::slots_per_epoch() == 0 {
-                            StoreOp::PutState(state_root, &state)
-                        } else {
-                            StoreOp::PutStateSummary(
-                                state_root,
-                                HotStateSummary::new(&state_root, &state)?,
-                            )
-                        },
-                        StoreOp::PutStateTemporaryFlag(state_root),
-                    ]
-                };
-                chain.store.do_atomically(state_batch)?;
-                drop(txn_lock);
-
-                confirmed_state_roots.push(state_root);
+                intermediate_state_roots.push(state_root);
+
+                // A dry run must not write to the database.
+                if !dry_run {
+                    // Store the state immediately, marking it as temporary, and staging the
+                    // deletion of its temporary status as part of the larger atomic operation.
+                    let txn_lock = chain.store.hot_db.begin_rw_transaction();
+                    let state_already_exists =
+                        chain.store.load_hot_state_summary(&state_root)?.is_some();
+
+                    let state_batch = if state_already_exists {
+                        // If the state exists, it could be temporary or permanent, but in neither
+                        // case should we rewrite it or store a new temporary flag for it. We *will*
+                        // stage the temporary flag for deletion because it's OK to double-delete
+                        // the flag, and we don't mind if another thread gets there first.
+                        vec![]
+                    } else {
+                        vec![
+                            if state.slot() % T::EthSpec::slots_per_epoch() == 0 {
+                                StoreOp::PutState(state_root, &state)
+                            } else {
+                                StoreOp::PutStateSummary(
+                                    state_root,
+                                    HotStateSummary::new(&state_root, &state)?,
+                                )
+                            },
+                            StoreOp::PutStateTemporaryFlag(state_root),
+                        ]
+                    };
+                    chain.store.do_atomically(state_batch)?;
+                    drop(txn_lock);
+
+                    confirmed_state_roots.push(state_root);
+                }
 
                 state_root
             };
 
-            if let Some(summary) = per_slot_processing(&mut state, Some(state_root), &chain.spec)? {
+            let slot = state.slot();
+            let summary = match per_slot_processing(&mut state, Some(state_root), &chain.spec) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    metrics::stop_timer(catchup_timer);
+                    error!(
+                        chain.log,
+                        "Failed to advance parent state";
+                        "error" => ?e,
+                        "slot" => slot,
+                        "block_slot" => block.slot(),
+                        "block_root" => ?block_root,
+                    );
+                    return Err(BlockError::SlotProcessingFailed {
+                        slot,
+                        block_root,
+                        source: e,
+                    });
+                }
+            };
+            if let Some(summary) = summary {
                 // Expose Prometheus metrics.
                 if let Err(e) = summary.observe_metrics() {
                     error!(
@@ -1332,12 +3339,13 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             }
         }
         metrics::stop_timer(catchup_timer);
+        progress.report(VerificationStage::CatchupDone);
 
         let block_slot = block.slot();
         let state_current_epoch = state.current_epoch();
 
         // If the block is sufficiently recent, notify the validator monitor.
-        if let Some(slot) = chain.slot_clock.now() {
+        if let Some(slot) = chain.slot_clock.now().filter(|_| !dry_run) {
             let epoch = slot.epoch(T::EthSpec::slots_per_epoch());
             if block_slot.epoch(T::EthSpec::slots_per_epoch())
                 + VALIDATOR_MONITOR_HISTORIC_EPOCHS as u64
@@ -1368,27 +3376,46 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
 
         let committee_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_COMMITTEE);
 
-        state.build_committee_cache(RelativeEpoch::Previous, &chain.spec)?;
-        state.build_committee_cache(RelativeEpoch::Current, &chain.spec)?;
+        for relative_epoch in [RelativeEpoch::Previous, RelativeEpoch::Current] {
+            // `build_committee_cache` does not rebuild a cache which is already initialized for the
+            // epoch (e.g., by the snapshot cache or an earlier verification stage), the check here
+            // is only to record how often the existing cache is reused.
+            let outcome = if state.committee_cache_is_initialized(relative_epoch) {
+                "reused"
+            } else {
+                "built"
+            };
+            metrics::inc_counter_vec(
+                &metrics::BLOCK_PROCESSING_COMMITTEE_CACHE_BUILDS,
+                &[relative_epoch_label(relative_epoch), outcome],
+            );
+            state.build_committee_cache(relative_epoch, &chain.spec)?;
+        }
 
         metrics::stop_timer(committee_timer);
 
         /*
          * If we have block reward listeners, compute the block reward and push it to the
-         * event handler.
+         * event handler. A dry run always computes the reward, but never registers an event.
          */
-        if let Some(ref event_handler) = chain.event_handler {
-            if event_handler.has_block_reward_subscribers() {
-                let mut reward_cache = Default::default();
-                let block_reward = chain.compute_block_reward(
-                    block.message(),
-                    block_root,
-                    &state,
-                    &mut reward_cache,
-                    true,
-                )?;
-                event_handler.register(EventKind::BlockReward(block_reward));
-            }
+        let event_handler = chain
+            .event_handler
+            .as_ref()
+            .filter(|event_handler| !dry_run && event_handler.has_block_reward_subscribers());
+        let block_reward = if dry_run || event_handler.is_some() {
+            let mut reward_cache = Default::default();
+            Some(chain.compute_block_reward(
+                block.message(),
+                block_root,
+                &state,
+                &mut reward_cache,
+                true,
+            )?)
+        } else {
+            None
+        };
+        if let (Some(event_handler), Some(block_reward)) = (event_handler, &block_reward) {
+            event_handler.register(EventKind::BlockReward(block_reward.clone()));
         }
 
         /*
@@ -1396,12 +3423,26 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
          * invalid.
          */
 
-        write_state(
-            &format!("state_pre_block_{}", block_root),
-            &state,
-            &chain.log,
-        );
-        write_block(&block, block_root, &chain.log);
+        let ssz_dir = block_processing_ssz_dir(chain);
+        if let Some(dir) = &ssz_dir {
+            write_state(
+                dir,
+                &format!(
+                    "state_pre_block_{}_parent_{}",
+                    block_root,
+                    block.parent_root()
+                ),
+                &state,
+                &chain.log,
+            );
+            write_block(dir, &block, block_root, &chain.log);
+        }
+
+        // The caches are not required for diffing, so they're not cloned.
+        let pre_block_state = chain
+            .config
+            .retain_pre_block_state
+            .then(|| state.clone_with(CloneConfig::none()));
 
         let core_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_CORE);
 
@@ -1410,7 +3451,7 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             &block,
             // Signatures were verified earlier in this function.
             BlockSignatureStrategy::NoVerification,
-            StateProcessingStrategy::Accurate,
+            state_processing_strategy,
             VerifyBlockRoot::True,
             &mut consensus_context,
             &chain.spec,
@@ -1424,6 +3465,7 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
         };
 
         metrics::stop_timer(core_timer);
+        progress.report(VerificationStage::PerBlockDone);
 
         /*
          * Calculate the state root of the newly modified state
@@ -1435,61 +3477,131 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
 
         metrics::stop_timer(state_root_timer);
 
-        write_state(
-            &format!("state_post_block_{}", block_root),
-            &state,
-            &chain.log,
-        );
+        if let Some(dir) = &ssz_dir {
+            write_state(
+                dir,
+                &format!(
+                    "state_post_block_{}_parent_{}",
+                    block_root,
+                    block.parent_root()
+                ),
+                &state,
+                &chain.log,
+            );
+        }
 
         /*
          * Check to ensure the state root on the block matches the one we have calculated.
          */
 
+        if chain.config.detect_known_state_roots && block.state_root() != state_root {
+            warn_on_known_state_root(chain, &block, block_root);
+        }
+
         if block.state_root() != state_root {
-            return Err(BlockError::StateRootMismatch {
-                block: block.state_root(),
-                local: state_root,
-            });
+            match verify_state_root {
+                VerifyStateRoot::True => {
+                    return Err(BlockError::StateRootMismatch {
+                        block: block.state_root(),
+                        local: state_root,
+                    });
+                }
+                VerifyStateRoot::False => warn!(
+                    chain.log,
+                    "Ignoring block state root mismatch";
+                    "block_root" => ?block_root,
+                    "block_state_root" => ?block.state_root(),
+                    "local_state_root" => ?state_root,
+                ),
+            }
         }
+        progress.report(VerificationStage::StateRootOk);
 
         /*
          * Apply the block's attestations to fork choice.
          *
          * We're running in parallel with the payload verification at this point, so this is
          * free real estate.
+         *
+         * A dry run must not modify fork choice.
+         *
+         * If the caller has requested it, collect the updates rather than applying them. Since
+         * nothing is applied, `applied_attestations` is not consulted.
          */
-        let current_slot = chain.slot()?;
-        let mut fork_choice = chain.canonical_head.fork_choice_write_lock();
+        let mut deferred_fork_choice_updates = None;
+        let mut fork_choice_attestations_applied = 0;
+        let mut attester_slashings_applied = 0;
+        if !dry_run && !apply_attestations_to_fork_choice {
+            let indexed_attestations = block
+                .message()
+                .body()
+                .attestations()
+                .iter()
+                .enumerate()
+                .map(|(i, attestation)| {
+                    consensus_context
+                        .get_indexed_attestation(&state, attestation)
+                        .cloned()
+                        .map_err(|e| BlockError::PerBlockProcessingError(e.into_with_index(i)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            deferred_fork_choice_updates = Some(DeferredForkChoiceUpdates {
+                attester_slashings: block.message().body().attester_slashings().to_vec(),
+                indexed_attestations,
+            });
+        } else if !dry_run {
+            let current_slot = chain.slot()?;
+            let mut fork_choice = chain.canonical_head.fork_choice_write_lock();
+
+            // Register each attester slashing in the block with fork choice.
+            for attester_slashing in block.message().body().attester_slashings() {
+                fork_choice.on_attester_slashing(attester_slashing);
+                attester_slashings_applied += 1;
+            }
 
-        // Register each attester slashing in the block with fork choice.
-        for attester_slashing in block.message().body().attester_slashings() {
-            fork_choice.on_attester_slashing(attester_slashing);
-        }
+            let mut applied_attestations = applied_attestations.map(|applied| applied.lock());
 
-        // Register each attestation in the block with fork choice.
-        for (i, attestation) in block.message().body().attestations().iter().enumerate() {
-            let _fork_choice_attestation_timer =
-                metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_ATTESTATION_TIMES);
+            // Register each attestation in the block with fork choice.
+            for (i, attestation) in block.message().body().attestations().iter().enumerate() {
+                let _fork_choice_attestation_timer =
+                    metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_ATTESTATION_TIMES);
 
-            let indexed_attestation = consensus_context
-                .get_indexed_attestation(&state, attestation)
-                .map_err(|e| BlockError::PerBlockProcessingError(e.into_with_index(i)))?;
+                let indexed_attestation = consensus_context
+                    .get_indexed_attestation(&state, attestation)
+                    .map_err(|e| BlockError::PerBlockProcessingError(e.into_with_index(i)))?;
 
-            match fork_choice.on_attestation(
-                current_slot,
-                indexed_attestation,
-                AttestationFromBlock::True,
-            ) {
-                Ok(()) => Ok(()),
-                // Ignore invalid attestations whilst importing attestations from a block. The
-                // block might be very old and therefore the attestations useless to fork choice.
-                Err(ForkChoiceError::InvalidAttestation(_)) => Ok(()),
-                Err(e) => Err(BlockError::BeaconChainError(e.into())),
-            }?;
+                // Skip attestations whose votes have all been applied by an earlier block.
+                if applied_attestations
+                    .as_ref()
+                    .map_or(false, |applied| applied.is_redundant(indexed_attestation))
+                {
+                    metrics::inc_counter(&metrics::FORK_CHOICE_REDUNDANT_BLOCK_ATTESTATIONS);
+                    continue;
+                }
+
+                match fork_choice.on_attestation(
+                    current_slot,
+                    indexed_attestation,
+                    AttestationFromBlock::True,
+                ) {
+                    Ok(()) => {
+                        if let Some(applied) = applied_attestations.as_mut() {
+                            applied.insert(indexed_attestation);
+                        }
+                        fork_choice_attestations_applied += 1;
+                        Ok(())
+                    }
+                    // Ignore invalid attestations whilst importing attestations from a block.
+                    // The block might be very old and therefore the attestations useless to fork
+                    // choice.
+                    Err(ForkChoiceError::InvalidAttestation(_)) => Ok(()),
+                    Err(e) => Err(BlockError::BeaconChainError(e.into())),
+                }?;
+            }
+            drop(fork_choice);
         }
-        drop(fork_choice);
 
-        Ok(Self {
+        let execution_pending = Self {
             block,
             block_root,
             state,
@@ -1498,8 +3610,112 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             confirmed_state_roots,
             consensus_context,
             payload_verification_handle,
-        })
+            skipped_slots,
+            intermediate_state_roots,
+            parent_execution_status,
+            pre_block_state,
+            deferred_fork_choice_updates,
+            fork_choice_attestations_applied,
+            attester_slashings_applied,
+            imported_optimistically: false,
+        };
+
+        if let Some(hook) = chain.on_execution_pending.as_ref().filter(|_| !dry_run) {
+            hook(&execution_pending);
+        }
+
+        Ok((execution_pending, block_reward))
+    }
+}
+
+/// Returns an error if the structure of `block` is not that of the fork which `spec` schedules at
+/// the block's slot.
+///
+/// If this happens for every block, it is likely that our fork schedule differs from that of the
+/// network, so the mismatch is logged in detail.
+fn check_block_fork<T: BeaconChainTypes>(
+    block: &SignedBeaconBlock<T::EthSpec>,
+    chain: &BeaconChain<T>,
+    spec: &ChainSpec,
+) -> Result<(), BlockError<T::EthSpec>> {
+    block.fork_name(spec).map(|_| ()).map_err(|e| {
+        debug!(
+            chain.log,
+            "Block structure is inconsistent with the fork schedule";
+            "msg" => "if this occurs for all blocks, check the fork schedule is configured correctly",
+            "slot" => block.slot(),
+            "block_fork" => %e.object_fork,
+            "expected_fork" => %e.fork_at_slot,
+        );
+        BlockError::InconsistentFork(e)
+    })
+}
+
+/// Returns an error if `block` is from a slot later than the present slot, after allowing for
+/// `tolerance` of clock disparity.
+fn check_block_against_present_slot<T: BeaconChainTypes>(
+    block: BeaconBlockRef<'_, T::EthSpec>,
+    tolerance: Duration,
+    chain: &BeaconChain<T>,
+) -> Result<(), BlockError<T::EthSpec>> {
+    let present_slot = present_slot_override(chain)
+        .or_else(|| chain.slot_clock.now_with_future_tolerance(tolerance))
+        .ok_or(BeaconChainError::UnableToReadSlot)?;
+    if block.slot() > present_slot {
+        return Err(BlockError::FutureSlot {
+            present_slot,
+            block_slot: block.slot(),
+            tolerance,
+        });
+    }
+    Ok(())
+}
+
+/// Returns the metrics label for `relative_epoch`.
+fn relative_epoch_label(relative_epoch: RelativeEpoch) -> &'static str {
+    match relative_epoch {
+        RelativeEpoch::Previous => "previous",
+        RelativeEpoch::Current => "current",
+        RelativeEpoch::Next => "next",
+    }
+}
+
+/// Returns `ChainConfig::present_slot_override`, which only exists in tests.
+#[cfg(test)]
+fn present_slot_override<T: BeaconChainTypes>(chain: &BeaconChain<T>) -> Option<Slot> {
+    chain.config.present_slot_override
+}
+
+#[cfg(not(test))]
+fn present_slot_override<T: BeaconChainTypes>(_chain: &BeaconChain<T>) -> Option<Slot> {
+    None
+}
+
+/// Returns `Ok(())` if the number of slots skipped between a block at `block_slot` and its parent
+/// at `parent_slot` does not exceed `ChainConfig::block_max_skip_slots`.
+///
+/// This is not part of the specification, it protects us from blocks which would require the
+/// parent state to be advanced through a very large number of slots.
+fn check_block_skip_slots<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    parent_slot: Slot,
+    block_slot: Slot,
+) -> Result<(), BlockError<T::EthSpec>> {
+    if let Some(max) = chain.config.block_max_skip_slots {
+        // The slots strictly between the parent and the block are skipped.
+        let skipped_slots = block_slot
+            .as_u64()
+            .saturating_sub(parent_slot.as_u64())
+            .saturating_sub(1);
+        if skipped_slots > max {
+            return Err(BlockError::TooManySkipSlots {
+                block_slot,
+                parent_slot,
+                max,
+            });
+        }
     }
+    Ok(())
 }
 
 /// Returns `Ok(())` if the block's slot is greater than the anchor block's slot (if any).
@@ -1515,34 +3731,64 @@ fn check_block_against_anchor_slot<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Determines which blocks rejected by `check_block_against_finalized_slot` are recorded in the
+/// pre-finalization block cache (see `BeaconChain::pre_finalization_block_rejected`).
+#[derive(Clone, Copy)]
+enum PreFinalizationRejection {
+    /// Always record the block. Used for gossip blocks, which attestations are likely to reference.
+    Record,
+    /// Only record the block if it is the subject of an in-progress lookup. Used by relevancy
+    /// checks, which see many old blocks during sync.
+    RecordLookups,
+}
+
 /// Returns `Ok(())` if the block is later than the finalized slot on `chain`.
 ///
+/// If `finalized_checkpoint` is supplied it is used in place of the finalized checkpoint of
+/// `chain`, in which case a rejected block is not recorded in the pre-finalization caches.
+///
 /// Returns an error if the block is earlier or equal to the finalized slot, or there was an error
-/// verifying that condition.
+/// verifying that condition. The finalized block itself (when it is at the finalized slot) is
+/// reported as `BlockAtFinalizedSlot`, rather than `WouldRevertFinalizedSlot`, since it is already
+/// known rather than conflicting with finality.
 fn check_block_against_finalized_slot<T: BeaconChainTypes>(
     block: BeaconBlockRef<'_, T::EthSpec>,
     block_root: Hash256,
     chain: &BeaconChain<T>,
+    rejection: PreFinalizationRejection,
+    finalized_checkpoint: Option<Checkpoint>,
 ) -> Result<(), BlockError<T::EthSpec>> {
     // The finalized checkpoint is being read from fork choice, rather than the cached head.
     //
     // Fork choice has the most up-to-date view of finalization and there's no point importing a
     // block which conflicts with the fork-choice view of finalization.
-    let finalized_slot = chain
-        .canonical_head
-        .cached_head()
-        .finalized_checkpoint()
-        .epoch
-        .start_slot(T::EthSpec::slots_per_epoch());
+    let checkpoint = finalized_checkpoint
+        .unwrap_or_else(|| chain.canonical_head.cached_head().finalized_checkpoint());
+    let finalized_slot = checkpoint.epoch.start_slot(T::EthSpec::slots_per_epoch());
 
-    if block.slot() <= finalized_slot {
-        chain.pre_finalization_block_rejected(block_root);
+    if block.slot() > finalized_slot {
+        Ok(())
+    } else if block.slot() == finalized_slot && block_root == checkpoint.root {
+        Err(BlockError::BlockAtFinalizedSlot {
+            block_root,
+            block_slot: block.slot(),
+        })
+    } else {
+        // The pre-finalization caches reflect the finalization of `chain`, not the override.
+        if finalized_checkpoint.is_none() {
+            match rejection {
+                PreFinalizationRejection::Record => {
+                    chain.pre_finalization_block_rejected(block_root)
+                }
+                PreFinalizationRejection::RecordLookups => {
+                    chain.pre_finalization_lookup_rejected(block_root)
+                }
+            }
+        }
         Err(BlockError::WouldRevertFinalizedSlot {
             block_slot: block.slot(),
             finalized_slot,
         })
-    } else {
-        Ok(())
     }
 }
 
@@ -1583,25 +3829,30 @@ pub fn check_block_is_finalized_checkpoint_or_descendant<T: BeaconChainTypes>(
 
 /// Performs simple, cheap checks to ensure that the block is relevant to be imported.
 ///
+/// If `block_root` is `None`, the root will be computed from `signed_block`.
+///
 /// `Ok(block_root)` is returned if the block passes these checks and should progress with
-/// verification (viz., it is relevant).
+/// verification (viz., it is relevant). The returned root is the one used during the checks and
+/// should be used by the caller in place of any root it supplied.
+///
+/// If `finalized_checkpoint` is supplied, the block is checked against it rather than the finalized
+/// checkpoint of `chain`. This allows simulating whether the block would be relevant at another
+/// point of finalization.
 ///
 /// Returns an error if the block fails one of these checks (viz., is not relevant) or an error is
 /// experienced whilst attempting to verify.
 pub fn check_block_relevancy<T: BeaconChainTypes>(
     signed_block: &SignedBeaconBlock<T::EthSpec>,
-    block_root: Hash256,
+    block_root: Option<Hash256>,
     chain: &BeaconChain<T>,
+    finalized_checkpoint: Option<Checkpoint>,
 ) -> Result<Hash256, BlockError<T::EthSpec>> {
     let block = signed_block.message();
+    let block_root = block_root.unwrap_or_else(|| get_block_root(signed_block));
 
     // Do not process blocks from the future.
-    if block.slot() > chain.slot()? {
-        return Err(BlockError::FutureSlot {
-            present_slot: chain.slot()?,
-            block_slot: block.slot(),
-        });
-    }
+    let tolerance = chain.config.future_block_tolerance.unwrap_or_default();
+    check_block_against_present_slot(block, tolerance, chain)?;
 
     // Do not re-process the genesis block.
     if block.slot() == 0 {
@@ -1615,7 +3866,13 @@ pub fn check_block_relevancy<T: BeaconChainTypes>(
     }
 
     // Do not process a block from a finalized slot.
-    check_block_against_finalized_slot(block, block_root, chain)?;
+    check_block_against_finalized_slot(
+        block,
+        block_root,
+        chain,
+        PreFinalizationRejection::RecordLookups,
+        finalized_checkpoint,
+    )?;
 
     // Check if the block is already known. We know it is post-finalization, so it is
     // sufficient to check the fork choice.
@@ -1633,14 +3890,388 @@ pub fn check_block_relevancy<T: BeaconChainTypes>(
 /// Returns the canonical root of the given `block`.
 ///
 /// Use this function to ensure that we report the block hashing time Prometheus metric.
-pub fn get_block_root<E: EthSpec>(block: &SignedBeaconBlock<E>) -> Hash256 {
+pub fn get_block_root<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    block: &SignedBeaconBlock<E, Payload>,
+) -> Hash256 {
     let block_root_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_BLOCK_ROOT);
 
-    let block_root = block.canonical_root();
+    let block_root = block.canonical_root();
+
+    metrics::stop_timer(block_root_timer);
+
+    block_root
+}
+
+/// Returns the index of the proposer expected to have produced `block`, see
+/// `BeaconChain::expected_proposer_for_block`.
+///
+/// If the parent must be loaded, it is cloned from the snapshot cache rather than removed, so that
+/// it remains available for the import of `block` (or its siblings).
+pub(crate) fn expected_proposer_for_block<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &SignedBeaconBlock<T::EthSpec>,
+) -> Result<u64, BlockError<T::EthSpec>> {
+    let parent_block = chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&block.parent_root())
+        .ok_or_else(|| BlockError::ParentUnknown(Arc::new(block.clone())))?;
+
+    let proposer_shuffling_decision_block =
+        proposer_shuffling_decision_root::<T::EthSpec>(&parent_block, block.slot());
+    let proposer_opt = chain
+        .beacon_proposer_cache
+        .lock()
+        .get_slot::<T::EthSpec>(proposer_shuffling_decision_block, block.slot());
+    if let Some(proposer) = proposer_opt {
+        return Ok(proposer.index as u64);
+    }
+
+    // The block is only cloned if the parent state is required to compute the shuffling.
+    let (proposer_index, _, _, _) = expected_proposer(
+        chain,
+        Arc::new(block.clone()),
+        block.canonical_root(),
+        &parent_block,
+        None,
+        SnapshotCacheAccess::for_chain(chain, true),
+    )?;
+    Ok(proposer_index as u64)
+}
+
+/// Returns the index of the proposer expected to have produced `block` (according to the proposer
+/// shuffling), along with the fork used to verify its proposal signature.
+///
+/// The proposer is read from the `beacon_proposer_cache` if possible. Otherwise the parent is
+/// loaded in order to compute the shuffling, the parent snapshot is returned and the cache is
+/// primed. When a `spec_override` is supplied the caches are neither read nor primed.
+///
+/// The snapshot cache is used to load the parent according to `snapshot_cache_access`.
+#[allow(clippy::type_complexity)]
+fn expected_proposer<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    block_root: Hash256,
+    parent_block: &ProtoBlock,
+    spec_override: Option<&ChainSpec>,
+    snapshot_cache_access: SnapshotCacheAccess,
+) -> Result<
+    (
+        usize,
+        Fork,
+        Option<PreProcessingSnapshot<T::EthSpec>>,
+        Arc<SignedBeaconBlock<T::EthSpec>>,
+    ),
+    BlockError<T::EthSpec>,
+> {
+    let spec = spec_override.unwrap_or(&chain.spec);
+    let block_epoch = block.slot().epoch(T::EthSpec::slots_per_epoch());
+
+    let proposer_shuffling_decision_block =
+        proposer_shuffling_decision_root::<T::EthSpec>(parent_block, block.slot());
+
+    // We assign to a variable instead of using `if let Some` directly to ensure we drop the
+    // write lock before trying to acquire it again in the `else` clause.
+    let proposer_cache_lookup_timer =
+        metrics::start_timer(&metrics::GOSSIP_BEACON_BLOCK_PROPOSER_CACHE_LOOKUP);
+    // The cached proposers (and their fork) were computed under `chain.spec`.
+    let proposer_opt = if spec_override.is_none() {
+        chain
+            .beacon_proposer_cache
+            .lock()
+            .get_slot::<T::EthSpec>(proposer_shuffling_decision_block, block.slot())
+    } else {
+        None
+    };
+    metrics::stop_timer(proposer_cache_lookup_timer);
+    if let Some(proposer) = proposer_opt {
+        // The proposer index was cached and we can return it without needing to load the
+        // parent.
+        Ok((proposer.index, proposer.fork, None, block))
+    } else {
+        // The proposer index was *not* cached and we must load the parent in order to determine
+        // the proposer index.
+        let _proposer_cache_miss_timer =
+            metrics::start_timer(&metrics::GOSSIP_BEACON_BLOCK_PROPOSER_CACHE_MISS);
+        let (mut parent, block, _) =
+            load_parent_with_source(block_root, block, chain, snapshot_cache_access)?;
+
+        debug!(
+            chain.log,
+            "Proposer shuffling cache miss";
+            "parent_root" => ?parent.beacon_block_root,
+            "parent_slot" => parent.beacon_block.slot(),
+            "block_root" => ?block_root,
+            "block_slot" => block.slot(),
+        );
+
+        // The state produced is only valid for determining proposer/attester shuffling indices.
+        //
+        // States advanced under a `spec_override` must not be cached.
+        let state = cheap_state_advance_to_obtain_committees(
+            &mut parent.pre_state,
+            parent.beacon_state_root,
+            block.slot(),
+            spec_override
+                .is_none()
+                .then_some(&chain.committee_state_cache),
+            spec,
+        )?;
+
+        let proposers = state.get_beacon_proposer_indices(spec)?;
+        let proposer_index = *proposers
+            .get(block.slot().as_usize() % T::EthSpec::slots_per_epoch() as usize)
+            .ok_or_else(|| BeaconChainError::NoProposerForSlot(block.slot()))?;
+
+        // Prime the proposer shuffling cache with the newly-learned value.
+        if spec_override.is_none() {
+            chain.beacon_proposer_cache.lock().insert(
+                block_epoch,
+                proposer_shuffling_decision_block,
+                proposers,
+                state.fork(),
+            )?;
+        }
+
+        Ok((proposer_index, state.fork(), Some(parent), block))
+    }
+}
+
+/// Re-derives the proposer of `block` from its parent state and logs an error if it disagrees with
+/// the `cached_proposer` read from the `beacon_proposer_cache`.
+///
+/// This distinguishes a block from the wrong proposer from a bug in the proposer cache, which would
+/// cause us to reject valid blocks. The parent state is read from the database rather than the
+/// snapshot cache so that the check has no side-effects. It is purely diagnostic, so any error is
+/// logged rather than returned.
+fn recheck_cached_proposer<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &SignedBeaconBlock<T::EthSpec>,
+    block_root: Hash256,
+    cached_proposer: usize,
+    spec: &ChainSpec,
+) {
+    let compute_proposer = || -> Result<usize, BlockError<T::EthSpec>> {
+        let parent_root = block.parent_root();
+        let parent_block = chain
+            .get_blinded_block(&parent_root)?
+            .ok_or(BeaconChainError::MissingBeaconBlock(parent_root))?;
+        let (proposers, _) = proposers_from_parent_state(
+            chain,
+            parent_root,
+            parent_block.state_root(),
+            block.slot(),
+            None,
+            spec,
+        )?;
+        proposers
+            .get(block.slot().as_usize() % T::EthSpec::slots_per_epoch() as usize)
+            .copied()
+            .ok_or_else(|| BeaconChainError::NoProposerForSlot(block.slot()).into())
+    };
+
+    let outcome = match compute_proposer() {
+        Ok(proposer) if proposer == cached_proposer => {
+            debug!(
+                chain.log,
+                "Confirmed cached proposer for incorrect proposer";
+                "block_root" => ?block_root,
+                "block_slot" => block.slot(),
+                "block_proposer" => block.message().proposer_index(),
+                "cached_proposer" => cached_proposer,
+            );
+            "confirmed"
+        }
+        Ok(proposer) => {
+            error!(
+                chain.log,
+                "Proposer cache is inconsistent with the parent state";
+                "msg" => "valid blocks may be rejected, please report this issue",
+                "block_root" => ?block_root,
+                "block_slot" => block.slot(),
+                "block_proposer" => block.message().proposer_index(),
+                "cached_proposer" => cached_proposer,
+                "computed_proposer" => proposer,
+            );
+            "inconsistent"
+        }
+        Err(e) => {
+            warn!(
+                chain.log,
+                "Unable to recheck cached proposer";
+                "block_root" => ?block_root,
+                "block_slot" => block.slot(),
+                "error" => ?e,
+            );
+            "error"
+        }
+    };
+    metrics::inc_counter_vec(&metrics::GOSSIP_BEACON_BLOCK_PROPOSER_RECHECKS, &[outcome]);
+}
+
+/// Counts the rejection of a gossip block by `check` in `GOSSIP_BEACON_BLOCK_CHECK_REJECTIONS`,
+/// returning `error`.
+///
+/// Several checks may produce the same `BlockError` variant, so this distinguishes them.
+fn record_gossip_rejection<E: EthSpec>(check: &'static str, error: BlockError<E>) -> BlockError<E> {
+    metrics::inc_counter_vec(&metrics::GOSSIP_BEACON_BLOCK_CHECK_REJECTIONS, &[check]);
+    error
+}
+
+/// Returns the slot of the state with root `state_root` if it is already known to the database,
+/// either as a hot or a frozen state.
+fn known_state_slot<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    state_root: &Hash256,
+) -> Result<Option<Slot>, DBError> {
+    if let Some(summary) = chain.store.load_hot_state_summary(state_root)? {
+        return Ok(Some(summary.slot));
+    }
+    chain.store.load_cold_state_slot(state_root)
+}
+
+/// Warns if the state root claimed by `block` is that of a state which is already known to the
+/// database.
+///
+/// This is only called when the claimed state root differs from the one computed for the block, so
+/// a collision means the block claims the state root of some unrelated state. That is a strong
+/// signal of a crafted block. The state root check remains authoritative, so this is purely
+/// diagnostic and any error is logged rather than returned.
+fn warn_on_known_state_root<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &SignedBeaconBlock<T::EthSpec>,
+    block_root: Hash256,
+) {
+    match known_state_slot(chain, &block.state_root()) {
+        Ok(Some(known_state_slot)) => warn!(
+            chain.log,
+            "Block claims the state root of a known state";
+            "msg" => "the block may have been crafted",
+            "block_root" => ?block_root,
+            "block_slot" => block.slot(),
+            "block_state_root" => ?block.state_root(),
+            "known_state_slot" => known_state_slot,
+        ),
+        Ok(None) => (),
+        Err(e) => debug!(
+            chain.log,
+            "Unable to check block state root against known states";
+            "block_root" => ?block_root,
+            "error" => ?e,
+        ),
+    }
+}
+
+/// Returns the root of the block which determines the proposer shuffling for a block at
+/// `block_slot` which descends from `parent_block`.
+fn proposer_shuffling_decision_root<E: EthSpec>(
+    parent_block: &ProtoBlock,
+    block_slot: Slot,
+) -> Hash256 {
+    if parent_block.slot.epoch(E::slots_per_epoch()) == block_slot.epoch(E::slots_per_epoch()) {
+        parent_block
+            .next_epoch_shuffling_id
+            .shuffling_decision_block
+    } else {
+        parent_block.root
+    }
+}
+
+/// Returns the proposers for the epoch of `block_slot`, along with the fork at `block_slot`, by
+/// loading the state of the parent block from the database and advancing it.
+///
+/// If a `committee_state_cache` is supplied, it is used as per
+/// `cheap_state_advance_to_obtain_committees`.
+fn proposers_from_parent_state<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    parent_root: Hash256,
+    parent_state_root: Hash256,
+    block_slot: Slot,
+    committee_state_cache: Option<&CommitteeStateCache<T::EthSpec>>,
+    spec: &ChainSpec,
+) -> Result<(Vec<usize>, Fork), BlockError<T::EthSpec>> {
+    let (parent_state_root, mut parent_state) = chain
+        .store
+        .get_advanced_hot_state(parent_root, block_slot, parent_state_root)?
+        .ok_or_else(|| {
+            BeaconChainError::DBInconsistent(format!(
+                "Missing state for parent block {parent_root:?}"
+            ))
+        })?;
+    let state = cheap_state_advance_to_obtain_committees(
+        &mut parent_state,
+        Some(parent_state_root),
+        block_slot,
+        committee_state_cache,
+        spec,
+    )?;
+    Ok((state.get_beacon_proposer_indices(spec)?, state.fork()))
+}
+
+/// Verifies that `header` was signed by the proposer expected at its slot, returning the index of
+/// that proposer. See `BeaconChain::verify_gossip_block_header_only`.
+///
+/// The proposer is read from the `beacon_proposer_cache` if possible. Otherwise it is computed from
+/// the parent state, which is loaded from the database, and the cache is primed.
+pub(crate) fn verify_block_header_proposal<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    header: &SignedBeaconBlockHeader,
+) -> Result<u64, BlockError<T::EthSpec>> {
+    let block_slot = header.message.slot;
+    let parent_root = header.message.parent_root;
+
+    let parent_block = chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&parent_root)
+        .ok_or(BeaconChainError::MissingBeaconBlock(parent_root))?;
+
+    if parent_block.slot >= block_slot {
+        return Err(BlockError::BlockIsNotLaterThanParent {
+            block_slot,
+            parent_slot: parent_block.slot,
+        });
+    }
+
+    let proposer_shuffling_decision_block =
+        proposer_shuffling_decision_root::<T::EthSpec>(&parent_block, block_slot);
+    let proposer_opt = chain
+        .beacon_proposer_cache
+        .lock()
+        .get_slot::<T::EthSpec>(proposer_shuffling_decision_block, block_slot);
+    let (proposer_index, fork) = if let Some(proposer) = proposer_opt {
+        (proposer.index, proposer.fork)
+    } else {
+        let (proposers, fork) = proposers_from_parent_state(
+            chain,
+            parent_root,
+            parent_block.state_root,
+            block_slot,
+            Some(&chain.committee_state_cache),
+            &chain.spec,
+        )?;
+        let proposer_index = *proposers
+            .get(block_slot.as_usize() % T::EthSpec::slots_per_epoch() as usize)
+            .ok_or(BeaconChainError::NoProposerForSlot(block_slot))?;
+        chain.beacon_proposer_cache.lock().insert(
+            block_slot.epoch(T::EthSpec::slots_per_epoch()),
+            proposer_shuffling_decision_block,
+            proposers,
+            fork,
+        )?;
+        (proposer_index, fork)
+    };
 
-    metrics::stop_timer(block_root_timer);
+    if proposer_index as u64 != header.message.proposer_index {
+        return Err(BlockError::IncorrectBlockProposer {
+            block: header.message.proposer_index,
+            local_shuffling: proposer_index as u64,
+        });
+    }
 
-    block_root
+    verify_header_signature_with_fork(chain, header, &fork)?;
+
+    Ok(header.message.proposer_index)
 }
 
 /// Verify the parent of `block` is known, returning some information about the parent block from
@@ -1661,6 +4292,32 @@ fn verify_parent_block_is_known<T: BeaconChainTypes>(
     }
 }
 
+/// Determines how `load_parent_with_source` uses the snapshot cache.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SnapshotCacheAccess {
+    /// The parent may be removed from the snapshot cache, see
+    /// `SnapshotCache::get_state_for_block_processing`. Only for blocks which are being imported.
+    Take,
+    /// The parent is cloned from the snapshot cache, leaving it unchanged.
+    Clone,
+    /// The snapshot cache is not consulted and the parent is always loaded from the database.
+    Skip,
+}
+
+impl SnapshotCacheAccess {
+    /// Returns `Self::Take` for blocks which are being imported (or `Self::Clone` if `dry_run` is
+    /// `true`), unless the snapshot cache is disabled by `ChainConfig::load_parent_from_db`.
+    fn for_chain<T: BeaconChainTypes>(chain: &BeaconChain<T>, dry_run: bool) -> Self {
+        if chain.config.load_parent_from_db {
+            Self::Skip
+        } else if dry_run {
+            Self::Clone
+        } else {
+            Self::Take
+        }
+    }
+}
+
 /// Load the parent snapshot (block and state) of the given `block`.
 ///
 /// Returns `Err(BlockError::ParentUnknown)` if the parent is not found, or if an error occurs
@@ -1676,6 +4333,33 @@ fn load_parent<T: BeaconChainTypes>(
         Arc<SignedBeaconBlock<T::EthSpec>>,
     ),
     BlockError<T::EthSpec>,
+> {
+    load_parent_with_source(
+        block_root,
+        block,
+        chain,
+        SnapshotCacheAccess::for_chain(chain, false),
+    )
+    .map(|(parent, block, _)| (parent, block))
+}
+
+/// As for `load_parent`, but also returns `true` if the parent was read from the snapshot cache
+/// rather than the database.
+///
+/// The snapshot cache is used according to `snapshot_cache_access`.
+#[allow(clippy::type_complexity)]
+fn load_parent_with_source<T: BeaconChainTypes>(
+    block_root: Hash256,
+    block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    chain: &BeaconChain<T>,
+    snapshot_cache_access: SnapshotCacheAccess,
+) -> Result<
+    (
+        PreProcessingSnapshot<T::EthSpec>,
+        Arc<SignedBeaconBlock<T::EthSpec>>,
+        bool,
+    ),
+    BlockError<T::EthSpec>,
 > {
     let spec = &chain.spec;
 
@@ -1697,43 +4381,70 @@ fn load_parent<T: BeaconChainTypes>(
         return Err(BlockError::ParentUnknown(block));
     }
 
-    let block_delay = chain
-        .block_times_cache
-        .read()
-        .get_block_delays(
-            block_root,
-            chain
-                .slot_clock
-                .start_of(block.slot())
-                .unwrap_or_else(|| Duration::from_secs(0)),
-        )
-        .observed;
+    // If the slot clock cannot provide the start of the slot, the block delay is unknown and the
+    // block is treated as though it has no delay (e.g., as during sync).
+    let slot_start = chain.slot_clock.start_of(block.slot());
+    let slot_start_known = slot_start.is_some();
+    let block_delay = slot_start.and_then(|slot_start| {
+        chain
+            .block_times_cache
+            .read()
+            .get_block_delays(block_root, slot_start)
+            .observed
+    });
+    if !slot_start_known {
+        metrics::inc_counter(&metrics::BLOCK_PROCESSING_SNAPSHOT_CACHE_UNKNOWN_SLOT_START);
+    } else if let Some(block_delay) = block_delay {
+        metrics::observe_duration(
+            &metrics::BLOCK_PROCESSING_SNAPSHOT_CACHE_BLOCK_DELAY,
+            block_delay,
+        );
+    }
 
     let db_read_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_DB_READ);
 
-    let result = if let Some((snapshot, cloned)) = chain
-        .snapshot_cache
-        .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
-        .and_then(|mut snapshot_cache| {
-            snapshot_cache.get_state_for_block_processing(
-                block.parent_root(),
-                block.slot(),
-                block_delay,
-                spec,
-            )
-        }) {
-        if cloned {
-            metrics::inc_counter(&metrics::BLOCK_PROCESSING_SNAPSHOT_CACHE_CLONES);
+    let cached_snapshot = match snapshot_cache_access {
+        SnapshotCacheAccess::Take => chain
+            .snapshot_cache
+            .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+            .and_then(|mut snapshot_cache| {
+                snapshot_cache.get_state_for_block_processing(
+                    block.parent_root(),
+                    block.slot(),
+                    block_delay,
+                    spec,
+                )
+            }),
+        // The snapshot is cloned regardless of the block delay, so there is no clone reason.
+        SnapshotCacheAccess::Clone => chain
+            .snapshot_cache
+            .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+            .and_then(|snapshot_cache| {
+                snapshot_cache.get_cloned_for_block_processing(block.parent_root(), block.slot())
+            })
+            .map(|snapshot| (snapshot, None)),
+        SnapshotCacheAccess::Skip => None,
+    };
+    let result = if let Some((snapshot, cloned)) = cached_snapshot {
+        if let Some(clone_reason) = cloned {
+            metrics::inc_counter_vec(
+                &metrics::BLOCK_PROCESSING_SNAPSHOT_CACHE_CLONES,
+                &[clone_reason.as_str()],
+            );
             debug!(
                 chain.log,
                 "Cloned snapshot for late block/skipped slot";
+                "reason" => clone_reason.as_str(),
                 "slot" => %block.slot(),
                 "parent_slot" => %snapshot.beacon_block.slot(),
                 "parent_root" => ?block.parent_root(),
                 "block_delay" => ?block_delay,
+                "slot_start_known" => slot_start_known,
             );
+        } else {
+            metrics::inc_counter(&metrics::BLOCK_PROCESSING_SNAPSHOT_CACHE_CLEAN_HITS);
         }
-        Ok((snapshot, block))
+        Ok((snapshot, block, true))
     } else {
         // Load the blocks parent block from the database, returning invalid if that block is not
         // found.
@@ -1768,7 +4479,16 @@ fn load_parent<T: BeaconChainTypes>(
                 )
             })?;
 
+        // Distinguish a state which has already been advanced (e.g., by the state advance timer)
+        // from the parent's post-state, which will require more catchup processing.
+        let advanced = parent_state_root != parent_block.state_root();
+
         metrics::inc_counter(&metrics::BLOCK_PROCESSING_SNAPSHOT_CACHE_MISSES);
+        if advanced {
+            metrics::inc_counter(&metrics::BLOCK_PROCESSING_SNAPSHOT_CACHE_DB_HITS_ADVANCED);
+        } else {
+            metrics::inc_counter(&metrics::BLOCK_PROCESSING_SNAPSHOT_CACHE_DB_HITS_EXACT);
+        }
         debug!(
             chain.log,
             "Missed snapshot cache";
@@ -1776,6 +4496,8 @@ fn load_parent<T: BeaconChainTypes>(
             "parent_slot" => parent_block.slot(),
             "parent_root" => ?block.parent_root(),
             "block_delay" => ?block_delay,
+            "slot_start_known" => slot_start_known,
+            "advanced_state" => advanced,
         );
 
         Ok((
@@ -1786,14 +4508,162 @@ fn load_parent<T: BeaconChainTypes>(
                 beacon_state_root: Some(parent_state_root),
             },
             block,
+            false,
         ))
     };
 
     metrics::stop_timer(db_read_timer);
 
+    if let Ok((parent, block, _)) = &result {
+        check_parent_root(parent, block)?;
+        check_parent_payload_available(parent)?;
+    }
+
     result
 }
 
+/// Loads the block with root `parent_root` and its state from the database, for use as the parent
+/// of `block`. The state is advanced through to at most `block.slot()`.
+///
+/// Unlike `load_parent`, neither fork choice nor the snapshot cache are consulted, and
+/// `parent_root` need not be the parent root of `block`. Returns `BlockError::ParentUnknown` if
+/// there is no block with root `parent_root` in the database.
+fn load_parent_by_root<T: BeaconChainTypes>(
+    parent_root: Hash256,
+    block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    chain: &BeaconChain<T>,
+) -> Result<
+    (
+        PreProcessingSnapshot<T::EthSpec>,
+        Arc<SignedBeaconBlock<T::EthSpec>>,
+    ),
+    BlockError<T::EthSpec>,
+> {
+    let parent_block = match chain.get_blinded_block(&parent_root)? {
+        Some(parent_block) => parent_block,
+        None => return Err(BlockError::ParentUnknown(block)),
+    };
+
+    let (parent_state_root, parent_state) = chain
+        .store
+        .get_advanced_hot_state(parent_root, block.slot(), parent_block.state_root())?
+        .ok_or_else(|| {
+            BeaconChainError::DBInconsistent(format!("Missing state for block {parent_root:?}"))
+        })?;
+
+    let parent = PreProcessingSnapshot {
+        beacon_block: parent_block,
+        beacon_block_root: parent_root,
+        pre_state: parent_state,
+        beacon_state_root: Some(parent_state_root),
+    };
+    check_parent_payload_available(&parent)?;
+
+    Ok((parent, block))
+}
+
+/// Ensures that `consensus_context` was created for `block`, rather than another block.
+///
+/// The context is passed between the stages of verification separately from the block and its
+/// proposer index and block root are trusted by `per_block_processing`. Only the values which have
+/// been cached in the context are checked, an empty context cannot be mismatched.
+pub(crate) fn check_consensus_context<E: EthSpec>(
+    block: &SignedBeaconBlock<E>,
+    block_root: Hash256,
+    consensus_context: &ConsensusContext<E>,
+) -> Result<(), BeaconChainError> {
+    let block_proposer_index = block.message().proposer_index();
+    let context_block_root = consensus_context.cached_current_block_root();
+    let context_proposer_index = consensus_context.cached_proposer_index();
+
+    if consensus_context.slot() != block.slot()
+        || context_block_root.map_or(false, |root| root != block_root)
+        || context_proposer_index.map_or(false, |index| index != block_proposer_index)
+    {
+        return Err(BeaconChainError::ConsensusContextMismatch {
+            block_root,
+            block_slot: block.slot(),
+            block_proposer_index,
+            context_block_root,
+            context_slot: consensus_context.slot(),
+            context_proposer_index,
+        });
+    }
+
+    Ok(())
+}
+
+/// Ensures that `parent` is the snapshot of the parent of `block`.
+///
+/// Both the snapshot cache and the database are keyed by block root, so this should never fail.
+/// However, a bug or corruption in either would otherwise cause `block` to be silently verified
+/// against the wrong parent. The state is checked as well as the block root recorded in the
+/// snapshot, which is cheap since only the latest block header is hashed.
+fn check_parent_root<E: EthSpec>(
+    parent: &PreProcessingSnapshot<E>,
+    block: &SignedBeaconBlock<E>,
+) -> Result<(), BlockError<E>> {
+    let parent_root = block.parent_root();
+
+    // The state root of the latest block header is only zero if the state has not been advanced,
+    // in which case `beacon_state_root` should be known.
+    let latest_block_header = parent.pre_state.latest_block_header();
+    let state_block_root = match parent.beacon_state_root {
+        Some(state_root) => Some(parent.pre_state.get_latest_block_root(state_root)),
+        None if !latest_block_header.state_root.is_zero() => {
+            Some(latest_block_header.canonical_root())
+        }
+        None => None,
+    };
+
+    if parent.beacon_block_root != parent_root {
+        return Err(BeaconChainError::DBInconsistent(format!(
+            "Loaded parent {:?} for block with parent root {:?}",
+            parent.beacon_block_root, parent_root
+        ))
+        .into());
+    }
+
+    if let Some(state_block_root) = state_block_root.filter(|root| *root != parent_root) {
+        return Err(BeaconChainError::DBInconsistent(format!(
+            "Loaded state for block {:?} as the parent state of block with parent root {:?}",
+            state_block_root, parent_root
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Ensures that the execution payload of the (blinded) `parent` block is reflected in its
+/// `pre_state`.
+///
+/// Without this check, a child of a post-merge parent whose state lacks the parent's payload
+/// header would be mistaken for a merge transition block and fail with a confusing error.
+fn check_parent_payload_available<E: EthSpec>(
+    parent: &PreProcessingSnapshot<E>,
+) -> Result<(), BlockError<E>> {
+    let parent_block_hash = match parent.beacon_block.message().execution_payload() {
+        Ok(payload) if payload.block_hash() != ExecutionBlockHash::zero() => payload.block_hash(),
+        // The parent is pre-merge, there is no payload to be made available.
+        _ => return Ok(()),
+    };
+
+    let state_block_hash = parent
+        .pre_state
+        .latest_execution_payload_header()
+        .map(|header| header.block_hash())
+        .ok();
+
+    if state_block_hash == Some(parent_block_hash) {
+        Ok(())
+    } else {
+        Err(BlockError::ParentPayloadUnavailable {
+            parent_root: parent.beacon_block_root,
+        })
+    }
+}
+
 /// Performs a cheap (time-efficient) state advancement so the committees and proposer shuffling for
 /// `slot` can be obtained from `state`.
 ///
@@ -1805,10 +4675,14 @@ fn load_parent<T: BeaconChainTypes>(
 /// and `Cow::Borrowed(state)` will be returned. Otherwise, the state will be cloned, cheaply
 /// advanced and then returned as a `Cow::Owned`. The end result is that the given `state` is never
 /// mutated to be invalid (in fact, it is never changed beyond a simple committee cache build).
+///
+/// If a `committee_state_cache` is supplied and `state_root_opt` is known, advanced states are
+/// read from and written to that cache.
 fn cheap_state_advance_to_obtain_committees<'a, E: EthSpec>(
     state: &'a mut BeaconState<E>,
     state_root_opt: Option<Hash256>,
     block_slot: Slot,
+    committee_state_cache: Option<&CommitteeStateCache<E>>,
     spec: &ChainSpec,
 ) -> Result<Cow<'a, BeaconState<E>>, BlockError<E>> {
     let block_epoch = block_slot.epoch(E::slots_per_epoch());
@@ -1821,11 +4695,23 @@ fn cheap_state_advance_to_obtain_committees<'a, E: EthSpec>(
 
         Ok(Cow::Borrowed(state))
     } else if state.slot() > block_slot {
-        Err(BlockError::BlockIsNotLaterThanParent {
+        Err(BlockError::PreStateSlotTooHigh {
             block_slot,
-            parent_slot: state.slot(),
+            state_slot: state.slot(),
         })
     } else {
+        // The cache is keyed by the root of the state prior to advancement, so it can't be used
+        // if that root is unknown.
+        let cache_and_root = committee_state_cache.zip(state_root_opt);
+
+        if let Some((cache, state_root)) = cache_and_root {
+            if let Some(cached_state) = cache.get(state_root, block_epoch) {
+                metrics::inc_counter(&metrics::COMMITTEE_STATE_CACHE_HITS);
+                return Ok(Cow::Owned(cached_state));
+            }
+            metrics::inc_counter(&metrics::COMMITTEE_STATE_CACHE_MISSES);
+        }
+
         let mut state = state.clone_with(CloneConfig::committee_caches_only());
         let target_slot = block_epoch.start_slot(E::slots_per_epoch());
 
@@ -1837,38 +4723,198 @@ fn cheap_state_advance_to_obtain_committees<'a, E: EthSpec>(
         state.build_committee_cache(RelativeEpoch::Previous, spec)?;
         state.build_committee_cache(RelativeEpoch::Current, spec)?;
 
+        if let Some((cache, state_root)) = cache_and_root {
+            cache.insert(state_root, block_epoch, &state);
+        }
+
         Ok(Cow::Owned(state))
     }
 }
 
 /// Obtains a read-locked `ValidatorPubkeyCache` from the `chain`.
+///
+/// Each attempt waits at most `VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT` for the lock. If it times out
+/// the read is retried up to `ChainConfig::validator_pubkey_cache_lock_retries` times before giving
+/// up, so the total wait is bounded.
 pub fn get_validator_pubkey_cache<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
 ) -> Result<RwLockReadGuard<ValidatorPubkeyCache<T>>, BlockError<T::EthSpec>> {
-    chain
-        .validator_pubkey_cache
-        .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
-        .ok_or(BeaconChainError::ValidatorPubkeyCacheLockTimeout)
-        .map_err(BlockError::BeaconChainError)
+    let mut retries = 0;
+    loop {
+        if let Some(pubkey_cache) = chain
+            .validator_pubkey_cache
+            .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+        {
+            return Ok(pubkey_cache);
+        }
+
+        metrics::inc_counter(&metrics::BLOCK_PROCESSING_PUBKEY_CACHE_LOCK_TIMEOUTS);
+
+        if retries >= chain.config.validator_pubkey_cache_lock_retries {
+            return Err(BlockError::BeaconChainError(
+                BeaconChainError::ValidatorPubkeyCacheLockTimeout,
+            ));
+        }
+        retries += 1;
+
+        debug!(
+            chain.log,
+            "Retrying validator pubkey cache read";
+            "retry" => retries,
+            "max_retries" => chain.config.validator_pubkey_cache_lock_retries,
+        );
+    }
+}
+
+/// Logs the failure of signature verification for `blocks`, which are `(block_root, block)` pairs
+/// ordered by slot.
+///
+/// This should only be called on the failure path, since it iterates all of `blocks`.
+fn log_invalid_signature<'a, E: EthSpec>(
+    log: &Logger,
+    kind: SignatureKind,
+    blocks: impl IntoIterator<Item = (Hash256, &'a SignedBeaconBlock<E>)>,
+) {
+    let mut first = None;
+    let mut last = None;
+    let mut proposer_indices = vec![];
+    for (block_root, block) in blocks {
+        let block_id = (block_root, block.slot());
+        first.get_or_insert(block_id);
+        last = Some(block_id);
+        proposer_indices.push(block.message().proposer_index());
+    }
+
+    warn!(
+        log,
+        "Block signature verification failed";
+        "kind" => ?kind,
+        "epoch" => first.map(|(_, slot)| slot.epoch(E::slots_per_epoch())),
+        "first_block_root" => ?first.map(|(block_root, _)| block_root),
+        "first_block_slot" => first.map(|(_, slot)| slot),
+        "last_block_root" => ?last.map(|(block_root, _)| block_root),
+        "last_block_slot" => last.map(|(_, slot)| slot),
+        "proposer_indices" => ?proposer_indices,
+    );
+}
+
+/// Converts an error from including the signatures of `block` in a signature verifier into a
+/// `BlockError`, logging the block if it is not from the proposer expected by the verifier's state.
+///
+/// This should only be called on the failure path, e.g. via `Result::map_err`.
+fn signature_verifier_error<E: EthSpec>(
+    log: &Logger,
+    block_root: Hash256,
+    block: &SignedBeaconBlock<E>,
+    e: BlockSignatureVerifierError,
+) -> BlockError<E> {
+    if let BlockSignatureVerifierError::IncorrectBlockProposer {
+        block: block_proposer,
+        local_shuffling,
+    } = e
+    {
+        debug!(
+            log,
+            "Block from incorrect proposer";
+            "block_root" => ?block_root,
+            "block_slot" => block.slot(),
+            "block_proposer" => block_proposer,
+            "expected_proposer" => local_shuffling,
+        );
+    }
+    BlockError::from(e)
+}
+
+/// Determines the kind of the first invalid signature in `blocks`, after batch verification of
+/// their signatures has failed.
+///
+/// Returns `SignatureKind::Unknown` if no individual kind of signature is invalid.
+fn identify_invalid_signature<T: BeaconChainTypes>(
+    blocks: &mut [SignatureVerifiedBlock<T>],
+    state: &BeaconState<T::EthSpec>,
+    validator_pubkey_cache: &ValidatorPubkeyCache<T>,
+    spec: &ChainSpec,
+) -> SignatureKind {
+    blocks
+        .iter_mut()
+        .find_map(|signature_verified_block| {
+            identify_invalid_block_signature(
+                &signature_verified_block.block,
+                signature_verified_block.block_root,
+                &mut signature_verified_block.consensus_context,
+                state,
+                validator_pubkey_cache,
+                spec,
+            )
+        })
+        .unwrap_or(SignatureKind::Unknown)
+}
+
+/// Re-verifies each kind of signature in `block` individually, returning the first kind which is
+/// invalid.
+///
+/// This is much slower than verifying all of the signatures in a single batch, so it should only
+/// be used to diagnose a batch which has already failed verification.
+fn identify_invalid_block_signature<T: BeaconChainTypes>(
+    block: &SignedBeaconBlock<T::EthSpec>,
+    block_root: Hash256,
+    consensus_context: &mut ConsensusContext<T::EthSpec>,
+    state: &BeaconState<T::EthSpec>,
+    validator_pubkey_cache: &ValidatorPubkeyCache<T>,
+    spec: &ChainSpec,
+) -> Option<SignatureKind> {
+    let proposer_index = consensus_context
+        .get_proposer_index_from_epoch_state(state, spec)
+        .ok();
+
+    SignatureKind::ALL.into_iter().find(|kind| {
+        let mut signature_verifier = get_signature_verifier(state, validator_pubkey_cache, spec);
+
+        // This match statement should never have a default case so that we are forced to consider
+        // how to re-verify each new kind of signature.
+        let included = match kind {
+            SignatureKind::BlockProposal => {
+                signature_verifier.include_block_proposal(block, Some(block_root), proposer_index)
+            }
+            SignatureKind::RandaoReveal => {
+                signature_verifier.include_randao_reveal(block, proposer_index)
+            }
+            SignatureKind::ProposerSlashing => signature_verifier.include_proposer_slashings(block),
+            SignatureKind::AttesterSlashing => signature_verifier.include_attester_slashings(block),
+            SignatureKind::Attestation => {
+                signature_verifier.include_attestations(block, consensus_context)
+            }
+            SignatureKind::VoluntaryExit => signature_verifier.include_exits(block),
+            SignatureKind::SyncAggregate => signature_verifier.include_sync_aggregate(block),
+            SignatureKind::BlsToExecutionChange => {
+                signature_verifier.include_bls_to_execution_changes(block)
+            }
+            SignatureKind::Unknown => return false,
+        };
+
+        included.is_ok() && signature_verifier.verify().is_err()
+    })
 }
 
 /// Produces an _empty_ `BlockSignatureVerifier`.
 ///
 /// The signature verifier is empty because it does not yet have any of this block's signatures
 /// added to it. Use `Self::apply_to_signature_verifier` to apply the signatures.
-fn get_signature_verifier<'a, T: BeaconChainTypes>(
-    state: &'a BeaconState<T::EthSpec>,
+fn get_signature_verifier<'a, 's, T: BeaconChainTypes>(
+    state: &'s BeaconState<T::EthSpec>,
     validator_pubkey_cache: &'a ValidatorPubkeyCache<T>,
     spec: &'a ChainSpec,
 ) -> BlockSignatureVerifier<
     'a,
+    's,
     T::EthSpec,
     impl Fn(usize) -> Option<Cow<'a, PublicKey>> + Clone,
-    impl Fn(&'a PublicKeyBytes) -> Option<Cow<'a, PublicKey>>,
+    impl Fn(&PublicKeyBytes) -> Option<Cow<'a, PublicKey>>,
 > {
+    let num_validators = state.validators().len();
     let get_pubkey = move |validator_index| {
         // Disallow access to any validator pubkeys that are not in the current beacon state.
-        if validator_index < state.validators().len() {
+        if validator_index < num_validators {
             validator_pubkey_cache
                 .get(validator_index)
                 .map(Cow::Borrowed)
@@ -1877,7 +4923,7 @@ fn get_signature_verifier<'a, T: BeaconChainTypes>(
         }
     };
 
-    let decompressor = move |pk_bytes| {
+    let decompressor = move |pk_bytes: &PublicKeyBytes| {
         // Map compressed pubkey to validator index.
         let validator_index = validator_pubkey_cache.get_index(pk_bytes)?;
         // Map validator index to pubkey (respecting guard on unknown validators).
@@ -1887,22 +4933,84 @@ fn get_signature_verifier<'a, T: BeaconChainTypes>(
     BlockSignatureVerifier::new(state, get_pubkey, decompressor, spec)
 }
 
+/// As per `verify_proposal_signature_with_parent`, loading the parent of `block` into `parent`
+/// if it has not been loaded already.
+///
+/// The parent is retained so that it isn't loaded again during later verification.
+fn verify_proposal_signature_only<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &Arc<SignedBeaconBlock<T::EthSpec>>,
+    block_root: Hash256,
+    parent: &mut Option<PreProcessingSnapshot<T::EthSpec>>,
+    snapshot_cache_access: SnapshotCacheAccess,
+) -> Result<bool, BlockError<T::EthSpec>> {
+    let parent = match parent {
+        Some(parent) => parent,
+        None => parent.insert(
+            load_parent_with_source(block_root, block.clone(), chain, snapshot_cache_access)?.0,
+        ),
+    };
+    verify_proposal_signature_with_parent(block, block_root, parent, chain)
+}
+
+/// Verify that `block` was signed with a valid signature from the proposer it claims.
+///
+/// The signing domain is computed from the fork of `parent` once it has been advanced to the slot
+/// of `block`, rather than the fork of the canonical head. This means the result is correct even
+/// when the block was proposed under a different fork to the head (e.g., around a fork boundary).
+///
+/// The proposer index is *not* checked against the shuffling. Returns `Ok(false)` if the signature
+/// is invalid and an `Err` if the signature could not be checked.
+fn verify_proposal_signature_with_parent<T: BeaconChainTypes>(
+    block: &SignedBeaconBlock<T::EthSpec>,
+    block_root: Hash256,
+    parent: &mut PreProcessingSnapshot<T::EthSpec>,
+    chain: &BeaconChain<T>,
+) -> Result<bool, BlockError<T::EthSpec>> {
+    let state = cheap_state_advance_to_obtain_committees(
+        &mut parent.pre_state,
+        parent.beacon_state_root,
+        block.slot(),
+        Some(&chain.committee_state_cache),
+        &chain.spec,
+    )?;
+
+    let pubkey_cache = get_validator_pubkey_cache(chain)?;
+
+    let mut signature_verifier = get_signature_verifier(&state, &pubkey_cache, &chain.spec);
+
+    let proposer_index = block.message().proposer_index();
+    signature_verifier.include_block_proposal(block, Some(block_root), Some(proposer_index))?;
+
+    Ok(signature_verifier.verify().is_ok())
+}
+
 /// Verify that `header` was signed with a valid signature from its proposer.
 ///
 /// Return `Ok(())` if the signature is valid, and an `Err` otherwise.
 fn verify_header_signature<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     header: &SignedBeaconBlockHeader,
+) -> Result<(), BlockError<T::EthSpec>> {
+    let head_fork = chain.canonical_head.cached_head().head_fork();
+    verify_header_signature_with_fork(chain, header, &head_fork)
+}
+
+/// As per `verify_header_signature`, except that the signature is verified against `fork` rather
+/// than the fork at the head.
+fn verify_header_signature_with_fork<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    header: &SignedBeaconBlockHeader,
+    fork: &Fork,
 ) -> Result<(), BlockError<T::EthSpec>> {
     let proposer_pubkey = get_validator_pubkey_cache(chain)?
         .get(header.message.proposer_index as usize)
         .cloned()
         .ok_or(BlockError::UnknownValidator(header.message.proposer_index))?;
-    let head_fork = chain.canonical_head.cached_head().head_fork();
 
     if header.verify_signature::<T::EthSpec>(
         &proposer_pubkey,
-        &head_fork,
+        fork,
         chain.genesis_validators_root,
         &chain.spec,
     ) {
@@ -1912,45 +5020,510 @@ fn verify_header_signature<T: BeaconChainTypes>(
     }
 }
 
-fn write_state<T: EthSpec>(prefix: &str, state: &BeaconState<T>, log: &Logger) {
-    if WRITE_BLOCK_PROCESSING_SSZ {
-        let root = state.tree_hash_root();
-        let filename = format!("{}_slot_{}_root_{}.ssz", prefix, state.slot(), root);
-        let mut path = std::env::temp_dir().join("lighthouse");
-        let _ = fs::create_dir_all(path.clone());
-        path = path.join(filename);
+/// Returns the directory to which block processing SSZ files should be written, if any.
+///
+/// The directory in the `ChainConfig` takes precedence over the compile-time
+/// `WRITE_BLOCK_PROCESSING_SSZ` flag, which uses the temp directory.
+fn block_processing_ssz_dir<T: BeaconChainTypes>(chain: &BeaconChain<T>) -> Option<PathBuf> {
+    chain
+        .config
+        .block_processing_ssz_dir
+        .clone()
+        .or_else(|| WRITE_BLOCK_PROCESSING_SSZ.then(|| std::env::temp_dir().join("lighthouse")))
+}
+
+fn write_state<T: EthSpec>(dir: &Path, prefix: &str, state: &BeaconState<T>, log: &Logger) {
+    let root = state.tree_hash_root();
+    let filename = format!("{}_slot_{}_root_{}.ssz", prefix, state.slot(), root);
+    let _ = fs::create_dir_all(dir);
+    let path = dir.join(filename);
 
-        match fs::File::create(path.clone()) {
-            Ok(mut file) => {
-                let _ = file.write_all(&state.as_ssz_bytes());
-            }
-            Err(e) => error!(
-                log,
-                "Failed to log state";
-                "path" => format!("{:?}", path),
-                "error" => format!("{:?}", e)
-            ),
+    match fs::File::create(path.clone()) {
+        Ok(mut file) => {
+            let _ = file.write_all(&state.as_ssz_bytes());
+        }
+        Err(e) => error!(
+            log,
+            "Failed to log state";
+            "path" => format!("{:?}", path),
+            "error" => format!("{:?}", e)
+        ),
+    }
+}
+
+fn write_block<T: EthSpec>(dir: &Path, block: &SignedBeaconBlock<T>, root: Hash256, log: &Logger) {
+    let filename = format!(
+        "block_slot_{}_root{}_parent_{}.ssz",
+        block.slot(),
+        root,
+        block.parent_root()
+    );
+    let _ = fs::create_dir_all(dir);
+    let path = dir.join(filename);
+
+    match fs::File::create(path.clone()) {
+        Ok(mut file) => {
+            let _ = file.write_all(&block.as_ssz_bytes());
         }
+        Err(e) => error!(
+            log,
+            "Failed to log block";
+            "path" => format!("{:?}", path),
+            "error" => format!("{:?}", e)
+        ),
     }
 }
 
-fn write_block<T: EthSpec>(block: &SignedBeaconBlock<T>, root: Hash256, log: &Logger) {
-    if WRITE_BLOCK_PROCESSING_SSZ {
-        let filename = format!("block_slot_{}_root{}.ssz", block.slot(), root);
-        let mut path = std::env::temp_dir().join("lighthouse");
-        let _ = fs::create_dir_all(path.clone());
-        path = path.join(filename);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+    };
+    use crate::ChainConfig;
+    use fork_choice::InvalidationOperation;
+    use types::{ExecutionPayloadHeaderRefMut, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    fn get_harness(present_slot: Slot) -> BeaconChainHarness<EphemeralHarnessType<E>> {
+        BeaconChainHarness::builder(E::default())
+            .default_spec()
+            .chain_config(ChainConfig {
+                present_slot_override: Some(present_slot),
+                ..ChainConfig::default()
+            })
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build()
+    }
+
+    #[test]
+    fn validator_pubkey_cache_lock_retries() {
+        let lock_timeouts = || {
+            metrics::BLOCK_PROCESSING_PUBKEY_CACHE_LOCK_TIMEOUTS
+                .as_ref()
+                .unwrap()
+                .get()
+        };
+        let get_harness = |retries| {
+            BeaconChainHarness::builder(E::default())
+                .default_spec()
+                .chain_config(ChainConfig {
+                    validator_pubkey_cache_lock_retries: retries,
+                    ..ChainConfig::default()
+                })
+                .deterministic_keypairs(8)
+                .fresh_ephemeral_store()
+                .build()
+        };
 
-        match fs::File::create(path.clone()) {
-            Ok(mut file) => {
-                let _ = file.write_all(&block.as_ssz_bytes());
+        // Without retries, a single timeout is fatal.
+        let harness = get_harness(0);
+        let guard = harness.chain.validator_pubkey_cache.write();
+        assert!(matches!(
+            get_validator_pubkey_cache(&harness.chain).map(|cache| cache.len()),
+            Err(BlockError::BeaconChainError(
+                BeaconChainError::ValidatorPubkeyCacheLockTimeout
+            ))
+        ));
+        drop(guard);
+
+        // With a retry, the read succeeds once the writer releases the lock after the first
+        // timeout.
+        let harness = get_harness(1);
+        let chain = harness.chain.clone();
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let writer = std::thread::spawn(move || {
+            let _guard = chain.validator_pubkey_cache.write();
+            let timeouts = lock_timeouts();
+            locked_tx.send(()).unwrap();
+            while lock_timeouts() == timeouts {
+                std::thread::yield_now();
             }
-            Err(e) => error!(
-                log,
-                "Failed to log block";
-                "path" => format!("{:?}", path),
-                "error" => format!("{:?}", e)
-            ),
+        });
+        locked_rx.recv().unwrap();
+        assert_eq!(
+            get_validator_pubkey_cache(&harness.chain)
+                .map(|cache| cache.len())
+                .unwrap(),
+            8
+        );
+        writer.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn future_slot_uses_present_slot_override() {
+        // The harness slot clock stays at genesis throughout, so any block would be from a future
+        // slot if the clock were consulted.
+        for (present_slot, block_slot, is_future) in [
+            (1, 1, false),
+            (1, 2, true),
+            (3, 2, false),
+            (3, 3, false),
+            (3, 4, true),
+        ] {
+            let present_slot = Slot::new(present_slot);
+            let block_slot = Slot::new(block_slot);
+            let harness = get_harness(present_slot);
+            assert_eq!(harness.chain.slot().unwrap(), Slot::new(0));
+
+            let (block, _) = harness
+                .make_block(harness.get_current_state(), block_slot)
+                .await;
+            let block = Arc::new(block);
+
+            let expected_future_slot = |e: &BlockError<E>| match e {
+                BlockError::FutureSlot {
+                    present_slot: reported_present_slot,
+                    block_slot: reported_block_slot,
+                    ..
+                } => {
+                    assert_eq!(*reported_present_slot, present_slot);
+                    assert_eq!(*reported_block_slot, block_slot);
+                    true
+                }
+                _ => false,
+            };
+
+            let relevancy = check_block_relevancy(&block, None, &harness.chain, None);
+            assert_eq!(
+                relevancy.as_ref().err().map_or(false, expected_future_slot),
+                is_future,
+                "check_block_relevancy: present slot {present_slot}, block slot {block_slot}: \
+                 {relevancy:?}"
+            );
+
+            let gossip = GossipVerifiedBlock::new(block, &harness.chain);
+            assert_eq!(
+                gossip.as_ref().err().map_or(false, expected_future_slot),
+                is_future,
+                "gossip verification: present slot {present_slot}, block slot {block_slot}: \
+                 {:?}",
+                gossip.err()
+            );
+        }
+    }
+
+    #[test]
+    fn execution_layer_offline_errors() {
+        let offline = |e: ExecutionPayloadError| {
+            is_execution_layer_connection_error(&BlockError::<E>::ExecutionPayloadError(e))
+        };
+        assert!(offline(ExecutionPayloadError::NoExecutionConnection));
+        assert!(offline(ExecutionPayloadError::RequestFailed(
+            execution_layer::Error::EngineError(Box::new(EngineError::Offline))
+        )));
+        assert!(!offline(ExecutionPayloadError::RequestFailed(
+            execution_layer::Error::EngineError(Box::new(EngineError::Auth))
+        )));
+        assert!(!offline(ExecutionPayloadError::RequestFailed(
+            execution_layer::Error::NotSynced
+        )));
+        assert!(!offline(
+            ExecutionPayloadError::UnverifiedNonOptimisticCandidate
+        ));
+
+        // Peers are not penalized for our execution layer being offline.
+        let error =
+            BlockError::<E>::from(ExecutionPayloadError::MergeTransitionExecutionLayerOffline);
+        assert_eq!(error.penalize_peer(), None);
+    }
+
+    #[tokio::test]
+    async fn gossip_check_rejection_is_counted() {
+        let future_slot_rejections = || {
+            metrics::get_int_counter(
+                &metrics::GOSSIP_BEACON_BLOCK_CHECK_REJECTIONS,
+                &["future_slot"],
+            )
+            .unwrap()
+            .get()
+        };
+
+        let harness = get_harness(Slot::new(1));
+        let (block, _) = harness
+            .make_block(harness.get_current_state(), Slot::new(2))
+            .await;
+
+        // Other tests may also reject future blocks, so only an increase is checked.
+        let before = future_slot_rejections();
+        assert!(matches!(
+            GossipVerifiedBlock::new(Arc::new(block), &harness.chain),
+            Err(BlockError::FutureSlot { .. })
+        ));
+        assert!(future_slot_rejections() > before);
+    }
+
+    #[tokio::test]
+    async fn known_state_root_is_still_rejected() {
+        let harness = BeaconChainHarness::builder(E::default())
+            .default_spec()
+            .chain_config(ChainConfig {
+                detect_known_state_roots: true,
+                ..ChainConfig::default()
+            })
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build();
+        harness.advance_slot();
+
+        let head = harness.chain.head_snapshot();
+        let genesis_state_root = head.beacon_state_root();
+        assert_eq!(
+            known_state_slot(&harness.chain, &genesis_state_root).unwrap(),
+            Some(Slot::new(0))
+        );
+        assert_eq!(
+            known_state_slot(&harness.chain, &Hash256::repeat_byte(42)).unwrap(),
+            None
+        );
+
+        // A block claiming the genesis state root is rejected by the state root check as usual.
+        let (block, _) = harness
+            .make_block_with_modifier(head.beacon_state.clone(), Slot::new(1), |block| {
+                *block.state_root_mut() = genesis_state_root
+            })
+            .await;
+        let block = Arc::new(block);
+        let block_root = block.canonical_root();
+        let result = ExecutionPendingBlock::from_signature_verified_components(
+            block,
+            block_root,
+            PreProcessingSnapshot::from(head.as_ref().clone()),
+            ConsensusContext::new(Slot::new(1)).set_current_block_root(block_root),
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+            None,
+        );
+        assert!(matches!(
+            result,
+            Err(BlockError::StateRootMismatch { block, .. }) if block == genesis_state_root
+        ));
+    }
+
+    #[tokio::test]
+    async fn dry_run_leaves_snapshot_cache_unchanged() {
+        let harness = get_harness(Slot::new(1));
+        let head_root = harness.chain.head_snapshot().beacon_block_root;
+        let snapshot_cache_roots = || {
+            harness
+                .chain
+                .snapshot_cache
+                .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                .unwrap()
+                .beacon_block_roots()
+        };
+        let roots_before = snapshot_cache_roots();
+        assert!(roots_before.contains(&head_root));
+
+        let (block, _) = harness
+            .make_block(harness.get_current_state(), Slot::new(1))
+            .await;
+        let dry_run = harness
+            .chain
+            .verify_block_dry_run(Arc::new(block), NotifyExecutionLayer::Yes)
+            .await
+            .unwrap();
+        assert_eq!(dry_run.state.slot(), Slot::new(1));
+
+        // The parent must have been cloned, rather than removed from the snapshot cache.
+        assert_eq!(snapshot_cache_roots(), roots_before);
+    }
+
+    #[tokio::test]
+    async fn expected_proposer_for_block_leaves_snapshot_cache_unchanged() {
+        let harness = get_harness(Slot::new(1));
+        let snapshot_cache_roots = || {
+            harness
+                .chain
+                .snapshot_cache
+                .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                .unwrap()
+                .beacon_block_roots()
+        };
+        let roots_before = snapshot_cache_roots();
+
+        let (block, _) = harness
+            .make_block(harness.get_current_state(), Slot::new(1))
+            .await;
+
+        // Clear the proposer cache so that the parent must be loaded to compute the shuffling.
+        *harness.chain.beacon_proposer_cache.lock() = <_>::default();
+        assert_eq!(
+            harness.chain.expected_proposer_for_block(&block).unwrap(),
+            block.message().proposer_index()
+        );
+
+        // The parent must have been cloned, rather than removed from the snapshot cache.
+        assert_eq!(snapshot_cache_roots(), roots_before);
+    }
+
+    #[tokio::test]
+    async fn pruned_parent_payload_is_unavailable() {
+        let mut spec = E::default_spec();
+        spec.altair_fork_epoch = Some(Epoch::new(0));
+        spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+        let harness = BeaconChainHarness::builder(E::default())
+            .spec(spec)
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        harness
+            .execution_block_generator()
+            .move_to_terminal_block()
+            .unwrap();
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                2,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        let head = harness.chain.head_snapshot();
+        assert_ne!(
+            head.beacon_block
+                .message()
+                .execution_payload()
+                .unwrap()
+                .block_hash(),
+            ExecutionBlockHash::zero(),
+            "parent should be post-merge"
+        );
+        let mut parent = PreProcessingSnapshot::from(head.as_ref().clone());
+        assert!(check_parent_payload_available(&parent).is_ok());
+
+        // Simulate a parent state which was reconstructed after the parent's payload was pruned.
+        match parent
+            .pre_state
+            .latest_execution_payload_header_mut()
+            .unwrap()
+        {
+            ExecutionPayloadHeaderRefMut::Merge(header) => *header = <_>::default(),
+            ExecutionPayloadHeaderRefMut::Capella(header) => *header = <_>::default(),
+        }
+        assert!(matches!(
+            check_parent_payload_available(&parent),
+            Err(BlockError::ParentPayloadUnavailable { parent_root })
+                if parent_root == head.beacon_block_root
+        ));
+    }
+
+    #[tokio::test]
+    async fn payload_verification_join_errors() {
+        // An aborted task indicates that the executor is shutting down.
+        let handle = tokio::spawn(future::pending());
+        handle.abort();
+        assert!(matches!(
+            PayloadVerificationHandle::<E>::Spawned(handle)
+                .outcome()
+                .await,
+            Err(BlockError::BeaconChainError(
+                BeaconChainError::RuntimeShutdown
+            ))
+        ));
+
+        // A panic is not mistaken for a cancellation.
+        let handle = tokio::spawn(async { panic!("payload verification panicked") });
+        assert!(matches!(
+            PayloadVerificationHandle::<E>::Spawned(handle).outcome().await,
+            Err(BlockError::BeaconChainError(BeaconChainError::TokioJoin(e))) if e.is_panic()
+        ));
+    }
+
+    #[tokio::test]
+    async fn irrelevant_block_cancels_payload_verification() {
+        let mut spec = E::default_spec();
+        spec.altair_fork_epoch = Some(Epoch::new(0));
+        spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+        let harness = BeaconChainHarness::builder(E::default())
+            .spec(spec)
+            .chain_config(ChainConfig {
+                max_concurrent_payload_verifications: 1,
+                ..ChainConfig::default()
+            })
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        harness
+            .execution_block_generator()
+            .move_to_terminal_block()
+            .unwrap();
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                2,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        // Import the parent optimistically, so that it can be invalidated later.
+        harness
+            .mock_execution_layer
+            .as_ref()
+            .unwrap()
+            .server
+            .all_payloads_syncing(true);
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                1,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        let parent = harness.chain.head_snapshot();
+        assert!(harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .get_block_execution_status(&parent.beacon_block_root)
+            .unwrap()
+            .is_strictly_optimistic());
+
+        // Hold the only permit, so that the payload verification stays in flight.
+        let permit = harness
+            .chain
+            .payload_verification_permits
+            .acquire()
+            .await
+            .unwrap();
+
+        harness.advance_slot();
+        let slot = harness.get_current_slot();
+        let (block, _) = harness.make_block(parent.beacon_state.clone(), slot).await;
+        let block = Arc::new(block);
+        let block_root = block.canonical_root();
+        let execution_pending = block
+            .into_execution_pending_block(block_root, &harness.chain, NotifyExecutionLayer::Yes)
+            .unwrap();
+        while harness.chain.pending_payload_verifications.is_empty() {
+            tokio::task::yield_now().await;
         }
+
+        // Invalidating the parent makes the block irrelevant, which cancels its verification.
+        harness
+            .chain
+            .process_invalid_execution_payload(&InvalidationOperation::InvalidateOne {
+                block_root: parent.beacon_block_root,
+            })
+            .await
+            .unwrap();
+        assert!(harness.chain.pending_payload_verifications.is_empty());
+        assert!(matches!(
+            execution_pending
+                .payload_verification_handle
+                .outcome()
+                .await,
+            Err(BlockError::ExecutionPayloadError(
+                ExecutionPayloadError::VerificationCancelled
+            ))
+        ));
+        drop(permit);
     }
 }
@@ -0,0 +1,192 @@
+//! Provides `BeaconChain::verify_block_with_report`, which fully verifies a block whilst recording
+//! the outcome and duration of each stage of verification.
+//!
+//! This is intended for diagnostics rather than the live node. It drives the same verification
+//! functions as block import, observing them via `VerificationProgress` rather than duplicating
+//! their logic.
+use crate::block_verification::{
+    check_block_relevancy, ExecutionPendingBlock, IntoExecutionPendingBlock,
+    PayloadVerificationHandle, SignatureVerifiedBlock, VerificationProgress, VerificationStage,
+};
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, NotifyExecutionLayer};
+use futures::future;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use types::{Hash256, SignedBeaconBlock, Slot};
+
+/// The outcome of each stage of verifying a block. See `BeaconChain::verify_block_with_report`.
+///
+/// Each duration is the time taken by that stage alone. A stage which was not completed is `None`,
+/// so a block which failed verification has an `error` and a `None` duration for the stage which
+/// failed, along with every subsequent stage.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct BlockVerificationReport {
+    pub block_root: Option<Hash256>,
+    pub slot: Slot,
+    /// Checks that the block is relevant (e.g., not from the future, already known or finalized).
+    pub relevancy: Option<Duration>,
+    /// Loading the parent block and state.
+    pub parent_load: Option<Duration>,
+    /// Whether the parent was read from the snapshot cache rather than the database.
+    pub parent_snapshot_cache_hit: Option<bool>,
+    /// Verifying all of the signatures in the block.
+    pub signatures: Option<Duration>,
+    /// The number of slots through which the parent state was advanced.
+    pub catchup_slots: Option<u64>,
+    /// Advancing the parent state to the slot of the block.
+    pub catchup: Option<Duration>,
+    /// Running `per_block_processing`.
+    pub per_block_processing: Option<Duration>,
+    /// Computing the post-state root and checking it against the block.
+    pub state_root: Option<Duration>,
+    /// Waiting for the execution payload to be verified, once the other stages were complete.
+    pub payload: Option<Duration>,
+    /// The `PayloadVerificationStatus` of the execution payload.
+    pub payload_status: Option<String>,
+    /// The error which caused verification to fail, if any.
+    pub error: Option<String>,
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Fully verifies `block` as per `Self::process_block`, returning the `ExecutionPendingBlock`
+    /// (which may be imported) along with a report of each stage of verification.
+    ///
+    /// Unlike `Self::process_block`, the execution payload verification is awaited before
+    /// returning, so that its outcome can be reported. The returned block holds the outcome, so
+    /// importing it does not wait for the payload again.
+    ///
+    /// This is not intended for use on the hot path, see `BlockVerificationReport`.
+    pub async fn verify_block_with_report(
+        self: &Arc<Self>,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    ) -> (
+        Result<ExecutionPendingBlock<T>, BlockError<T::EthSpec>>,
+        BlockVerificationReport,
+    ) {
+        let chain = self.clone();
+        let join_result = self
+            .task_executor
+            .clone()
+            .spawn_blocking_handle(
+                move || {
+                    let mut report = BlockVerificationReport {
+                        slot: block.slot(),
+                        ..BlockVerificationReport::default()
+                    };
+                    let result = chain.verify_block_with_report_blocking(block, &mut report);
+                    (result, report)
+                },
+                "verify_block_with_report",
+            )
+            .ok_or(BeaconChainError::RuntimeShutdown);
+
+        let (result, mut report) = match join_result {
+            Ok(handle) => match handle.await {
+                Ok(result_and_report) => result_and_report,
+                Err(e) => (
+                    Err(BeaconChainError::TokioJoin(e).into()),
+                    BlockVerificationReport::default(),
+                ),
+            },
+            Err(e) => (Err(e.into()), BlockVerificationReport::default()),
+        };
+
+        let result = match result {
+            Ok(mut execution_pending) => {
+                let payload_timer = Instant::now();
+                match execution_pending
+                    .payload_verification_handle
+                    .outcome()
+                    .await
+                {
+                    Ok(outcome) => {
+                        report.payload = Some(payload_timer.elapsed());
+                        report.payload_status =
+                            Some(format!("{:?}", outcome.payload_verification_status));
+                        execution_pending.payload_verification_handle =
+                            PayloadVerificationHandle::Deferred(Box::pin(future::ready(Ok(
+                                outcome,
+                            ))));
+                        Ok(execution_pending)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = &result {
+            report.error = Some(format!("{:?}", e));
+        }
+
+        (result, report)
+    }
+
+    fn verify_block_with_report_blocking(
+        self: &Arc<Self>,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        report: &mut BlockVerificationReport,
+    ) -> Result<ExecutionPendingBlock<T>, BlockError<T::EthSpec>> {
+        let mut lap_start = Instant::now();
+        let mut lap = || {
+            let now = Instant::now();
+            let elapsed = now.duration_since(lap_start);
+            lap_start = now;
+            elapsed
+        };
+
+        let block_root = check_block_relevancy(&block, None, self, None)?;
+        report.block_root = Some(block_root);
+        report.relevancy = Some(lap());
+
+        let signature_verified = SignatureVerifiedBlock::new_observing_parent(
+            block,
+            block_root,
+            self,
+            None,
+            &mut |parent, snapshot_cache_hit| {
+                report.parent_load = Some(lap());
+                report.parent_snapshot_cache_hit = Some(snapshot_cache_hit);
+                report.catchup_slots = Some(
+                    report
+                        .slot
+                        .as_u64()
+                        .saturating_sub(parent.pre_state.slot().as_u64()),
+                );
+            },
+        )?;
+        report.signatures = Some(lap());
+
+        // The remaining stages are timed relative to one another, starting from the creation of
+        // the `VerificationProgress`.
+        let stages = RefCell::new(vec![]);
+        let result = signature_verified.into_execution_pending_block_with_progress(
+            block_root,
+            self,
+            NotifyExecutionLayer::Yes,
+            &VerificationProgress::new(&|stage, elapsed| {
+                stages.borrow_mut().push((stage, elapsed))
+            }),
+        );
+
+        let mut previous = Duration::ZERO;
+        for (stage, elapsed) in stages.into_inner() {
+            let field = match stage {
+                VerificationStage::CatchupDone => &mut report.catchup,
+                VerificationStage::PerBlockDone => &mut report.per_block_processing,
+                VerificationStage::StateRootOk => &mut report.state_root,
+                // The time taken to submit the payload is included in the catchup, which starts
+                // immediately afterwards.
+                VerificationStage::GossipOk
+                | VerificationStage::SignaturesOk
+                | VerificationStage::PayloadSubmitted => continue,
+            };
+            *field = Some(elapsed.saturating_sub(previous));
+            previous = elapsed;
+        }
+
+        result
+    }
+}
@@ -1,4 +1,6 @@
 use crate::beacon_chain::{CanonicalHead, BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, OP_POOL_DB_KEY};
+use crate::block_verification::{ExecutionPendingHook, ProposerFilter};
+use crate::committee_state_cache::CommitteeStateCache;
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::eth1_finalization_cache::Eth1FinalizationCache;
 use crate::fork_choice_signal::ForkChoiceSignalTx;
@@ -6,6 +8,7 @@ use crate::fork_revert::{reset_fork_choice_to_finalization, revert_to_fork_bound
 use crate::head_tracker::HeadTracker;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
 use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::proposer_rate_limiter::ProposerRateLimiter;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
 use crate::timeout_rw_lock::TimeoutRwLock;
@@ -32,6 +35,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use store::{Error as StoreError, HotColdDB, ItemStore, KeyValueStoreOp};
 use task_executor::{ShutdownReason, TaskExecutor};
+use tokio::sync::Semaphore;
 use types::{
     BeaconBlock, BeaconState, ChainSpec, Checkpoint, Epoch, EthSpec, Graffiti, Hash256,
     PublicKeyBytes, Signature, SignedBeaconBlock, Slot,
@@ -91,6 +95,8 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     log: Option<Logger>,
     graffiti: Graffiti,
     slasher: Option<Arc<Slasher<T::EthSpec>>>,
+    proposer_filter: Option<ProposerFilter>,
+    on_execution_pending: Option<ExecutionPendingHook<T>>,
     validator_monitor: Option<ValidatorMonitor<T::EthSpec>>,
     // Pending I/O batch that is constructed during building and should be executed atomically
     // alongside `PersistedBeaconChain` storage when `BeaconChainBuilder::build` is called.
@@ -132,6 +138,8 @@ where
             log: None,
             graffiti: Graffiti::default(),
             slasher: None,
+            proposer_filter: None,
+            on_execution_pending: None,
             validator_monitor: None,
             pending_io_batch: vec![],
             task_executor: None,
@@ -205,6 +213,20 @@ where
         self
     }
 
+    /// Sets a filter which causes gossip blocks to be refused if it returns `false` for their
+    /// proposer index.
+    pub fn proposer_filter(mut self, proposer_filter: ProposerFilter) -> Self {
+        self.proposer_filter = Some(proposer_filter);
+        self
+    }
+
+    /// Sets a callback which is run on each block which passes full verification, prior to
+    /// import. See `ExecutionPendingHook` for the constraints on the callback.
+    pub fn on_execution_pending(mut self, hook: ExecutionPendingHook<T>) -> Self {
+        self.on_execution_pending = Some(hook);
+        self
+    }
+
     /// Sets the logger.
     ///
     /// Should generally be called early in the build chain.
@@ -801,6 +823,18 @@ where
         let head_for_snapshot_cache = head_snapshot.clone();
         let canonical_head = CanonicalHead::new(fork_choice, Arc::new(head_snapshot));
         let shuffling_cache_size = self.chain_config.shuffling_cache_size;
+        let max_concurrent_payload_verifications =
+            self.chain_config.max_concurrent_payload_verifications;
+        if max_concurrent_payload_verifications == 0 {
+            return Err("max_concurrent_payload_verifications must be non-zero".into());
+        }
+        let committee_state_cache_size = self.chain_config.committee_state_cache_size;
+        let proposer_rate_limiter = self.chain_config.gossip_proposer_rate_limit.map(|limit| {
+            ProposerRateLimiter::new(
+                limit,
+                self.chain_config.gossip_proposer_rate_limit_window_slots,
+            )
+        });
 
         // Calculate the weak subjectivity point in which to backfill blocks to.
         let genesis_backfill_slot = if self.chain_config.genesis_backfill {
@@ -883,8 +917,11 @@ where
             )),
             eth1_finalization_cache: TimeoutRwLock::new(Eth1FinalizationCache::new(log.clone())),
             beacon_proposer_cache: <_>::default(),
+            committee_state_cache: CommitteeStateCache::new(committee_state_cache_size),
             block_times_cache: <_>::default(),
             pre_finalization_block_cache: <_>::default(),
+            pending_payload_verifications: <_>::default(),
+            payload_verification_permits: Semaphore::new(max_concurrent_payload_verifications),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
             attester_cache: <_>::default(),
             early_attester_cache: <_>::default(),
@@ -894,6 +931,10 @@ where
             log: log.clone(),
             graffiti: self.graffiti,
             slasher: self.slasher.clone(),
+            proposer_filter: self.proposer_filter,
+            on_execution_pending: self.on_execution_pending,
+            proposer_rate_limiter,
+            rejected_block_cache: <_>::default(),
             validator_monitor: RwLock::new(validator_monitor),
             genesis_backfill_slot,
         };
@@ -1007,6 +1007,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.attester_cache
             .prune_below(new_view.finalized_checkpoint.epoch);
 
+        self.committee_state_cache
+            .prune_below(new_view.finalized_checkpoint.epoch);
+
+        self.cancel_irrelevant_payload_verifications();
+
         if let Some(event_handler) = self.event_handler.as_ref() {
             if event_handler.has_finalized_subscribers() {
                 event_handler.register(EventKind::FinalizedCheckpoint(SseFinalizedCheckpoint {
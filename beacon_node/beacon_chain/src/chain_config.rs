@@ -1,5 +1,6 @@
 pub use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
 use types::{Checkpoint, Epoch, ProgressiveBalancesMode};
 
@@ -15,6 +16,19 @@ pub const DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR: u32 = 3;
 /// Fraction of a slot lookahead for fork choice in the state advance timer (500ms on mainnet).
 pub const FORK_CHOICE_LOOKAHEAD_FACTOR: u32 = 24;
 
+/// Default maximum number of skipped slots between a block and its parent (around 9 days on
+/// mainnet). No blocks at all are produced during such a gap, so it is well beyond any realistic
+/// period of non-finality.
+pub const DEFAULT_BLOCK_MAX_SKIP_SLOTS: u64 = 1 << 16;
+
+/// Default number of slots for which a block which conflicts with finality is ignored rather than
+/// penalized, see `ChainConfig::not_finalized_descendant_grace_slots`.
+pub const DEFAULT_NOT_FINALIZED_DESCENDANT_GRACE_SLOTS: u64 = 2;
+
+/// Default maximum number of execution payloads sent to the execution engine for verification at
+/// once, see `ChainConfig::max_concurrent_payload_verifications`.
+pub const DEFAULT_MAX_CONCURRENT_PAYLOAD_VERIFICATIONS: usize = 16;
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct ChainConfig {
     /// Maximum number of slots to skip when importing an attestation.
@@ -72,6 +86,9 @@ pub struct ChainConfig {
     pub optimistic_finalized_sync: bool,
     /// The size of the shuffling cache,
     pub shuffling_cache_size: usize,
+    /// The number of cheaply-advanced states to cache for determining the committees of blocks
+    /// whose parent state is in a prior epoch. A value of zero (the default) disables the cache.
+    pub committee_state_cache_size: usize,
     /// If using a weak-subjectivity sync, whether we should download blocks all the way back to
     /// genesis.
     pub genesis_backfill: bool,
@@ -83,6 +100,87 @@ pub struct ChainConfig {
     pub progressive_balances_mode: ProgressiveBalancesMode,
     /// Number of epochs between each migration of data from the hot database to the freezer.
     pub epochs_per_migration: u64,
+    /// The clock disparity tolerated when rejecting blocks from future slots.
+    ///
+    /// If `None`, gossip verification tolerates `MAXIMUM_GOSSIP_CLOCK_DISPARITY` whilst the
+    /// relevancy checks performed prior to import tolerate no disparity at all. This means a block
+    /// which is slightly early may be accepted via gossip but rejected at import. If `Some`, the
+    /// same tolerance is applied to both checks.
+    ///
+    /// The tolerance used by range sync when deciding how to log a future block is separate and is
+    /// not affected by this value.
+    pub future_block_tolerance: Option<Duration>,
+    /// If set, the pre-state, post-state and block are written as SSZ files to this directory
+    /// whenever a block is processed.
+    ///
+    /// This is intended for debugging and the directory is never pruned.
+    pub block_processing_ssz_dir: Option<PathBuf>,
+    /// The maximum number of distinct blocks accepted via gossip from a single proposer within
+    /// each window of `gossip_proposer_rate_limit_window_slots` slots.
+    ///
+    /// If `None`, there is no limit.
+    pub gossip_proposer_rate_limit: Option<usize>,
+    /// The length of the window used by `gossip_proposer_rate_limit`, in slots.
+    pub gossip_proposer_rate_limit_window_slots: u64,
+    /// Maximum number of slots that may be skipped between a block and its parent.
+    ///
+    /// This protects against blocks which would force us to advance the parent state through a
+    /// very large number of slots. If `None`, there is no limit.
+    pub block_max_skip_slots: Option<u64>,
+    /// If `true`, a copy of the state immediately prior to `per_block_processing` is attached to
+    /// each `ExecutionPendingBlock` as `pre_block_state`.
+    ///
+    /// This is intended for tooling which diffs the changes made by each block. It doubles the
+    /// number of states held in memory by each block being verified, so it should not be enabled
+    /// on a live node.
+    pub retain_pre_block_state: bool,
+    /// If `true`, a gossip block which is not from the proposer in the proposer cache causes the
+    /// proposer to be re-derived from the parent state, logging an error if the cache was wrong.
+    ///
+    /// This is a diagnostic for the proposer cache. It requires a state to be loaded from the
+    /// database for each such block, so it should not be enabled on a live node.
+    pub recheck_cached_proposer_on_mismatch: bool,
+    /// If `true`, the parent of each block being verified is always loaded from the database
+    /// rather than the snapshot cache.
+    ///
+    /// The contents of the snapshot cache depend on the history of the node, so this makes block
+    /// verification reproducible for a given database when debugging. It is much slower, so it
+    /// should not be enabled on a live node.
+    pub load_parent_from_db: bool,
+    /// Gossip blocks which do not descend from finalization, but which are within this many
+    /// slots of the current slot, are ignored without penalizing the peer which sent them.
+    ///
+    /// Whilst finalization is advancing, a valid competing block may conflict with the new
+    /// finalized checkpoint by the time it arrives. Older blocks are still penalized.
+    pub not_finalized_descendant_grace_slots: u64,
+    /// If `true`, the `ConsensusContext` handed between the stages of block verification is
+    /// checked against the block before the state transition.
+    ///
+    /// The context is constructed alongside the block, so a mismatch indicates a bug in Lighthouse.
+    /// This is intended for debugging and testing.
+    pub verify_consensus_context: bool,
+    /// If `true`, a block whose state root does not match the computed state root is checked
+    /// against the states in the database, warning if it claims the root of a known state.
+    ///
+    /// This is a heuristic for detecting crafted blocks. It only logs, since such blocks are
+    /// already rejected by the state root check, and it costs a database read for each mismatch.
+    pub detect_known_state_roots: bool,
+    /// Maximum number of execution payloads which may be awaiting verification by the execution
+    /// engine at once. Further payloads wait until an earlier verification completes.
+    ///
+    /// This prevents a flood of blocks from overwhelming the execution engine. Must be non-zero.
+    pub max_concurrent_payload_verifications: usize,
+    /// Number of times block verification retries reading the validator pubkey cache after its
+    /// lock times out, before failing with `ValidatorPubkeyCacheLockTimeout`.
+    ///
+    /// Retrying avoids dropping blocks due to transient lock contention.
+    pub validator_pubkey_cache_lock_retries: usize,
+    /// Test-only: treat this as the present slot when checking blocks against the slot clock
+    /// (see `check_block_against_present_slot`), instead of reading the `SlotClock`.
+    ///
+    /// The override already accounts for any clock disparity, so no tolerance is added to it.
+    #[cfg(test)]
+    pub present_slot_override: Option<types::Slot>,
 }
 
 impl Default for ChainConfig {
@@ -110,10 +208,27 @@ impl Default for ChainConfig {
             // This value isn't actually read except in tests.
             optimistic_finalized_sync: true,
             shuffling_cache_size: crate::shuffling_cache::DEFAULT_CACHE_SIZE,
+            committee_state_cache_size: crate::committee_state_cache::DEFAULT_CACHE_SIZE,
             genesis_backfill: false,
             always_prepare_payload: false,
             progressive_balances_mode: ProgressiveBalancesMode::Checked,
             epochs_per_migration: crate::migrate::DEFAULT_EPOCHS_PER_MIGRATION,
+            future_block_tolerance: None,
+            block_processing_ssz_dir: None,
+            gossip_proposer_rate_limit: None,
+            gossip_proposer_rate_limit_window_slots:
+                crate::proposer_rate_limiter::DEFAULT_WINDOW_SLOTS,
+            block_max_skip_slots: Some(DEFAULT_BLOCK_MAX_SKIP_SLOTS),
+            retain_pre_block_state: false,
+            recheck_cached_proposer_on_mismatch: false,
+            load_parent_from_db: false,
+            not_finalized_descendant_grace_slots: DEFAULT_NOT_FINALIZED_DESCENDANT_GRACE_SLOTS,
+            verify_consensus_context: false,
+            detect_known_state_roots: false,
+            max_concurrent_payload_verifications: DEFAULT_MAX_CONCURRENT_PAYLOAD_VERIFICATIONS,
+            validator_pubkey_cache_lock_retries: 0,
+            #[cfg(test)]
+            present_slot_override: None,
         }
     }
 }
@@ -0,0 +1,168 @@
+//! Provides a small cache of states which have been cheaply advanced to the start of some epoch in
+//! order to obtain the committees and proposers for that epoch.
+//!
+//! When a block arrives whose parent state is in an earlier epoch, we must clone the parent state
+//! and advance it through the epoch boundary before we can determine the block's proposer or
+//! verify its signatures. Sibling blocks (e.g., competing blocks after a skip slot) would otherwise
+//! repeat this work.
+//!
+//! ## Warning
+//!
+//! The states stored in this cache are **not valid beacon states**. They are advanced with
+//! `partial_state_advance`, so their state roots (and the block roots of any skipped slots) are
+//! bogus. They must only ever be used to derive committees and proposers.
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use types::{BeaconState, CloneConfig, Epoch, EthSpec, Hash256};
+
+/// The default number of advanced states to cache.
+///
+/// The cache is disabled by default, since each cached state is a full `BeaconState`.
+pub const DEFAULT_CACHE_SIZE: usize = 0;
+
+/// The key is the root of the state *prior* to advancement and the epoch it was advanced to.
+type CacheKey = (Hash256, Epoch);
+
+/// Caches states which are only valid for obtaining committees and proposers.
+///
+/// See the module-level documentation for more information.
+pub struct CommitteeStateCache<E: EthSpec> {
+    /// Set to `None` if the cache is disabled.
+    cache: Option<Mutex<LruCache<CacheKey, Arc<BeaconState<E>>>>>,
+}
+
+impl<E: EthSpec> CommitteeStateCache<E> {
+    /// Instantiate a cache with capacity for `size` states. A `size` of zero disables the cache.
+    pub fn new(size: usize) -> Self {
+        Self {
+            cache: (size > 0).then(|| Mutex::new(LruCache::new(size))),
+        }
+    }
+
+    /// Returns a copy of the state with root `state_root` after it was advanced to `epoch`, if
+    /// one is cached.
+    ///
+    /// The state is copied after the lock is released, so concurrent callers are not blocked.
+    pub fn get(&self, state_root: Hash256, epoch: Epoch) -> Option<BeaconState<E>> {
+        let state = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.lock().get(&(state_root, epoch)).cloned())?;
+        Some(state.clone_with(CloneConfig::committee_caches_only()))
+    }
+
+    /// Store `state`, which is the state with root `state_root` after it was advanced to `epoch`.
+    pub fn insert(&self, state_root: Hash256, epoch: Epoch, state: &BeaconState<E>) {
+        if let Some(cache) = self.cache.as_ref() {
+            let state = Arc::new(state.clone_with(CloneConfig::committee_caches_only()));
+            cache.lock().put((state_root, epoch), state);
+        }
+    }
+
+    /// Remove all states advanced to an epoch lower than the given `epoch`.
+    ///
+    /// Generally, the provided `epoch` should be the finalized epoch.
+    pub fn prune_below(&self, epoch: Epoch) {
+        if let Some(cache) = self.cache.as_ref() {
+            let mut cache = cache.lock();
+            let stale_keys = cache
+                .iter()
+                .map(|(key, _)| *key)
+                .filter(|(_, target_epoch)| *target_epoch < epoch)
+                .collect::<Vec<_>>();
+            for key in stale_keys {
+                cache.pop(&key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{ChainSpec, Eth1Data, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn root(i: u64) -> Hash256 {
+        Hash256::from_low_u64_be(i)
+    }
+
+    /// A state which is identified by its `genesis_time`.
+    fn state(id: u64, spec: &ChainSpec) -> BeaconState<E> {
+        BeaconState::new(id, Eth1Data::default(), spec)
+    }
+
+    fn cached_id(cache: &CommitteeStateCache<E>, state_root: Hash256, epoch: Epoch) -> Option<u64> {
+        cache
+            .get(state_root, epoch)
+            .map(|state| state.genesis_time())
+    }
+
+    #[test]
+    fn hits_and_misses() {
+        let spec = E::default_spec();
+        let cache = CommitteeStateCache::<E>::new(4);
+
+        assert_eq!(cached_id(&cache, root(1), Epoch::new(1)), None);
+
+        cache.insert(root(1), Epoch::new(1), &state(1, &spec));
+        cache.insert(root(1), Epoch::new(2), &state(2, &spec));
+
+        assert_eq!(cached_id(&cache, root(1), Epoch::new(1)), Some(1));
+        assert_eq!(cached_id(&cache, root(1), Epoch::new(2)), Some(2));
+        assert_eq!(
+            cached_id(&cache, root(1), Epoch::new(3)),
+            None,
+            "a different epoch misses"
+        );
+        assert_eq!(
+            cached_id(&cache, root(2), Epoch::new(1)),
+            None,
+            "a different root misses"
+        );
+    }
+
+    #[test]
+    fn disabled() {
+        let spec = E::default_spec();
+        let cache = CommitteeStateCache::<E>::new(0);
+
+        cache.insert(root(1), Epoch::new(1), &state(1, &spec));
+        assert_eq!(cached_id(&cache, root(1), Epoch::new(1)), None);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let spec = E::default_spec();
+        let cache = CommitteeStateCache::<E>::new(2);
+
+        cache.insert(root(1), Epoch::new(1), &state(1, &spec));
+        cache.insert(root(2), Epoch::new(1), &state(2, &spec));
+
+        // Reading the first state makes the second the least recently used.
+        assert_eq!(cached_id(&cache, root(1), Epoch::new(1)), Some(1));
+        cache.insert(root(3), Epoch::new(1), &state(3, &spec));
+
+        assert_eq!(cached_id(&cache, root(1), Epoch::new(1)), Some(1));
+        assert_eq!(cached_id(&cache, root(2), Epoch::new(1)), None);
+        assert_eq!(cached_id(&cache, root(3), Epoch::new(1)), Some(3));
+    }
+
+    #[test]
+    fn prune_below() {
+        let spec = E::default_spec();
+        let cache = CommitteeStateCache::<E>::new(4);
+
+        for epoch in 1..=3 {
+            cache.insert(root(epoch), Epoch::new(epoch), &state(epoch, &spec));
+        }
+        cache.prune_below(Epoch::new(2));
+
+        assert_eq!(cached_id(&cache, root(1), Epoch::new(1)), None);
+        assert_eq!(cached_id(&cache, root(2), Epoch::new(2)), Some(2));
+        assert_eq!(cached_id(&cache, root(3), Epoch::new(3)), Some(3));
+    }
+}
@@ -83,6 +83,11 @@ pub enum BeaconChainError {
     InvariantViolated(String),
     SszTypesError(SszTypesError),
     NoProposerForSlot(Slot),
+    ProposerShufflingDecisionRootMismatch {
+        expected: Hash256,
+        actual: Hash256,
+        epoch: Epoch,
+    },
     CanonicalHeadLockTimeout,
     AttestationCacheLockTimeout,
     ValidatorPubkeyCacheLockTimeout,
@@ -126,6 +131,14 @@ pub enum BeaconChainError {
         block_slot: Slot,
         state_slot: Slot,
     },
+    ConsensusContextMismatch {
+        block_root: Hash256,
+        block_slot: Slot,
+        block_proposer_index: u64,
+        context_block_root: Option<Hash256>,
+        context_slot: Slot,
+        context_proposer_index: Option<u64>,
+    },
     HistoricalBlockError(HistoricalBlockError),
     InvalidStateForShuffling {
         state_epoch: Epoch,
@@ -166,6 +179,7 @@ pub enum BeaconChainError {
     BlockRewardSlotError,
     BlockRewardAttestationError,
     BlockRewardSyncError,
+    DryRunBlockRewardMissing,
     SyncCommitteeRewardsSyncError,
     AttestationRewardsError,
     HeadMissingFromForkChoice(Hash256),
@@ -198,6 +212,7 @@ pub enum BeaconChainError {
     },
     RuntimeShutdown,
     TokioJoin(tokio::task::JoinError),
+    ChainSegmentCancelled,
     ProcessInvalidExecutionPayload(JoinError),
     ForkChoiceSignalOutOfOrder {
         current: Slot,
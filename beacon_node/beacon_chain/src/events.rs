@@ -1,4 +1,6 @@
-pub use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead};
+pub use eth2::types::{
+    EventKind, SseBlock, SseDelayedEquivocatingBlock, SseFinalizedCheckpoint, SseHead,
+};
 use slog::{trace, Logger};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::{error::SendError, Receiver, Sender};
@@ -16,6 +18,9 @@ pub struct ServerSentEventHandler<T: EthSpec> {
     contribution_tx: Sender<EventKind<T>>,
     payload_attributes_tx: Sender<EventKind<T>>,
     late_head: Sender<EventKind<T>>,
+    block_equivocation_tx: Sender<EventKind<T>>,
+    optimistic_block_rejected_tx: Sender<EventKind<T>>,
+    delayed_equivocating_block_tx: Sender<EventKind<T>>,
     block_reward_tx: Sender<EventKind<T>>,
     log: Logger,
 }
@@ -38,6 +43,9 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         let (contribution_tx, _) = broadcast::channel(capacity);
         let (payload_attributes_tx, _) = broadcast::channel(capacity);
         let (late_head, _) = broadcast::channel(capacity);
+        let (block_equivocation_tx, _) = broadcast::channel(capacity);
+        let (optimistic_block_rejected_tx, _) = broadcast::channel(capacity);
+        let (delayed_equivocating_block_tx, _) = broadcast::channel(capacity);
         let (block_reward_tx, _) = broadcast::channel(capacity);
 
         Self {
@@ -50,6 +58,9 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
             contribution_tx,
             payload_attributes_tx,
             late_head,
+            block_equivocation_tx,
+            optimistic_block_rejected_tx,
+            delayed_equivocating_block_tx,
             block_reward_tx,
             log,
         }
@@ -101,6 +112,18 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
                 .late_head
                 .send(kind)
                 .map(|count| log_count("late head", count)),
+            EventKind::BlockEquivocation(_) => self
+                .block_equivocation_tx
+                .send(kind)
+                .map(|count| log_count("block equivocation", count)),
+            EventKind::OptimisticBlockRejected(_) => self
+                .optimistic_block_rejected_tx
+                .send(kind)
+                .map(|count| log_count("optimistic block rejected", count)),
+            EventKind::DelayedEquivocatingBlock(_) => self
+                .delayed_equivocating_block_tx
+                .send(kind)
+                .map(|count| log_count("delayed equivocating block", count)),
             EventKind::BlockReward(_) => self
                 .block_reward_tx
                 .send(kind)
@@ -147,6 +170,18 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         self.late_head.subscribe()
     }
 
+    pub fn subscribe_block_equivocation(&self) -> Receiver<EventKind<T>> {
+        self.block_equivocation_tx.subscribe()
+    }
+
+    pub fn subscribe_optimistic_block_rejected(&self) -> Receiver<EventKind<T>> {
+        self.optimistic_block_rejected_tx.subscribe()
+    }
+
+    pub fn subscribe_delayed_equivocating_block(&self) -> Receiver<EventKind<T>> {
+        self.delayed_equivocating_block_tx.subscribe()
+    }
+
     pub fn subscribe_block_reward(&self) -> Receiver<EventKind<T>> {
         self.block_reward_tx.subscribe()
     }
@@ -187,6 +222,18 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         self.late_head.receiver_count() > 0
     }
 
+    pub fn has_block_equivocation_subscribers(&self) -> bool {
+        self.block_equivocation_tx.receiver_count() > 0
+    }
+
+    pub fn has_optimistic_block_rejected_subscribers(&self) -> bool {
+        self.optimistic_block_rejected_tx.receiver_count() > 0
+    }
+
+    pub fn has_delayed_equivocating_block_subscribers(&self) -> bool {
+        self.delayed_equivocating_block_tx.receiver_count() > 0
+    }
+
     pub fn has_block_reward_subscribers(&self) -> bool {
         self.block_reward_tx.receiver_count() > 0
     }
@@ -9,19 +9,21 @@
 
 use crate::otb_verification_service::OptimisticTransitionBlock;
 use crate::{
-    BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, BlockProductionError,
+    metrics, BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, BlockProductionError,
     ExecutionPayloadError,
 };
 use execution_layer::{BlockProposalContents, BuilderParams, PayloadAttributes, PayloadStatus};
 use fork_choice::{InvalidationOperation, PayloadVerificationStatus};
+use futures::future::{self, Either};
 use proto_array::{Block as ProtoBlock, ExecutionStatus};
 use slog::{debug, warn};
-use slot_clock::SlotClock;
 use state_processing::per_block_processing::{
     compute_timestamp_at_slot, get_expected_withdrawals, is_execution_enabled,
     is_merge_transition_complete, partially_verify_execution_payload,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::SemaphorePermit;
 use tokio::task::JoinHandle;
 use tree_hash::TreeHash;
 use types::*;
@@ -46,10 +48,18 @@ pub enum NotifyExecutionLayer {
     No,
 }
 
+/// Payload verification outcomes which are already known (e.g., from a prior sync), keyed by the
+/// execution block hash of the payload.
+///
+/// A payload with a known status will not be sent to the EL, regardless of the
+/// `NotifyExecutionLayer` value.
+pub type KnownPayloadStatuses = HashMap<ExecutionBlockHash, PayloadVerificationStatus>;
+
 /// Used to await the result of executing payload with a remote EE.
 pub struct PayloadNotifier<T: BeaconChainTypes> {
     pub chain: Arc<BeaconChain<T>>,
     pub block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    pub block_root: Hash256,
     payload_verification_status: Option<PayloadVerificationStatus>,
 }
 
@@ -57,8 +67,10 @@ impl<T: BeaconChainTypes> PayloadNotifier<T> {
     pub fn new(
         chain: Arc<BeaconChain<T>>,
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
         state: &BeaconState<T::EthSpec>,
         notify_execution_layer: NotifyExecutionLayer,
+        known_payload_statuses: Option<&KnownPayloadStatuses>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
         let payload_verification_status = if is_execution_enabled(state, block.message().body()) {
             // Perform the initial stages of payload verification.
@@ -77,31 +89,46 @@ impl<T: BeaconChainTypes> PayloadNotifier<T> {
             )
             .map_err(BlockError::PerBlockProcessingError)?;
 
-            match notify_execution_layer {
-                NotifyExecutionLayer::No if chain.config.optimistic_finalized_sync => {
-                    // Verify the block hash here in Lighthouse and immediately mark the block as
-                    // optimistically imported. This saves a lot of roundtrips to the EL.
-                    let execution_layer = chain
-                        .execution_layer
-                        .as_ref()
-                        .ok_or(ExecutionPayloadError::NoExecutionConnection)?;
-
-                    if let Err(e) =
-                        execution_layer.verify_payload_block_hash(payload.execution_payload_ref())
-                    {
-                        warn!(
-                            chain.log,
-                            "Falling back to slow block hash verification";
-                            "block_number" => payload.block_number(),
-                            "info" => "you can silence this warning with --disable-optimistic-finalized-sync",
-                            "error" => ?e,
-                        );
-                        None
-                    } else {
-                        Some(PayloadVerificationStatus::Optimistic)
+            if let Some(known_status) =
+                known_payload_statuses.and_then(|statuses| statuses.get(&payload.block_hash()))
+            {
+                // The outcome of this payload is already known, there's no need to ask the EL. A
+                // payload which reaches this point is never irrelevant, so a caller claiming
+                // otherwise has supplied the wrong statuses.
+                if *known_status == PayloadVerificationStatus::Irrelevant {
+                    return Err(ExecutionPayloadError::IrrelevantKnownPayloadStatus {
+                        block_hash: payload.block_hash(),
+                    }
+                    .into());
+                }
+                Some(*known_status)
+            } else {
+                match notify_execution_layer {
+                    NotifyExecutionLayer::No if chain.config.optimistic_finalized_sync => {
+                        // Verify the block hash here in Lighthouse and immediately mark the block
+                        // as optimistically imported. This saves a lot of roundtrips to the EL.
+                        let execution_layer = chain
+                            .execution_layer
+                            .as_ref()
+                            .ok_or(ExecutionPayloadError::NoExecutionConnection)?;
+
+                        if let Err(e) = execution_layer
+                            .verify_payload_block_hash(payload.execution_payload_ref())
+                        {
+                            warn!(
+                                chain.log,
+                                "Falling back to slow block hash verification";
+                                "block_number" => payload.block_number(),
+                                "info" => "you can silence this warning with --disable-optimistic-finalized-sync",
+                                "error" => ?e,
+                            );
+                            None
+                        } else {
+                            Some(PayloadVerificationStatus::Optimistic)
+                        }
                     }
+                    _ => None,
                 }
-                _ => None,
             }
         } else {
             Some(PayloadVerificationStatus::Irrelevant)
@@ -110,18 +137,84 @@ impl<T: BeaconChainTypes> PayloadNotifier<T> {
         Ok(Self {
             chain,
             block,
+            block_root,
             payload_verification_status,
         })
     }
 
+    /// Returns the verification status of the payload, sending it to the EL if required.
+    ///
+    /// Whilst the EL is processing the payload the verification is tracked by
+    /// `BeaconChain::pending_payload_verifications`. If the block becomes irrelevant in the
+    /// meantime (see `BeaconChain::cancel_irrelevant_payload_verifications`) this function returns
+    /// early with `ExecutionPayloadError::VerificationCancelled`.
     pub async fn notify_new_payload(
         self,
     ) -> Result<PayloadVerificationStatus, BlockError<T::EthSpec>> {
         if let Some(precomputed_status) = self.payload_verification_status {
-            Ok(precomputed_status)
-        } else {
-            notify_new_payload(&self.chain, self.block.message()).await
+            return Ok(precomputed_status);
         }
+
+        // The verification stops being tracked once `registration` is dropped.
+        let registration = self.chain.pending_payload_verifications.register(
+            self.block_root,
+            self.block.slot(),
+            self.block.parent_root(),
+        );
+        // Waiting for a permit is also cancellable, the block may become irrelevant whilst the
+        // execution engine is busy with other payloads.
+        let verification = async {
+            let _permit = PayloadVerificationPermit::acquire(&self.chain).await?;
+            notify_new_payload(&self.chain, self.block.message()).await
+        };
+        let result = match future::select(
+            Box::pin(verification),
+            Box::pin(registration.token().cancelled()),
+        )
+        .await
+        {
+            Either::Left((result, _)) => result,
+            Either::Right(((), _)) => {
+                debug!(
+                    self.chain.log,
+                    "Cancelled payload verification";
+                    "block_root" => ?self.block_root,
+                    "slot" => self.block.slot(),
+                );
+                Err(ExecutionPayloadError::VerificationCancelled.into())
+            }
+        };
+        drop(registration);
+        result
+    }
+}
+
+/// A permit to send a payload to the execution engine for verification, see
+/// `ChainConfig::max_concurrent_payload_verifications`.
+///
+/// The permit is released when this is dropped, including when the verification fails or is
+/// cancelled.
+struct PayloadVerificationPermit<'a> {
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<'a> PayloadVerificationPermit<'a> {
+    async fn acquire<T: BeaconChainTypes>(
+        chain: &'a Arc<BeaconChain<T>>,
+    ) -> Result<PayloadVerificationPermit<'a>, BlockError<T::EthSpec>> {
+        let permit = chain
+            .payload_verification_permits
+            .acquire()
+            .await
+            .map_err(|_| BeaconChainError::RuntimeShutdown)?;
+        metrics::inc_gauge(&metrics::BLOCK_PROCESSING_PAYLOAD_VERIFICATIONS_IN_FLIGHT);
+        Ok(Self { _permit: permit })
+    }
+}
+
+impl Drop for PayloadVerificationPermit<'_> {
+    fn drop(&mut self) {
+        metrics::dec_gauge(&metrics::BLOCK_PROCESSING_PAYLOAD_VERIFICATIONS_IN_FLIGHT);
     }
 }
 
@@ -201,6 +294,10 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
                         .await?;
                 }
 
+                metrics::inc_counter_vec(
+                    &metrics::BLOCK_PROCESSING_PAYLOAD_REJECTIONS,
+                    &["invalid"],
+                );
                 Err(ExecutionPayloadError::RejectedByExecutionEngine { status }.into())
             }
             PayloadStatus::InvalidBlockHash {
@@ -221,7 +318,11 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
                 // Returning an error here should be sufficient to invalidate the block. We have no
                 // information to indicate its parent is invalid, so no need to run
                 // `BeaconChain::process_invalid_execution_payload`.
-                Err(ExecutionPayloadError::RejectedByExecutionEngine { status }.into())
+                metrics::inc_counter_vec(
+                    &metrics::BLOCK_PROCESSING_PAYLOAD_REJECTIONS,
+                    &["invalid_block_hash"],
+                );
+                Err(ExecutionPayloadError::BlockHashRejectedByExecutionEngine { status }.into())
             }
         },
         Err(e) => Err(ExecutionPayloadError::RequestFailed(e).into()),
@@ -336,6 +437,34 @@ pub async fn is_optimistic_candidate_block<T: BeaconChainTypes>(
         .map_err(BeaconChainError::from)
 }
 
+/// Verify that `payload_timestamp` is the expected timestamp of an execution payload at `slot`,
+/// for a chain which started at `genesis_time`.
+///
+/// This is the timestamp check from the gossip validation rules, it does not require a
+/// `BeaconChain`.
+pub fn validate_execution_payload_timestamp(
+    genesis_time: u64,
+    slot: Slot,
+    payload_timestamp: u64,
+    spec: &ChainSpec,
+) -> Result<(), ExecutionPayloadError> {
+    // Slots are bounded well below the point of overflow by gossip verification, saturate to
+    // ensure an overflowing slot can never match.
+    let expected = slot
+        .as_u64()
+        .saturating_mul(spec.seconds_per_slot)
+        .saturating_add(genesis_time);
+
+    if payload_timestamp == expected {
+        Ok(())
+    } else {
+        Err(ExecutionPayloadError::InvalidPayloadTimestamp {
+            expected,
+            found: payload_timestamp,
+        })
+    }
+}
+
 /// Validate the gossip block's execution_payload according to the checks described here:
 /// https://github.com/ethereum/consensus-specs/blob/dev/specs/merge/p2p-interface.md#beacon_block
 pub fn validate_execution_payload_for_gossip<T: BeaconChainTypes>(
@@ -364,23 +493,13 @@ pub fn validate_execution_payload_for_gossip<T: BeaconChainTypes>(
         };
 
         if is_merge_transition_complete || !execution_payload.is_default_with_empty_roots() {
-            let expected_timestamp = chain
-                .slot_clock
-                .start_of(block.slot())
-                .map(|d| d.as_secs())
-                .ok_or(BlockError::BeaconChainError(
-                    BeaconChainError::UnableToComputeTimeAtSlot,
-                ))?;
-
             // The block's execution payload timestamp is correct with respect to the slot
-            if execution_payload.timestamp() != expected_timestamp {
-                return Err(BlockError::ExecutionPayloadError(
-                    ExecutionPayloadError::InvalidPayloadTimestamp {
-                        expected: expected_timestamp,
-                        found: execution_payload.timestamp(),
-                    },
-                ));
-            }
+            validate_execution_payload_timestamp(
+                chain.genesis_time,
+                block.slot(),
+                execution_payload.timestamp(),
+                &chain.spec,
+            )?;
         }
     }
 
@@ -556,3 +675,47 @@ where
 
     Ok(block_contents)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn payload_timestamp_at_fork_boundary() {
+        let mut spec = E::default_spec();
+        let bellatrix_fork_epoch = Epoch::new(2);
+        spec.bellatrix_fork_epoch = Some(bellatrix_fork_epoch);
+        let genesis_time = 1_606_824_023;
+
+        // The first slot of the fork is measured from genesis, not from the fork.
+        let fork_slot = bellatrix_fork_epoch.start_slot(E::slots_per_epoch());
+        let fork_timestamp = genesis_time + fork_slot.as_u64() * spec.seconds_per_slot;
+        assert!(validate_execution_payload_timestamp(
+            genesis_time,
+            fork_slot,
+            fork_timestamp,
+            &spec
+        )
+        .is_ok());
+
+        // The timestamp of the adjacent slots is invalid.
+        for timestamp in [
+            fork_timestamp - spec.seconds_per_slot,
+            fork_timestamp + spec.seconds_per_slot,
+        ] {
+            assert!(matches!(
+                validate_execution_payload_timestamp(genesis_time, fork_slot, timestamp, &spec),
+                Err(ExecutionPayloadError::InvalidPayloadTimestamp { expected, found })
+                    if expected == fork_timestamp && found == timestamp
+            ));
+        }
+    }
+
+    #[test]
+    fn payload_timestamp_overflow() {
+        let spec = E::default_spec();
+        assert!(validate_execution_payload_timestamp(0, Slot::new(u64::MAX), 0, &spec).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Provides `ExecutionPendingBlockBuilder`, for assembling an `ExecutionPendingBlock` directly from
+//! its components.
+//!
+//! This is only intended for testing the import of blocks in isolation from block verification,
+//! e.g., in property tests. The components are **not** checked for consistency with each other, so
+//! it must never be used in production. It is only compiled for tests, or with the `test_builders`
+//! feature.
+use crate::block_verification::{
+    ExecutionPendingBlock, PayloadVerificationHandle, PayloadVerificationOutcome,
+};
+use crate::{BeaconChainTypes, BlockError, DeferredForkChoiceUpdates, Eth1FinalizationData};
+use fork_choice::{ExecutionStatus, PayloadVerificationStatus};
+use futures::future;
+use state_processing::ConsensusContext;
+use std::sync::Arc;
+use types::{BeaconState, BlindedPayload, Hash256, SignedBeaconBlock};
+
+/// Builds an `ExecutionPendingBlock` from explicit components.
+///
+/// Components which are not supplied are given defaults which are consistent with `block` and
+/// `state` (see the individual setters). The payload verification handle resolves immediately to
+/// the configured outcome, which defaults to a `PayloadVerificationStatus::Verified` payload.
+pub struct ExecutionPendingBlockBuilder<T: BeaconChainTypes> {
+    block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    block_root: Option<Hash256>,
+    state: BeaconState<T::EthSpec>,
+    parent_block: SignedBeaconBlock<T::EthSpec, BlindedPayload<T::EthSpec>>,
+    parent_eth1_finalization_data: Option<Eth1FinalizationData>,
+    confirmed_state_roots: Vec<Hash256>,
+    consensus_context: Option<ConsensusContext<T::EthSpec>>,
+    payload_verification_outcome: Result<PayloadVerificationOutcome, BlockError<T::EthSpec>>,
+    skipped_slots: u64,
+    intermediate_state_roots: Vec<Hash256>,
+    parent_execution_status: Option<ExecutionStatus>,
+    pre_block_state: Option<BeaconState<T::EthSpec>>,
+    deferred_fork_choice_updates: Option<DeferredForkChoiceUpdates<T::EthSpec>>,
+}
+
+impl<T: BeaconChainTypes> ExecutionPendingBlockBuilder<T> {
+    /// Start building an `ExecutionPendingBlock` for `block`, where `state` is the state after
+    /// applying `block` and `parent_block` is its parent.
+    pub fn new(
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        state: BeaconState<T::EthSpec>,
+        parent_block: SignedBeaconBlock<T::EthSpec, BlindedPayload<T::EthSpec>>,
+    ) -> Self {
+        Self {
+            block,
+            block_root: None,
+            state,
+            parent_block,
+            parent_eth1_finalization_data: None,
+            confirmed_state_roots: vec![],
+            consensus_context: None,
+            payload_verification_outcome: Ok(PayloadVerificationOutcome {
+                payload_verification_status: PayloadVerificationStatus::Verified,
+                is_valid_merge_transition_block: false,
+            }),
+            skipped_slots: 0,
+            intermediate_state_roots: vec![],
+            parent_execution_status: None,
+            pre_block_state: None,
+            deferred_fork_choice_updates: None,
+        }
+    }
+
+    /// Defaults to the canonical root of the block.
+    pub fn block_root(mut self, block_root: Hash256) -> Self {
+        self.block_root = Some(block_root);
+        self
+    }
+
+    /// Defaults to the eth1 data and deposit index of the state.
+    pub fn parent_eth1_finalization_data(
+        mut self,
+        parent_eth1_finalization_data: Eth1FinalizationData,
+    ) -> Self {
+        self.parent_eth1_finalization_data = Some(parent_eth1_finalization_data);
+        self
+    }
+
+    /// Defaults to no confirmed states.
+    pub fn confirmed_state_roots(mut self, confirmed_state_roots: Vec<Hash256>) -> Self {
+        self.confirmed_state_roots = confirmed_state_roots;
+        self
+    }
+
+    /// Defaults to a context primed with the block root and the block's proposer index.
+    pub fn consensus_context(mut self, consensus_context: ConsensusContext<T::EthSpec>) -> Self {
+        self.consensus_context = Some(consensus_context);
+        self
+    }
+
+    /// The outcome (or error) that the payload verification handle resolves to.
+    pub fn payload_verification_outcome(
+        mut self,
+        outcome: Result<PayloadVerificationOutcome, BlockError<T::EthSpec>>,
+    ) -> Self {
+        self.payload_verification_outcome = outcome;
+        self
+    }
+
+    /// Shorthand for a successful `Self::payload_verification_outcome` with `status`, for a block
+    /// which is not the merge transition block.
+    pub fn payload_verification_status(self, status: PayloadVerificationStatus) -> Self {
+        self.payload_verification_outcome(Ok(PayloadVerificationOutcome {
+            payload_verification_status: status,
+            is_valid_merge_transition_block: false,
+        }))
+    }
+
+    pub fn skipped_slots(mut self, skipped_slots: u64) -> Self {
+        self.skipped_slots = skipped_slots;
+        self
+    }
+
+    pub fn intermediate_state_roots(mut self, intermediate_state_roots: Vec<Hash256>) -> Self {
+        self.intermediate_state_roots = intermediate_state_roots;
+        self
+    }
+
+    pub fn parent_execution_status(mut self, parent_execution_status: ExecutionStatus) -> Self {
+        self.parent_execution_status = Some(parent_execution_status);
+        self
+    }
+
+    pub fn pre_block_state(mut self, pre_block_state: BeaconState<T::EthSpec>) -> Self {
+        self.pre_block_state = Some(pre_block_state);
+        self
+    }
+
+    pub fn deferred_fork_choice_updates(
+        mut self,
+        deferred_fork_choice_updates: DeferredForkChoiceUpdates<T::EthSpec>,
+    ) -> Self {
+        self.deferred_fork_choice_updates = Some(deferred_fork_choice_updates);
+        self
+    }
+
+    pub fn build(self) -> ExecutionPendingBlock<T> {
+        let block_root = self
+            .block_root
+            .unwrap_or_else(|| self.block.canonical_root());
+        let parent_eth1_finalization_data =
+            self.parent_eth1_finalization_data
+                .unwrap_or_else(|| Eth1FinalizationData {
+                    eth1_data: self.state.eth1_data().clone(),
+                    eth1_deposit_index: self.state.eth1_deposit_index(),
+                });
+        let consensus_context = self.consensus_context.unwrap_or_else(|| {
+            ConsensusContext::new(self.block.slot())
+                .set_current_block_root(block_root)
+                .set_proposer_index(self.block.message().proposer_index())
+        });
+
+        ExecutionPendingBlock {
+            block: self.block,
+            block_root,
+            state: self.state,
+            parent_block: self.parent_block,
+            parent_eth1_finalization_data,
+            confirmed_state_roots: self.confirmed_state_roots,
+            consensus_context,
+            payload_verification_handle: PayloadVerificationHandle::Deferred(Box::pin(
+                future::ready(self.payload_verification_outcome),
+            )),
+            skipped_slots: self.skipped_slots,
+            intermediate_state_roots: self.intermediate_state_roots,
+            parent_execution_status: self.parent_execution_status,
+            pre_block_state: self.pre_block_state,
+            deferred_fork_choice_updates: self.deferred_fork_choice_updates,
+            fork_choice_attestations_applied: 0,
+            attester_slashings_applied: 0,
+            imported_optimistically: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+    use crate::ExecutionPayloadError;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    #[tokio::test]
+    async fn import_built_block() {
+        let harness: BeaconChainHarness<EphemeralHarnessType<E>> =
+            BeaconChainHarness::builder(E::default())
+                .default_spec()
+                .deterministic_keypairs(8)
+                .fresh_ephemeral_store()
+                .build();
+        harness.advance_slot();
+
+        let slot = harness.get_current_slot();
+        let (block, state) = harness.make_block(harness.get_current_state(), slot).await;
+        let block = Arc::new(block);
+        let block_root = block.canonical_root();
+        let parent_block = harness
+            .chain
+            .get_blinded_block(&block.parent_root())
+            .unwrap()
+            .unwrap();
+
+        // A failed payload verification prevents the block from being imported.
+        let execution_pending =
+            ExecutionPendingBlockBuilder::new(block.clone(), state.clone(), parent_block.clone())
+                .payload_verification_outcome(Err(
+                    ExecutionPayloadError::NoExecutionConnection.into()
+                ))
+                .build();
+        assert!(matches!(
+            harness
+                .chain
+                .clone()
+                .import_execution_pending_block(execution_pending)
+                .await,
+            Err(BlockError::ExecutionPayloadError(
+                ExecutionPayloadError::NoExecutionConnection
+            ))
+        ));
+        assert!(!harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .contains_block(&block_root));
+
+        // Otherwise, the block is imported.
+        let execution_pending = ExecutionPendingBlockBuilder::new(block, state, parent_block)
+            .payload_verification_status(PayloadVerificationStatus::Irrelevant)
+            .build();
+        assert_eq!(
+            harness
+                .chain
+                .clone()
+                .import_execution_pending_block(execution_pending)
+                .await
+                .unwrap(),
+            block_root
+        );
+        assert!(harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .contains_block(&block_root));
+    }
+}
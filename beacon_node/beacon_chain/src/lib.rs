@@ -1,3 +1,4 @@
+mod applied_attestations;
 pub mod attestation_rewards;
 pub mod attestation_verification;
 mod attester_cache;
@@ -10,16 +11,20 @@ mod beacon_snapshot;
 pub mod block_reward;
 mod block_times_cache;
 mod block_verification;
+mod block_verification_report;
 pub mod builder;
 pub mod canonical_head;
 pub mod capella_readiness;
 pub mod chain_config;
+mod committee_state_cache;
 mod early_attester_cache;
 mod errors;
 pub mod eth1_chain;
 mod eth1_finalization_cache;
 pub mod events;
 pub mod execution_payload;
+#[cfg(any(test, feature = "test_builders"))]
+pub mod execution_pending_block_builder;
 pub mod fork_choice_signal;
 pub mod fork_revert;
 mod head_tracker;
@@ -35,10 +40,13 @@ mod observed_attesters;
 pub mod observed_block_producers;
 pub mod observed_operations;
 pub mod otb_verification_service;
+mod pending_payload_verifications;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
 mod pre_finalization_cache;
 pub mod proposer_prep_service;
+mod proposer_rate_limiter;
+mod rejected_block_cache;
 pub mod schema_change;
 pub mod shuffling_cache;
 mod snapshot_cache;
@@ -51,23 +59,32 @@ pub mod validator_monitor;
 pub mod validator_pubkey_cache;
 
 pub use self::beacon_chain::{
-    AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, BeaconStore, ChainSegmentResult,
-    ForkChoiceError, OverrideForkchoiceUpdate, ProduceBlockVerification, StateSkipConfig,
-    WhenSlotSkipped, INVALID_FINALIZED_MERGE_TRANSITION_BLOCK_SHUTDOWN_REASON,
+    AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, BeaconStore,
+    ChainSegmentBlockOutcome, ChainSegmentProgressSender, ChainSegmentResult, ForkChoiceError,
+    OverrideForkchoiceUpdate, ProduceBlockVerification, StateSkipConfig, WhenSlotSkipped,
+    INVALID_FINALIZED_MERGE_TRANSITION_BLOCK_SHUTDOWN_REASON,
     INVALID_JUSTIFIED_PAYLOAD_SHUTDOWN_REASON,
 };
 pub use self::beacon_snapshot::BeaconSnapshot;
 pub use self::chain_config::ChainConfig;
 pub use self::errors::{BeaconChainError, BlockProductionError};
 pub use self::historical_blocks::HistoricalBlockError;
+pub use self::snapshot_cache::PreProcessingSnapshot;
 pub use attestation_verification::Error as AttestationError;
 pub use beacon_fork_choice_store::{BeaconForkChoiceStore, Error as ForkChoiceStoreError};
 pub use block_verification::{
-    get_block_root, BlockError, ExecutionPayloadError, GossipVerifiedBlock,
-    IntoExecutionPendingBlock, IntoGossipVerifiedBlock,
+    check_block_relevancy, get_block_root, prepare_chain_segment, signature_verify_chain_segment,
+    signature_verify_chain_segments, validate_chain_segment_linearity,
+    verify_chain_segment_headers, verify_deposit_signatures, BlockError, BlockRejectionClass,
+    DeferredForkChoiceUpdates, DryRunVerifiedBlock, ExecutionPayloadError, ExecutionPendingBlock,
+    ExecutionPendingHook, GossipVerifiedBlock, IntoExecutionPendingBlock, IntoGossipVerifiedBlock,
+    PayloadVerificationHandle, PayloadVerificationOutcome, PeerPenalty, ProposerFilter,
+    SignatureKind, SignatureVerifiedBlock, VerificationProgress, VerificationStage,
 };
+pub use block_verification_report::BlockVerificationReport;
 pub use canonical_head::{CachedHead, CanonicalHead, CanonicalHeadRwLock};
 pub use eth1_chain::{Eth1Chain, Eth1ChainBackend};
+pub use eth1_finalization_cache::Eth1FinalizationData;
 pub use events::ServerSentEventHandler;
 pub use execution_layer::EngineState;
 pub use execution_payload::NotifyExecutionLayer;
@@ -75,6 +92,7 @@ pub use fork_choice::{ExecutionStatus, ForkchoiceUpdateParameters};
 pub use metrics::scrape_for_metrics;
 pub use migrate::MigratorConfig;
 pub use parking_lot;
+pub use rejected_block_cache::CachedBlockRejection;
 pub use slot_clock;
 pub use state_processing::per_block_processing::errors::{
     AttestationValidationError, AttesterSlashingValidationError, DepositValidationError,
@@ -30,9 +30,63 @@ lazy_static! {
         "beacon_block_processing_snapshot_cache_misses",
         "Count of snapshot cache misses"
     );
-    pub static ref BLOCK_PROCESSING_SNAPSHOT_CACHE_CLONES: Result<IntCounter> = try_create_int_counter(
+    pub static ref BLOCK_PROCESSING_SNAPSHOT_CACHE_CLONES: Result<IntCounterVec> = try_create_int_counter_vec(
         "beacon_block_processing_snapshot_cache_clones",
-        "Count of snapshot cache clones"
+        "Count of snapshot cache clones, by the reason the snapshot was cloned",
+        &["reason"]
+    );
+    pub static ref BLOCK_PROCESSING_SNAPSHOT_CACHE_CLEAN_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_snapshot_cache_clean_hits",
+        "Count of snapshot cache hits where the snapshot was removed from the cache without cloning"
+    );
+    pub static ref BLOCK_PROCESSING_SNAPSHOT_CACHE_BLOCK_DELAY: Result<Histogram> = try_create_histogram(
+        "beacon_block_processing_snapshot_cache_block_delay_seconds",
+        "Delay between the start of the slot and the block being observed, as used to decide whether \
+        to clone from the snapshot cache"
+    );
+    pub static ref BLOCK_PROCESSING_SNAPSHOT_CACHE_UNKNOWN_SLOT_START: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_snapshot_cache_unknown_slot_start_total",
+        "Count of blocks for which the slot clock could not provide the start of the slot, so the \
+        block delay is unknown"
+    );
+    pub static ref BLOCK_PROCESSING_SNAPSHOT_CACHE_DB_HITS_ADVANCED: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_snapshot_cache_db_hits_advanced",
+        "Count of snapshot cache misses where an advanced parent state was loaded from the database"
+    );
+    pub static ref BLOCK_PROCESSING_SNAPSHOT_CACHE_DB_HITS_EXACT: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_snapshot_cache_db_hits_exact",
+        "Count of snapshot cache misses where the unadvanced parent state was loaded from the database"
+    );
+    pub static ref COMMITTEE_STATE_CACHE_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_committee_state_cache_hits",
+        "Count of committee state cache hits"
+    );
+    pub static ref COMMITTEE_STATE_CACHE_MISSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_committee_state_cache_misses",
+        "Count of committee state cache misses"
+    );
+    pub static ref BLOCK_PROCESSING_OPTIMISTIC_REJECTIONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_optimistic_rejections_total",
+        "Count of blocks rejected because their payload was not verified by the execution engine \
+        and they were not candidates for optimistic import"
+    );
+    pub static ref BLOCK_PROCESSING_PAYLOAD_VERIFICATIONS_IN_FLIGHT: Result<IntGauge> = try_create_int_gauge(
+        "beacon_block_processing_payload_verifications_in_flight",
+        "Number of execution payloads currently being verified by the execution engine, excluding \
+        those waiting for a permit"
+    );
+    pub static ref BLOCK_PROCESSING_PAYLOAD_REJECTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_block_processing_payload_rejections_total",
+        "Count of execution payloads rejected by the execution engine, by the status returned",
+        &["status"]
+    );
+    pub static ref BLOCK_PROCESSING_PUBKEY_CACHE_LOCK_TIMEOUTS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_pubkey_cache_lock_timeouts_total",
+        "Count of timeouts whilst acquiring the validator pubkey cache lock during block verification, including those which were retried"
+    );
+    pub static ref BLOCK_PROCESSING_PAYLOAD_VERIFICATIONS_CANCELLED: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_processing_payload_verifications_cancelled_total",
+        "Count of payload verifications cancelled because the block became irrelevant"
     );
     pub static ref BLOCK_PROCESSING_TIMES: Result<Histogram> =
         try_create_histogram("beacon_block_processing_seconds", "Full runtime of block processing");
@@ -44,6 +98,12 @@ lazy_static! {
         "beacon_block_processing_db_read_seconds",
         "Time spent loading block and state from DB for block processing"
     );
+    pub static ref BLOCK_PROCESSING_PAYLOAD_VERIFICATION_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "beacon_block_processing_payload_verification_seconds",
+        "Time from the start of execution payload verification until its completion, including \
+        the round-trip to the execution layer, by whether it succeeded or failed",
+        &["outcome"]
+    );
     pub static ref BLOCK_PROCESSING_CATCHUP_STATE: Result<Histogram> = try_create_histogram(
         "beacon_block_processing_catch_up_state_seconds",
         "Time spent skipping slots on a state before processing a block."
@@ -52,6 +112,11 @@ lazy_static! {
         "beacon_block_processing_committee_building_seconds",
         "Time spent building/obtaining committees for block processing."
     );
+    pub static ref BLOCK_PROCESSING_COMMITTEE_CACHE_BUILDS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_block_processing_committee_cache_builds_total",
+        "Count of committee caches required for block processing, by whether they were built or already present",
+        &["relative_epoch", "outcome"]
+    );
     pub static ref BLOCK_PROCESSING_SIGNATURE: Result<Histogram> = try_create_histogram(
         "beacon_block_processing_signature_seconds",
         "Time spent doing signature verification for a block."
@@ -360,6 +425,11 @@ lazy_static! {
         "beacon_fork_choice_process_attestation_seconds",
         "Time taken to add an attestation to fork choice"
     );
+    pub static ref FORK_CHOICE_REDUNDANT_BLOCK_ATTESTATIONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_fork_choice_redundant_block_attestations_total",
+        "Count of block attestations not applied to fork choice because an earlier block in the \
+        same chain segment applied the same votes"
+    );
     pub static ref FORK_CHOICE_SET_HEAD_LAG_TIMES: Result<Histogram> = try_create_histogram(
         "beacon_fork_choice_set_head_lag_times",
         "Time taken between finding the head and setting the canonical head value"
@@ -855,6 +925,51 @@ lazy_static! {
             "gossip_beacon_block_skipped_slots",
             "For each gossip blocks, the number of skip slots between it and its parent"
         );
+    pub static ref GOSSIP_BEACON_BLOCK_ANCHOR_CHECK: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_anchor_check_seconds",
+        "Time spent checking a gossip block against the anchor slot"
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_FINALIZED_CHECK: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_finalized_check_seconds",
+        "Time spent checking a gossip block against the finalized slot"
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_FORK_CHOICE_CHECK: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_fork_choice_check_seconds",
+        "Time spent checking whether a gossip block is known to fork choice and descends from \
+        finality"
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_CHECK_REJECTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_block_gossip_check_rejections_total",
+        "Count of gossip blocks rejected, by the gossip check which rejected them",
+        &["check"]
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_PROPOSER_RECHECKS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_block_gossip_proposer_rechecks_total",
+        "Count of rechecks of the cached proposer for gossip blocks with an unexpected proposer, \
+        by whether the cached proposer was confirmed, inconsistent or could not be rechecked",
+        &["outcome"]
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_REJECTED_CACHE_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_gossip_rejected_cache_hits_total",
+        "Count of gossip blocks refused because they were recently rejected"
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_PARENT_LOOKUP: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_parent_lookup_seconds",
+        "Time spent looking up the parent of a gossip block in fork choice"
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_PROPOSER_CACHE_LOOKUP: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_proposer_cache_lookup_seconds",
+        "Time spent looking up the proposer of a gossip block in the proposer shuffling cache"
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_PROPOSER_CACHE_MISS: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_proposer_cache_miss_seconds",
+        "Time spent loading the parent state and computing the proposer of a gossip block after \
+        a proposer shuffling cache miss"
+    );
+    pub static ref GOSSIP_BEACON_BLOCK_SIGNATURE: Result<Histogram> = try_create_histogram(
+        "beacon_block_gossip_signature_seconds",
+        "Time spent verifying the proposal signature of a gossip block"
+    );
 }
 
 // Fourth lazy-static block is used to account for macro recursion limit.
@@ -49,6 +49,7 @@ impl<E: EthSpec> Default for ObservedBlockProducers<E> {
     }
 }
 
+#[derive(Debug, PartialEq)]
 pub enum SeenBlock {
     Duplicate,
     Slashable,
@@ -73,6 +74,11 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
     ///
     /// The supplied `block` **MUST** be signature verified (see struct-level documentation).
     ///
+    /// The check for an existing proposal and the observation of `block` are a single operation on
+    /// `&mut self`, so callers which hold the write lock on `self` are serialized. Of several
+    /// distinct blocks observed concurrently for the same proposer and slot, exactly one is
+    /// `SeenBlock::UniqueNonSlashable` and the others are `SeenBlock::Slashable`.
+    ///
     /// ## Errors
     ///
     /// - `block.proposer_index` is greater than `VALIDATOR_REGISTRY_LIMIT`.
@@ -117,6 +123,29 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
         Ok(slashable_proposal)
     }
 
+    /// Observes each of the `blocks`, as per `Self::observe_proposal`, returning the result for
+    /// each block in the same order.
+    ///
+    /// This is identical to calling `Self::observe_proposal` for each block in turn, so a block
+    /// which equivocates with an earlier block in `blocks` is `SeenBlock::Slashable`. An error for
+    /// one block does not prevent the observation of the blocks which follow it. This is useful
+    /// for observing many blocks whilst only taking the lock on `self` once.
+    ///
+    /// Each of the `blocks` **MUST** be signature verified (see struct-level documentation).
+    ///
+    /// Observations cannot be undone, so this must not be used to observe blocks ahead of their
+    /// import. A block which is observed but never imported causes later copies of it to be
+    /// ignored as duplicates.
+    pub fn observe_proposals_batch(
+        &mut self,
+        blocks: &[(Hash256, BeaconBlockRef<'_, E>)],
+    ) -> Vec<Result<SeenBlock, Error>> {
+        blocks
+            .iter()
+            .map(|(block_root, block)| self.observe_proposal(*block_root, *block))
+            .collect()
+    }
+
     /// Returns `Ok(true)` if the `block` has been observed before, `Ok(false)` if not. Does not
     /// update the cache, so calling this function multiple times will continue to return
     /// `Ok(false)`, until `Self::observe_proposer` is called.
@@ -527,4 +556,46 @@ mod tests {
             "only one proposer should be present in slot 1"
         );
     }
+
+    #[test]
+    fn batch_observation_matches_sequential() {
+        let block_a = get_block(1, 0);
+        let mut block_b = get_block(1, 0);
+        *block_b.state_root_mut() = Hash256::repeat_byte(1);
+        let block_c = get_block(2, 1);
+        let block_d = get_block(3, <E as EthSpec>::ValidatorRegistryLimit::to_u64());
+
+        let blocks = [&block_a, &block_a, &block_b, &block_c, &block_d, &block_c]
+            .into_iter()
+            .map(|block| (block.canonical_root(), block.to_ref()))
+            .collect::<Vec<_>>();
+
+        let mut sequential_cache = ObservedBlockProducers::<E>::default();
+        let sequential = blocks
+            .iter()
+            .map(|(block_root, block)| sequential_cache.observe_proposal(*block_root, *block))
+            .collect::<Vec<_>>();
+
+        let mut batch_cache = ObservedBlockProducers::<E>::default();
+        let batch = batch_cache.observe_proposals_batch(&blocks);
+
+        assert_eq!(
+            batch, sequential,
+            "batch should match sequential observation"
+        );
+        assert_eq!(
+            batch,
+            vec![
+                Ok(SeenBlock::UniqueNonSlashable),
+                Ok(SeenBlock::Duplicate),
+                Ok(SeenBlock::Slashable),
+                Ok(SeenBlock::UniqueNonSlashable),
+                Err(Error::ValidatorIndexTooHigh(
+                    <E as EthSpec>::ValidatorRegistryLimit::to_u64()
+                )),
+                Ok(SeenBlock::Duplicate),
+            ]
+        );
+        assert_eq!(batch_cache.items, sequential_cache.items);
+    }
 }
@@ -0,0 +1,211 @@
+//! Provides the `PendingPayloadVerifications` struct which tracks execution payloads that are
+//! currently being verified by the execution engine.
+//!
+//! A payload verification can take a long time to complete (e.g., when the execution engine is
+//! under load). If the block becomes irrelevant whilst we wait (e.g., it is finalized away or its
+//! parent is invalidated) the verification is cancelled rather than left to run to completion.
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio_util::sync::CancellationToken;
+use types::{Hash256, Slot};
+
+/// A payload verification which is awaiting a response from the execution engine.
+pub struct PendingPayloadVerification {
+    pub slot: Slot,
+    pub parent_root: Hash256,
+    token: CancellationToken,
+    /// Distinguishes this verification from any later verification of the same block.
+    id: u64,
+    /// The number of `PendingPayloadRegistration`s for this verification which are still alive.
+    registrations: usize,
+}
+
+/// Tracks in-flight payload verifications, keyed by block root.
+#[derive(Default)]
+pub struct PendingPayloadVerifications {
+    pending: Mutex<HashMap<Hash256, PendingPayloadVerification>>,
+    next_id: AtomicU64,
+}
+
+/// A registration returned by `PendingPayloadVerifications::register`.
+///
+/// The verification is tracked until every registration for the block has been dropped, or until
+/// it is cancelled.
+#[must_use]
+pub struct PendingPayloadRegistration<'a> {
+    verifications: &'a PendingPayloadVerifications,
+    block_root: Hash256,
+    id: u64,
+    token: CancellationToken,
+}
+
+impl<'a> PendingPayloadRegistration<'a> {
+    /// Returns the token which will be cancelled if the block becomes irrelevant.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl<'a> Drop for PendingPayloadRegistration<'a> {
+    fn drop(&mut self) {
+        self.verifications.deregister(&self.block_root, self.id);
+    }
+}
+
+impl PendingPayloadVerifications {
+    /// Start tracking the payload verification of the block with `block_root`, returning a
+    /// registration whose token will be cancelled if the block becomes irrelevant.
+    ///
+    /// If the block is already being verified, the existing token is shared and the verification
+    /// remains tracked until the last registration is dropped.
+    pub fn register(
+        &self,
+        block_root: Hash256,
+        slot: Slot,
+        parent_root: Hash256,
+    ) -> PendingPayloadRegistration<'_> {
+        let mut pending = self.pending.lock();
+        let verification =
+            pending
+                .entry(block_root)
+                .or_insert_with(|| PendingPayloadVerification {
+                    slot,
+                    parent_root,
+                    token: CancellationToken::new(),
+                    id: self.next_id.fetch_add(1, Ordering::Relaxed),
+                    registrations: 0,
+                });
+        verification.registrations += 1;
+
+        PendingPayloadRegistration {
+            verifications: self,
+            block_root,
+            id: verification.id,
+            token: verification.token.clone(),
+        }
+    }
+
+    /// Drop a registration of the verification with `id`, ceasing to track it if this was the
+    /// last registration.
+    ///
+    /// A registration for a verification which has since been cancelled has no effect, even if
+    /// the block has been registered again.
+    fn deregister(&self, block_root: &Hash256, id: u64) {
+        let mut pending = self.pending.lock();
+        let is_last = match pending.get_mut(block_root) {
+            Some(verification) if verification.id == id => {
+                verification.registrations = verification.registrations.saturating_sub(1);
+                verification.registrations == 0
+            }
+            _ => false,
+        };
+        if is_last {
+            pending.remove(block_root);
+        }
+    }
+
+    /// Cancel and stop tracking all verifications for which `is_irrelevant` returns `true`.
+    ///
+    /// Returns the roots of the cancelled blocks.
+    pub fn cancel_where<F>(&self, mut is_irrelevant: F) -> Vec<Hash256>
+    where
+        F: FnMut(&Hash256, &PendingPayloadVerification) -> bool,
+    {
+        let mut cancelled = vec![];
+        self.pending.lock().retain(|block_root, pending| {
+            if is_irrelevant(block_root, pending) {
+                pending.token.cancel();
+                cancelled.push(*block_root);
+                false
+            } else {
+                true
+            }
+        });
+        cancelled
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(i: u64) -> Hash256 {
+        Hash256::from_low_u64_be(i)
+    }
+
+    #[test]
+    fn register_and_drop() {
+        let pending = PendingPayloadVerifications::default();
+        let registration = pending.register(root(1), Slot::new(1), root(0));
+        let token = registration.token().clone();
+        assert_eq!(pending.len(), 1);
+
+        drop(registration);
+        assert!(pending.is_empty());
+        assert!(!token.is_cancelled(), "removal must not cancel");
+    }
+
+    #[test]
+    fn duplicate_registrations_are_counted() {
+        let pending = PendingPayloadVerifications::default();
+        let first = pending.register(root(1), Slot::new(1), root(0));
+        let second = pending.register(root(1), Slot::new(1), root(0));
+
+        drop(first);
+        assert_eq!(pending.len(), 1, "should track until the last registration");
+
+        pending.cancel_where(|_, _| true);
+        assert!(second.token().is_cancelled());
+    }
+
+    #[test]
+    fn stale_registration_does_not_remove_new_verification() {
+        let pending = PendingPayloadVerifications::default();
+        let stale = pending.register(root(1), Slot::new(1), root(0));
+        pending.cancel_where(|_, _| true);
+
+        let fresh = pending.register(root(1), Slot::new(1), root(0));
+        drop(stale);
+        assert_eq!(pending.len(), 1);
+        assert!(!fresh.token().is_cancelled());
+
+        drop(fresh);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn cancel_irrelevant() {
+        let pending = PendingPayloadVerifications::default();
+        let early = pending.register(root(1), Slot::new(1), root(0));
+        let late = pending.register(root(2), Slot::new(2), root(1));
+
+        let cancelled = pending.cancel_where(|_, pending| pending.slot <= Slot::new(1));
+
+        assert_eq!(cancelled, vec![root(1)]);
+        assert!(early.token().is_cancelled());
+        assert!(!late.token().is_cancelled());
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn duplicate_registrations_share_token() {
+        let pending = PendingPayloadVerifications::default();
+        let first = pending.register(root(1), Slot::new(1), root(0));
+        let second = pending.register(root(1), Slot::new(1), root(0));
+
+        pending.cancel_where(|_, _| true);
+
+        assert!(first.token().is_cancelled());
+        assert!(second.token().is_cancelled());
+    }
+}
@@ -93,12 +93,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(false)
     }
 
+    /// Record that the block with `block_root` was rejected for being from a finalized slot.
+    ///
+    /// Attestations to this block will subsequently be rejected by `is_pre_finalization_block`
+    /// without a lookup, and any in-progress lookup of the block is considered complete.
+    ///
+    /// The cache is small, so this should only be called for blocks which attestations are likely
+    /// to reference (e.g., those received via gossip or a single block lookup). Calling it for every
+    /// old block seen during sync would evict more useful entries.
     pub fn pre_finalization_block_rejected(&self, block_root: Hash256) {
         // Future requests can know that this block is invalid without having to look it up again.
         let mut cache = self.pre_finalization_block_cache.cache.lock();
         cache.in_progress_lookups.pop(&block_root);
         cache.block_roots.put(block_root, ());
     }
+
+    /// As per `Self::pre_finalization_block_rejected`, but only if the block with `block_root` is
+    /// the subject of an in-progress lookup.
+    pub fn pre_finalization_lookup_rejected(&self, block_root: Hash256) {
+        let mut cache = self.pre_finalization_block_cache.cache.lock();
+        if cache.in_progress_lookups.pop(&block_root).is_some() {
+            cache.block_roots.put(block_root, ());
+        }
+    }
 }
 
 impl PreFinalizationBlockCache {
@@ -0,0 +1,129 @@
+//! Provides the `ProposerRateLimiter` which limits the number of distinct blocks accepted via gossip
+//! from a single proposer.
+//!
+//! The equivocation check in `ObservedBlockProducers` only catches multiple blocks from a proposer
+//! in the *same* slot. This limiter instead counts the distinct blocks from each proposer within a
+//! fixed window of slots, refusing any beyond the configured limit.
+//!
+//! Windows are aligned to multiples of `window_slots`, all counts are reset when the current slot
+//! moves into a new window.
+
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use types::{Hash256, Slot};
+
+/// The default length of the rate limiting window, in slots.
+pub const DEFAULT_WINDOW_SLOTS: u64 = 32;
+
+#[derive(Default)]
+struct Inner {
+    /// The index of the current window (i.e., `current_slot / window_slots`).
+    window: u64,
+    /// The distinct block roots seen from each proposer in the current window.
+    blocks: HashMap<u64, HashSet<Hash256>>,
+}
+
+pub struct ProposerRateLimiter {
+    /// The maximum number of distinct blocks permitted from a proposer in a single window.
+    limit: usize,
+    /// The length of each window, in slots.
+    window_slots: u64,
+    inner: Mutex<Inner>,
+}
+
+impl ProposerRateLimiter {
+    /// Create a new limiter, permitting at most `limit` distinct blocks per proposer in each
+    /// window of `window_slots` slots.
+    ///
+    /// A `window_slots` of zero is treated as one.
+    pub fn new(limit: usize, window_slots: u64) -> Self {
+        Self {
+            limit,
+            window_slots: std::cmp::max(window_slots, 1),
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Record a block with `block_root` from `proposer_index`, returning `false` if the proposer
+    /// has exceeded its limit for the window containing `current_slot`.
+    ///
+    /// A block root which has already been recorded in the current window is always permitted and
+    /// does not count towards the limit again.
+    pub fn check_and_insert(
+        &self,
+        proposer_index: u64,
+        block_root: Hash256,
+        current_slot: Slot,
+    ) -> bool {
+        let window = current_slot.as_u64() / self.window_slots;
+        let mut inner = self.inner.lock();
+
+        if window != inner.window {
+            inner.window = window;
+            inner.blocks.clear();
+        }
+
+        let blocks = inner.blocks.entry(proposer_index).or_default();
+        if blocks.contains(&block_root) {
+            true
+        } else if blocks.len() < self.limit {
+            blocks.insert(block_root);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(i: u64) -> Hash256 {
+        Hash256::from_low_u64_be(i)
+    }
+
+    #[test]
+    fn limits_distinct_blocks() {
+        let limiter = ProposerRateLimiter::new(2, 8);
+        let slot = Slot::new(1);
+
+        assert!(limiter.check_and_insert(0, root(1), slot));
+        assert!(limiter.check_and_insert(0, root(2), slot));
+        assert!(
+            limiter.check_and_insert(0, root(1), slot),
+            "repeated roots are permitted"
+        );
+        assert!(!limiter.check_and_insert(0, root(3), slot));
+        assert!(
+            limiter.check_and_insert(1, root(3), slot),
+            "other proposers are unaffected"
+        );
+    }
+
+    #[test]
+    fn window_rollover() {
+        let limiter = ProposerRateLimiter::new(1, 8);
+
+        assert!(limiter.check_and_insert(0, root(1), Slot::new(0)));
+        assert!(!limiter.check_and_insert(0, root(2), Slot::new(7)));
+        assert!(
+            limiter.check_and_insert(0, root(2), Slot::new(8)),
+            "the limit resets in a new window"
+        );
+        assert!(!limiter.check_and_insert(0, root(3), Slot::new(15)));
+        assert!(
+            limiter.check_and_insert(0, root(3), Slot::new(100)),
+            "skipped windows also reset the limit"
+        );
+    }
+
+    #[test]
+    fn zero_window_slots() {
+        let limiter = ProposerRateLimiter::new(1, 0);
+
+        assert!(limiter.check_and_insert(0, root(1), Slot::new(0)));
+        assert!(!limiter.check_and_insert(0, root(2), Slot::new(0)));
+        assert!(limiter.check_and_insert(0, root(2), Slot::new(1)));
+    }
+}
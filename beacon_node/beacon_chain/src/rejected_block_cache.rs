@@ -0,0 +1,100 @@
+//! Provides the `RejectedBlockCache`, a short-lived record of blocks which recently failed gossip
+//! verification.
+//!
+//! Peers frequently re-forward the same invalid block. Remembering the rejection by block root
+//! allows us to refuse the repeats without running gossip verification again.
+//!
+//! Only rejections which would be reproduced by re-verifying the block are cached. Notably, a block
+//! root does not commit to the proposer signature, so signature failures are never cached (this
+//! would allow an invalid signature to censor the correctly signed block).
+
+use crate::{BlockError, BlockRejectionClass, PeerPenalty};
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+use types::{EthSpec, Hash256};
+
+/// The maximum number of rejected block roots to retain.
+const CACHE_SIZE: usize = 256;
+/// The duration after which a rejection is forgotten.
+const REJECTION_TTL: Duration = Duration::from_secs(384);
+
+/// A summary of the `BlockError` which caused a block to be rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachedBlockRejection {
+    /// The `BlockError::as_static_str` of the original error.
+    pub reason: &'static str,
+    /// The `BlockError::rejection_class` of the original error.
+    pub class: BlockRejectionClass,
+    /// The `BlockError::penalize_peer` of the original error.
+    pub penalty: Option<PeerPenalty>,
+}
+
+pub struct RejectedBlockCache {
+    cache: Mutex<LruCache<Hash256, (Instant, CachedBlockRejection)>>,
+}
+
+impl Default for RejectedBlockCache {
+    fn default() -> Self {
+        Self {
+            cache: Mutex::new(LruCache::new(CACHE_SIZE)),
+        }
+    }
+}
+
+impl RejectedBlockCache {
+    /// Returns the rejection of the block with `block_root`, if it was rejected recently.
+    pub fn get(&self, block_root: &Hash256) -> Option<CachedBlockRejection> {
+        let mut cache = self.cache.lock();
+        let (rejected_at, rejection) = *cache.get(block_root)?;
+
+        if rejected_at.elapsed() < REJECTION_TTL {
+            Some(rejection)
+        } else {
+            cache.pop(block_root);
+            None
+        }
+    }
+
+    /// Record that the block with `block_root` was rejected due to `error`.
+    ///
+    /// Returns `false` if the error is not one which may be cached.
+    pub fn insert<E: EthSpec>(&self, block_root: Hash256, error: &BlockError<E>) -> bool {
+        if !is_cacheable(error) {
+            return false;
+        }
+
+        let rejection = CachedBlockRejection {
+            reason: error.as_static_str(),
+            class: error.rejection_class(),
+            penalty: error.penalize_peer(),
+        };
+        self.cache
+            .lock()
+            .put(block_root, (Instant::now(), rejection));
+        true
+    }
+}
+
+/// Returns `true` if re-verifying a block which failed with `error` is certain to fail again.
+fn is_cacheable<E: EthSpec>(error: &BlockError<E>) -> bool {
+    match error {
+        // These errors are determined by the contents of the block and the (unchangeable) parent.
+        BlockError::UnknownValidator(_)
+        | BlockError::BlockIsNotLaterThanParent { .. }
+        | BlockError::NonLinearParentRoots
+        | BlockError::NonLinearSlots
+        | BlockError::InconsistentFork(_)
+        | BlockError::ParentExecutionPayloadInvalid { .. } => true,
+        // Payload errors which don't penalize the peer may be an issue with our execution layer.
+        BlockError::ExecutionPayloadError(e) => e.penalize_peer(),
+        // The remaining errors may not be reproduced. For example, the parent of a
+        // `ParentUnknown` block may arrive later, a `FutureSlot` block may become valid, a
+        // signature error says nothing about a differently signed block with the same root, and
+        // a `BeaconChainError` may be transient.
+        //
+        // Repeats of an `IncorrectBlockProposer` block are already refused cheaply by the
+        // `observed_block_producers` cache.
+        _ => false,
+    }
+}
@@ -18,6 +18,29 @@ fn minimum_block_delay_for_clone(seconds_per_slot: u64) -> Duration {
     Duration::from_secs(seconds_per_slot) / 3
 }
 
+/// The reason a snapshot was cloned out of the cache for block processing, rather than removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotCloneReason {
+    /// The block was observed late enough that it might be re-orged, so the parent snapshot is
+    /// kept around for a competing block.
+    LateBlock,
+    /// The block skips one or more slots after its parent, so the parent snapshot may still be
+    /// needed by other children.
+    SkippedSlot,
+    /// The block was both late and skipped slots.
+    LateBlockAndSkippedSlot,
+}
+
+impl SnapshotCloneReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotCloneReason::LateBlock => "late_block",
+            SnapshotCloneReason::SkippedSlot => "skipped_slot",
+            SnapshotCloneReason::LateBlockAndSkippedSlot => "late_block_and_skipped_slot",
+        }
+    }
+}
+
 /// This snapshot is to be used for verifying a child of `self.beacon_block`.
 #[derive(Debug)]
 pub struct PreProcessingSnapshot<T: EthSpec> {
@@ -61,31 +84,44 @@ impl<T: EthSpec> CacheItem<T> {
         }
     }
 
-    pub fn into_pre_state(self) -> PreProcessingSnapshot<T> {
+    /// Returns the advanced `pre_state`, unless it has been advanced beyond `block_slot`.
+    ///
+    /// A state which is later than the block cannot be used to process it, so we fall back to the
+    /// unadvanced `beacon_state` in that case.
+    fn usable_pre_state(&self, block_slot: Slot) -> Option<&BeaconState<T>> {
+        self.pre_state
+            .as_ref()
+            .filter(|pre_state| pre_state.slot() <= block_slot)
+    }
+
+    pub fn into_pre_state(self, block_slot: Slot) -> PreProcessingSnapshot<T> {
+        let use_pre_state = self.usable_pre_state(block_slot).is_some();
+
         // Do not include the beacon state root if the state has been advanced.
-        let beacon_state_root =
-            Some(self.beacon_block.state_root()).filter(|_| self.pre_state.is_none());
+        let beacon_state_root = Some(self.beacon_block.state_root()).filter(|_| !use_pre_state);
 
         PreProcessingSnapshot {
             beacon_block: self.beacon_block.clone_as_blinded(),
             beacon_block_root: self.beacon_block_root,
-            pre_state: self.pre_state.unwrap_or(self.beacon_state),
+            pre_state: self
+                .pre_state
+                .filter(|_| use_pre_state)
+                .unwrap_or(self.beacon_state),
             beacon_state_root,
         }
     }
 
-    pub fn clone_as_pre_state(&self) -> PreProcessingSnapshot<T> {
+    pub fn clone_as_pre_state(&self, block_slot: Slot) -> PreProcessingSnapshot<T> {
+        let pre_state = self.usable_pre_state(block_slot);
+
         // Do not include the beacon state root if the state has been advanced.
         let beacon_state_root =
-            Some(self.beacon_block.state_root()).filter(|_| self.pre_state.is_none());
+            Some(self.beacon_block.state_root()).filter(|_| pre_state.is_none());
 
         PreProcessingSnapshot {
             beacon_block: self.beacon_block.clone_as_blinded(),
             beacon_block_root: self.beacon_block_root,
-            pre_state: self
-                .pre_state
-                .as_ref()
-                .map_or_else(|| self.beacon_state.clone(), |pre_state| pre_state.clone()),
+            pre_state: pre_state.map_or_else(|| self.beacon_state.clone(), Clone::clone),
             beacon_state_root,
         }
     }
@@ -245,13 +281,15 @@ impl<T: EthSpec> SnapshotCache<T> {
     /// moving it. This allows us to process the next block quickly in the case of a re-org.
     /// Additionally, if the slot was skipped, clone the cache. This ensures blocks that are
     /// later than 1 slot still have access to the cache and can be processed quickly.
+    ///
+    /// The returned `SnapshotCloneReason` is `Some` iff the snapshot was cloned.
     pub fn get_state_for_block_processing(
         &mut self,
         block_root: Hash256,
         block_slot: Slot,
         block_delay: Option<Duration>,
         spec: &ChainSpec,
-    ) -> Option<(PreProcessingSnapshot<T>, bool)> {
+    ) -> Option<(PreProcessingSnapshot<T>, Option<SnapshotCloneReason>)> {
         self.snapshots
             .iter()
             .position(|snapshot| snapshot.beacon_block_root == block_root)
@@ -259,18 +297,43 @@ impl<T: EthSpec> SnapshotCache<T> {
                 if let Some(cache) = self.snapshots.get(i) {
                     // Avoid cloning the block during sync (when the `block_delay` is `None`).
                     if let Some(delay) = block_delay {
-                        if delay >= minimum_block_delay_for_clone(spec.seconds_per_slot)
-                            && delay <= Duration::from_secs(spec.seconds_per_slot) * 4
-                            || block_slot > cache.beacon_block.slot() + 1
-                        {
-                            return (cache.clone_as_pre_state(), true);
+                        let late_block = delay
+                            >= minimum_block_delay_for_clone(spec.seconds_per_slot)
+                            && delay <= Duration::from_secs(spec.seconds_per_slot) * 4;
+                        let skipped_slot = block_slot > cache.beacon_block.slot() + 1;
+
+                        let reason = match (late_block, skipped_slot) {
+                            (true, true) => Some(SnapshotCloneReason::LateBlockAndSkippedSlot),
+                            (true, false) => Some(SnapshotCloneReason::LateBlock),
+                            (false, true) => Some(SnapshotCloneReason::SkippedSlot),
+                            (false, false) => None,
+                        };
+
+                        if reason.is_some() {
+                            return (cache.clone_as_pre_state(block_slot), reason);
                         }
                     }
                 }
-                (self.snapshots.remove(i).into_pre_state(), false)
+                (self.snapshots.remove(i).into_pre_state(block_slot), None)
             })
     }
 
+    /// As per `Self::get_state_for_block_processing`, except that the snapshot is always cloned,
+    /// leaving `self` unchanged.
+    ///
+    /// This is intended for verifying blocks which will not be imported (e.g., a dry run), where
+    /// removing the snapshot would only slow down the import of the next block.
+    pub fn get_cloned_for_block_processing(
+        &self,
+        block_root: Hash256,
+        block_slot: Slot,
+    ) -> Option<PreProcessingSnapshot<T>> {
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.beacon_block_root == block_root)
+            .map(|snapshot| snapshot.clone_as_pre_state(block_slot))
+    }
+
     /// If available, obtains a clone of a `BeaconState` that should be used for block production.
     /// The clone will use `CloneConfig:all()`, ensuring any tree-hash cache is cloned too.
     ///
@@ -520,4 +583,80 @@ mod test {
             "get_state_for_block_processing should get the correct snapshot"
         );
     }
+
+    #[test]
+    fn clone_reasons() {
+        let spec = MainnetEthSpec::default_spec();
+        let root = Hash256::from_low_u64_be(0);
+        let on_time = Some(Duration::from_secs(0));
+        let late = Some(minimum_block_delay_for_clone(spec.seconds_per_slot));
+
+        // The cached block is at slot 0, so a block at slot 1 is the next slot and a block at
+        // slot 2 skips a slot.
+        for (block_slot, block_delay, expected) in [
+            (Slot::new(1), late, Some(SnapshotCloneReason::LateBlock)),
+            (
+                Slot::new(2),
+                on_time,
+                Some(SnapshotCloneReason::SkippedSlot),
+            ),
+            (
+                Slot::new(2),
+                late,
+                Some(SnapshotCloneReason::LateBlockAndSkippedSlot),
+            ),
+            (Slot::new(2), None, None),
+            (Slot::new(1), on_time, None),
+        ] {
+            let mut cache = SnapshotCache::new(CACHE_SIZE, get_snapshot(0));
+            let (_, reason) = cache
+                .get_state_for_block_processing(root, block_slot, block_delay, &spec)
+                .expect("the snapshot should be in the cache");
+            assert_eq!(reason, expected, "slot {block_slot}, delay {block_delay:?}");
+            assert_eq!(
+                cache.snapshots.len(),
+                usize::from(reason.is_some()),
+                "the snapshot should only be retained if it was cloned"
+            );
+        }
+    }
+
+    #[test]
+    fn over_advanced_pre_state_is_not_used() {
+        let spec = MainnetEthSpec::default_spec();
+        let root = Hash256::from_low_u64_be(0);
+
+        let mut snapshot = get_snapshot(0);
+        *snapshot.beacon_state.slot_mut() = Slot::new(1);
+        let mut pre_state = snapshot.beacon_state.clone();
+        *pre_state.slot_mut() = Slot::new(3);
+
+        let mut cache = SnapshotCache::new(CACHE_SIZE, snapshot);
+        cache.update_pre_state(root, pre_state);
+
+        // A block at slot 2 cannot be processed atop the state at slot 3, so the unadvanced state
+        // should be provided whether the snapshot is cloned or removed.
+        for block_delay in [Some(Duration::from_secs(0)), None] {
+            let (snapshot, _) = cache
+                .get_state_for_block_processing(root, Slot::new(2), block_delay, &spec)
+                .expect("the snapshot should be in the cache");
+            assert_eq!(snapshot.pre_state.slot(), Slot::new(1));
+            assert!(snapshot.beacon_state_root.is_some());
+        }
+
+        // Once the block is at the same slot, the advanced state is used.
+        let mut snapshot = get_snapshot(0);
+        *snapshot.beacon_state.slot_mut() = Slot::new(1);
+        let mut pre_state = snapshot.beacon_state.clone();
+        *pre_state.slot_mut() = Slot::new(3);
+
+        let mut cache = SnapshotCache::new(CACHE_SIZE, snapshot);
+        cache.update_pre_state(root, pre_state);
+
+        let (snapshot, _) = cache
+            .get_state_for_block_processing(root, Slot::new(3), None, &spec)
+            .expect("the snapshot should be in the cache");
+        assert_eq!(snapshot.pre_state.slot(), Slot::new(3));
+        assert!(snapshot.beacon_state_root.is_none());
+    }
 }
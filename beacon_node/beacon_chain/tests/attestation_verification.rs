@@ -9,7 +9,8 @@ use beacon_chain::{
     test_utils::{
         test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
     },
-    BeaconChain, BeaconChainError, BeaconChainTypes, ChainConfig, WhenSlotSkipped,
+    BeaconChain, BeaconChainError, BeaconChainTypes, ChainConfig, ChainSegmentResult,
+    NotifyExecutionLayer, WhenSlotSkipped,
 };
 use genesis::{interop_genesis_state, DEFAULT_ETH1_BLOCK_HASH};
 use int_to_bytes::int_to_bytes32;
@@ -17,6 +18,7 @@ use lazy_static::lazy_static;
 use state_processing::{
     per_block_processing::errors::AttestationValidationError, per_slot_processing,
 };
+use std::sync::Arc;
 use tree_hash::TreeHash;
 use types::{
     test_utils::generate_deterministic_keypair, Address, AggregateSignature, Attestation,
@@ -1228,6 +1230,56 @@ async fn attestation_to_finalized_block() {
         .contains(earlier_block_root));
 }
 
+/// Finalized blocks seen during sync should not be recorded in the pre-finalization block cache.
+#[tokio::test]
+async fn finalized_block_in_chain_segment_not_cached() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            MainnetEthSpec::slots_per_epoch() as usize * 4 + 1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let finalized_checkpoint = harness
+        .chain
+        .with_head(|head| Ok::<_, BeaconChainError>(head.beacon_state.finalized_checkpoint()))
+        .unwrap();
+    assert!(finalized_checkpoint.epoch > 0);
+
+    let earlier_slot = finalized_checkpoint
+        .epoch
+        .start_slot(MainnetEthSpec::slots_per_epoch())
+        - 1;
+    let earlier_block_root = harness
+        .chain
+        .block_root_at_slot(earlier_slot, WhenSlotSkipped::Prev)
+        .expect("should not error getting block root at slot")
+        .expect("should find block root at slot");
+    let earlier_block = harness
+        .chain
+        .get_block(&earlier_block_root)
+        .await
+        .expect("should not error getting block")
+        .expect("should find block");
+
+    let result = harness
+        .chain
+        .process_chain_segment(vec![Arc::new(earlier_block)], NotifyExecutionLayer::Yes)
+        .await;
+    assert!(matches!(
+        result,
+        ChainSegmentResult::Successful { imported_blocks: 0 }
+    ));
+
+    assert!(!harness
+        .chain
+        .pre_finalization_block_cache
+        .contains(earlier_block_root));
+}
+
 #[tokio::test]
 async fn verify_aggregate_for_gossip_doppelganger_detection() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -4,9 +4,16 @@ use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
 };
 use beacon_chain::{
-    BeaconSnapshot, BlockError, ChainConfig, ChainSegmentResult, IntoExecutionPendingBlock,
-    NotifyExecutionLayer,
+    check_block_relevancy, execution_payload::KnownPayloadStatuses, metrics, prepare_chain_segment,
+    signature_verify_chain_segments, validate_chain_segment_linearity,
+    verify_chain_segment_headers, verify_deposit_signatures, BeaconChainError, BeaconSnapshot,
+    BlockError, ChainConfig, ChainSegmentBlockOutcome, ChainSegmentResult, DryRunVerifiedBlock,
+    ExecutionPayloadError, ExecutionPendingBlock, GossipVerifiedBlock, IntoExecutionPendingBlock,
+    NotifyExecutionLayer, PayloadVerificationHandle, PeerPenalty, PreProcessingSnapshot,
+    SignatureKind, SignatureVerifiedBlock, VerificationProgress, VerificationStage,
 };
+use fork_choice::PayloadVerificationStatus;
+use futures::FutureExt;
 use lazy_static::lazy_static;
 use logging::test_logger;
 use slasher::{Config as SlasherConfig, Slasher};
@@ -16,9 +23,11 @@ use state_processing::{
     per_slot_processing, BlockProcessingError, ConsensusContext, StateProcessingStrategy,
     VerifyBlockRoot,
 };
+use std::cell::RefCell;
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tempfile::tempdir;
+use tokio_util::sync::CancellationToken;
 use types::{test_utils::generate_deterministic_keypair, *};
 
 type E = MainnetEthSpec;
@@ -140,6 +149,25 @@ fn update_parent_roots(snapshots: &mut [BeaconSnapshot<E>]) {
     }
 }
 
+/// Returns `block` with its state root replaced by `state_root`, re-signed by its proposer in
+/// `state`.
+fn with_state_root(
+    block: &SignedBeaconBlock<E>,
+    state_root: Hash256,
+    state: &BeaconState<E>,
+    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+) -> Arc<SignedBeaconBlock<E>> {
+    let (mut block, _) = block.clone().deconstruct();
+    *block.state_root_mut() = state_root;
+    let proposer_index = block.proposer_index() as usize;
+    Arc::new(block.sign(
+        &harness.validator_keypairs[proposer_index].sk,
+        &state.fork(),
+        state.genesis_validators_root(),
+        &harness.spec,
+    ))
+}
+
 #[tokio::test]
 async fn chain_segment_full_segment() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -206,6 +234,241 @@ async fn chain_segment_varying_chunk_size() {
     }
 }
 
+#[tokio::test]
+async fn chain_segment_with_progress() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let chain_segment = get_chain_segment().await;
+    let blocks = chain_segment_blocks(&chain_segment);
+
+    harness
+        .chain
+        .slot_clock
+        .set_slot(blocks.last().unwrap().slot().as_u64());
+
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    harness
+        .chain
+        .process_chain_segment_with_progress(
+            blocks.clone(),
+            NotifyExecutionLayer::Yes,
+            Some(progress_tx),
+            None,
+        )
+        .await
+        .into_block_error()
+        .expect("should import chain segment");
+
+    // Each block should be reported in order.
+    for block in &blocks {
+        let (block_root, outcome) = progress_rx.recv().await.expect("should report block");
+        assert_eq!(block_root, block.canonical_root());
+        assert!(matches!(outcome, ChainSegmentBlockOutcome::Imported));
+    }
+    assert!(progress_rx.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn chain_segment_progress_reports_every_block() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let chain_segment = get_chain_segment().await;
+    let mut blocks = chain_segment_blocks(&chain_segment);
+
+    harness
+        .chain
+        .slot_clock
+        .set_slot(blocks.last().unwrap().slot().as_u64());
+
+    // The first blocks are already known, so they will be filtered out of the segment.
+    let known_blocks = 2;
+    harness
+        .chain
+        .process_chain_segment(blocks[..known_blocks].to_vec(), NotifyExecutionLayer::Yes)
+        .await
+        .into_block_error()
+        .expect("should import blocks");
+
+    // A block with an invalid signature fails the signature verification of its epoch.
+    let invalid_index = 70;
+    let (block, _) = blocks[invalid_index].as_ref().clone().deconstruct();
+    blocks[invalid_index] = Arc::new(SignedBeaconBlock::from_block(block, junk_signature()));
+    let invalid_epoch = blocks[invalid_index].message().epoch();
+
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    assert!(matches!(
+        harness
+            .chain
+            .process_chain_segment_with_progress(
+                blocks.clone(),
+                NotifyExecutionLayer::Yes,
+                Some(progress_tx),
+                None,
+            )
+            .await,
+        ChainSegmentResult::Failed {
+            error: BlockError::InvalidSignature {
+                kind: SignatureKind::BlockProposal
+            },
+            ..
+        }
+    ));
+
+    // Each block should be reported exactly once, in order.
+    for (i, block) in blocks.iter().enumerate() {
+        let (block_root, outcome) = progress_rx.recv().await.expect("should report block");
+        assert_eq!(block_root, block.canonical_root(), "block {i}");
+        if i < known_blocks {
+            assert!(
+                matches!(
+                    outcome,
+                    ChainSegmentBlockOutcome::NotImported(BlockError::BlockIsAlreadyKnown)
+                ),
+                "block {i}"
+            );
+        } else if block.message().epoch() < invalid_epoch {
+            assert!(
+                matches!(outcome, ChainSegmentBlockOutcome::Imported),
+                "block {i}"
+            );
+        } else if block.message().epoch() == invalid_epoch {
+            assert!(
+                matches!(outcome, ChainSegmentBlockOutcome::BatchFailed),
+                "block {i}"
+            );
+        } else {
+            assert!(
+                matches!(outcome, ChainSegmentBlockOutcome::NotProcessed),
+                "block {i}"
+            );
+        }
+    }
+    assert!(progress_rx.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn chain_segment_cancelled() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let chain_segment = get_chain_segment().await;
+    let blocks = chain_segment_blocks(&chain_segment);
+
+    harness
+        .chain
+        .slot_clock
+        .set_slot(blocks.last().unwrap().slot().as_u64());
+
+    // Nothing is imported if the token is cancelled before processing starts.
+    let cancellation_token = CancellationToken::new();
+    cancellation_token.cancel();
+    assert!(matches!(
+        harness
+            .chain
+            .process_chain_segment_with_progress(
+                blocks.clone(),
+                NotifyExecutionLayer::Yes,
+                None,
+                Some(cancellation_token),
+            )
+            .await,
+        ChainSegmentResult::Cancelled { imported_blocks: 0 }
+    ));
+
+    // Cancel once the first block has been imported.
+    let cancellation_token = CancellationToken::new();
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+    let canceller = {
+        let cancellation_token = cancellation_token.clone();
+        tokio::spawn(async move {
+            progress_rx.recv().await.expect("should report block");
+            cancellation_token.cancel();
+        })
+    };
+    let imported_blocks = match harness
+        .chain
+        .process_chain_segment_with_progress(
+            blocks.clone(),
+            NotifyExecutionLayer::Yes,
+            Some(progress_tx),
+            Some(cancellation_token),
+        )
+        .await
+    {
+        ChainSegmentResult::Cancelled { imported_blocks } => imported_blocks,
+        _ => panic!("should be cancelled"),
+    };
+    canceller.await.unwrap();
+    assert!(imported_blocks > 0 && imported_blocks < blocks.len());
+
+    // The blocks prior to cancellation are imported, the rest are not. The proposals of the
+    // blocks which were not imported must not have been observed, otherwise later copies of them
+    // would be ignored.
+    let fork_choice = harness.chain.canonical_head.fork_choice_read_lock();
+    let observed_block_producers = harness.chain.observed_block_producers.read();
+    for (i, block) in blocks.iter().enumerate() {
+        let block_root = block.canonical_root();
+        assert_eq!(
+            fork_choice.contains_block(&block_root),
+            i < imported_blocks,
+            "block {i}"
+        );
+        assert_eq!(
+            observed_block_producers
+                .proposer_has_been_observed(block.message(), block_root)
+                .unwrap()
+                .proposer_previously_observed(),
+            i < imported_blocks,
+            "block {i}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn chain_segment_failed_import_observes_imported_proposals() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let chain_segment = get_chain_segment().await;
+    let blocks = chain_segment_blocks(&chain_segment);
+
+    harness
+        .chain
+        .slot_clock
+        .set_slot(blocks.last().unwrap().slot().as_u64());
+
+    // A validly signed block with an invalid state root fails during import, after the blocks
+    // which precede it have been imported.
+    let failed_index = 3;
+    let invalid_block = with_state_root(
+        &blocks[failed_index],
+        Hash256::repeat_byte(42),
+        &chain_segment[failed_index].beacon_state,
+        &harness,
+    );
+    let mut segment = blocks[..failed_index].to_vec();
+    segment.push(invalid_block.clone());
+
+    match harness
+        .chain
+        .process_chain_segment(segment, NotifyExecutionLayer::Yes)
+        .await
+    {
+        ChainSegmentResult::Failed {
+            imported_blocks,
+            error: BlockError::StateRootMismatch { .. },
+        } => assert_eq!(imported_blocks, failed_index),
+        _ => panic!("should fail on the invalid block"),
+    }
+
+    // Only the proposals of the imported blocks are observed.
+    let observed_block_producers = harness.chain.observed_block_producers.read();
+    let is_observed = |block: &SignedBeaconBlock<E>| {
+        observed_block_producers
+            .proposer_has_been_observed(block.message(), block.canonical_root())
+            .unwrap()
+            .proposer_previously_observed()
+    };
+    for (i, block) in blocks.iter().enumerate().take(failed_index + 1) {
+        assert_eq!(is_observed(block), i < failed_index, "block {i}");
+    }
+    assert!(!is_observed(&invalid_block));
+}
+
 #[tokio::test]
 async fn chain_segment_non_linear_parent_roots() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -255,6 +518,123 @@ async fn chain_segment_non_linear_parent_roots() {
     );
 }
 
+#[tokio::test]
+async fn process_blocks_reverse() {
+    let chain_segment = get_chain_segment().await;
+    let reversed_blocks = || {
+        let mut blocks = chain_segment_blocks(&chain_segment);
+        blocks.reverse();
+        blocks
+    };
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .chain
+        .slot_clock
+        .set_slot(chain_segment.last().unwrap().beacon_block.slot().as_u64());
+
+    /*
+     * Test with blocks in ascending order, which are not linear when reversed.
+     */
+    assert!(
+        matches!(
+            harness
+                .chain
+                .process_blocks_reverse(
+                    chain_segment_blocks(&chain_segment),
+                    NotifyExecutionLayer::Yes
+                )
+                .await,
+            ChainSegmentResult::Failed {
+                imported_blocks: 0,
+                error: BlockError::NonLinearParentRoots
+            }
+        ),
+        "should not import blocks in ascending order"
+    );
+
+    /*
+     * Test with a block removed.
+     */
+    let mut blocks = reversed_blocks();
+    blocks.remove(2);
+    assert!(
+        matches!(
+            harness
+                .chain
+                .process_blocks_reverse(blocks, NotifyExecutionLayer::Yes)
+                .await,
+            ChainSegmentResult::Failed {
+                imported_blocks: 0,
+                error: BlockError::NonLinearParentRoots
+            }
+        ),
+        "should not import chain with missing parent"
+    );
+
+    /*
+     * Test with a full chain in descending order.
+     */
+    let blocks = reversed_blocks();
+    harness
+        .chain
+        .process_blocks_reverse(blocks.clone(), NotifyExecutionLayer::Yes)
+        .await
+        .into_block_error()
+        .expect("should import reversed chain segment");
+
+    harness.chain.recompute_head_at_current_slot().await;
+    assert_eq!(
+        harness.head_block_root(),
+        blocks.first().unwrap().canonical_root(),
+        "harness should have the highest block as head"
+    );
+}
+
+#[tokio::test]
+async fn verify_blocks_independently() {
+    let chain_segment = get_chain_segment().await;
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .chain
+        .slot_clock
+        .set_slot(chain_segment.last().unwrap().beacon_block.slot().as_u64());
+
+    let blocks = chain_segment_blocks(&chain_segment);
+    harness
+        .chain
+        .process_chain_segment(blocks[..4].to_vec(), NotifyExecutionLayer::Yes)
+        .await
+        .into_block_error()
+        .expect("should import the start of the chain segment");
+
+    // A block with an invalid proposal signature, amongst blocks with known parents.
+    let (block, _) = blocks[4].as_ref().clone().deconstruct();
+    let invalid_block = Arc::new(SignedBeaconBlock::from_block(block, junk_signature()));
+
+    let results = harness.chain.verify_blocks_independently(vec![
+        // This block has already been imported.
+        blocks[1].clone(),
+        invalid_block,
+        blocks[4].clone(),
+        // The parent of this block has not been imported.
+        blocks[6].clone(),
+    ]);
+
+    assert_eq!(results.len(), 4);
+    assert!(matches!(results[0], Err(BlockError::BlockIsAlreadyKnown)));
+    assert!(matches!(
+        results[1],
+        Err(BlockError::InvalidSignature {
+            kind: SignatureKind::BlockProposal
+        })
+    ));
+    assert_eq!(
+        results[2].as_ref().unwrap().block_root(),
+        blocks[4].canonical_root()
+    );
+    assert!(matches!(results[3], Err(BlockError::ParentUnknown(_))));
+}
+
 #[tokio::test]
 async fn chain_segment_non_linear_slots() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -307,88 +687,443 @@ async fn chain_segment_non_linear_slots() {
     );
 }
 
-async fn assert_invalid_signature(
-    chain_segment: &[BeaconSnapshot<E>],
-    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
-    block_index: usize,
-    snapshots: &[BeaconSnapshot<E>],
-    item: &str,
-) {
-    let blocks = snapshots
-        .iter()
-        .map(|snapshot| snapshot.beacon_block.clone())
-        .collect();
+#[tokio::test]
+async fn chain_segment_linearity_without_import() {
+    let chain_segment = get_chain_segment().await;
 
-    // Ensure the block will be rejected if imported in a chain segment.
+    let blocks = chain_segment_blocks(&chain_segment);
+    assert!(
+        validate_chain_segment_linearity(&blocks).is_ok(),
+        "should accept a linear chain"
+    );
+
+    let mut blocks = chain_segment_blocks(&chain_segment);
+    blocks.remove(2);
     assert!(
         matches!(
-            harness
-                .chain
-                .process_chain_segment(blocks, NotifyExecutionLayer::Yes)
-                .await
-                .into_block_error(),
-            Err(BlockError::InvalidSignature)
+            validate_chain_segment_linearity(&blocks),
+            Err(BlockError::NonLinearParentRoots)
         ),
-        "should not import chain segment with an invalid {} signature",
-        item
+        "should reject chain with missing parent"
     );
 
-    // Call fork choice to update cached head (including finalization).
-    harness.chain.recompute_head_at_current_slot().await;
-
-    // Ensure the block will be rejected if imported on its own (without gossip checking).
-    let ancestor_blocks = chain_segment
-        .iter()
-        .take(block_index)
-        .map(|snapshot| snapshot.beacon_block.clone())
-        .collect();
-    // We don't care if this fails, we just call this to ensure that all prior blocks have been
-    // imported prior to this test.
-    let _ = harness
-        .chain
-        .process_chain_segment(ancestor_blocks, NotifyExecutionLayer::Yes)
-        .await;
-    harness.chain.recompute_head_at_current_slot().await;
-
-    let process_res = harness
-        .chain
-        .process_block(
-            snapshots[block_index].beacon_block.canonical_root(),
-            snapshots[block_index].beacon_block.clone(),
-            NotifyExecutionLayer::Yes,
-            || Ok(()),
-        )
-        .await;
+    let mut blocks = chain_segment_blocks(&chain_segment);
+    let (mut block, signature) = blocks[3].as_ref().clone().deconstruct();
+    *block.slot_mut() = blocks[2].slot();
+    blocks[3] = Arc::new(SignedBeaconBlock::from_block(block, signature));
     assert!(
-        matches!(process_res, Err(BlockError::InvalidSignature)),
-        "should not import individual block with an invalid {} signature, got: {:?}",
-        item,
-        process_res
+        matches!(
+            validate_chain_segment_linearity(&blocks),
+            Err(BlockError::NonLinearSlots)
+        ),
+        "should reject chain with a parent that has an equal slot to its child"
     );
-
-    // NOTE: we choose not to check gossip verification here. It only checks one signature
-    // (proposal) and that is already tested elsewhere in this file.
-    //
-    // It's not trivial to just check gossip verification since it will start refusing
-    // blocks as soon as it has seen one valid proposal signature for a given (validator,
-    // slot) tuple.
 }
 
-async fn get_invalid_sigs_harness(
-    chain_segment: &[BeaconSnapshot<E>],
-) -> BeaconChainHarness<EphemeralHarnessType<E>> {
-    let harness = get_harness(VALIDATOR_COUNT);
-    harness
-        .chain
-        .slot_clock
-        .set_slot(chain_segment.last().unwrap().beacon_block.slot().as_u64());
-    harness
-}
 #[tokio::test]
-async fn invalid_signature_gossip_block() {
+async fn prepare_chain_segment_inputs() {
     let chain_segment = get_chain_segment().await;
-    for &block_index in BLOCK_INDICES {
-        // Ensure the block will be rejected if imported on its own (without gossip checking).
+    let blocks = chain_segment_blocks(&chain_segment);
+    let expected = blocks
+        .iter()
+        .map(|block| (block.canonical_root(), block.clone()))
+        .collect::<Vec<_>>();
+
+    // Duplicates are removed.
+    let mut duplicated = blocks.clone();
+    duplicated.insert(3, blocks[3].clone());
+    duplicated.push(blocks[0].clone());
+    assert_eq!(prepare_chain_segment(duplicated).unwrap(), expected);
+
+    // Out-of-order blocks are sorted.
+    let mut out_of_order = blocks.clone();
+    out_of_order.reverse();
+    out_of_order.swap(0, 5);
+    assert_eq!(prepare_chain_segment(out_of_order).unwrap(), expected);
+
+    // A missing block is not repaired.
+    let mut missing = blocks.clone();
+    missing.remove(2);
+    assert!(matches!(
+        prepare_chain_segment(missing),
+        Err(BlockError::NonLinearParentRoots)
+    ));
+
+    // Nor is a block which is not from the segment.
+    let mut foreign = blocks.clone();
+    let (mut block, signature) = foreign[3].as_ref().clone().deconstruct();
+    *block.parent_root_mut() = Hash256::repeat_byte(42);
+    foreign[3] = Arc::new(SignedBeaconBlock::from_block(block, signature));
+    assert!(matches!(
+        prepare_chain_segment(foreign),
+        Err(BlockError::NonLinearParentRoots)
+    ));
+}
+
+#[tokio::test]
+async fn chain_segment_header_signatures() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let chain_segment = get_chain_segment().await;
+    let headers = chain_segment_blocks(&chain_segment)
+        .iter()
+        .map(|block| block.signed_block_header())
+        .collect::<Vec<_>>();
+
+    assert!(
+        verify_chain_segment_headers(&headers, &harness.chain).is_ok(),
+        "should accept correctly signed headers"
+    );
+
+    let index = BLOCK_INDICES[2];
+    let mut forged_headers = headers.clone();
+    forged_headers[index].signature = junk_signature();
+    assert!(
+        matches!(
+            verify_chain_segment_headers(&forged_headers, &harness.chain),
+            Err(BlockError::ProposalSignatureInvalid)
+        ),
+        "should reject a forged header"
+    );
+
+    let mut unknown_proposer_headers = headers;
+    unknown_proposer_headers[index].message.proposer_index = VALIDATOR_COUNT as u64;
+    assert!(
+        matches!(
+            verify_chain_segment_headers(&unknown_proposer_headers, &harness.chain),
+            Err(BlockError::UnknownValidator(proposer)) if proposer == VALIDATOR_COUNT as u64
+        ),
+        "should reject a header from an unknown proposer"
+    );
+}
+
+#[tokio::test]
+async fn chain_segment_header_signatures_across_fork_boundary() {
+    let mut spec = MainnetEthSpec::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(1));
+
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec)
+        .keypairs(KEYPAIRS[..].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    // Build all the blocks in epoch 0 so that the head remains in the base fork.
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize - 1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // The first Altair block is not signed with the fork at the head.
+    harness.advance_slot();
+    let (block, _) = harness
+        .make_block(harness.get_current_state(), harness.get_current_slot())
+        .await;
+    assert!(block.as_altair().is_ok(), "test expects an altair block");
+    let header = block.signed_block_header();
+    let head_fork = harness.chain.canonical_head.cached_head().head_fork();
+    assert_ne!(head_fork.current_version, harness.spec.altair_fork_version);
+
+    assert!(
+        verify_chain_segment_headers(&[header.clone()], &harness.chain).is_ok(),
+        "should accept a header from the next fork"
+    );
+
+    let mut forged_header = header;
+    forged_header.signature = junk_signature();
+    assert!(
+        matches!(
+            verify_chain_segment_headers(&[forged_header], &harness.chain),
+            Err(BlockError::ProposalSignatureInvalid)
+        ),
+        "should reject a forged header from the next fork"
+    );
+}
+
+#[tokio::test]
+async fn chain_segments_batch_signature_verification() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let chain_segment = get_chain_segment().await;
+    let blocks = chain_segment_blocks(&chain_segment);
+
+    harness
+        .chain
+        .slot_clock
+        .set_slot(blocks.last().unwrap().slot().as_u64());
+
+    // Disjoint segments from consecutive epochs. The block at index `i` is at slot `i + 1`.
+    let (index_a, index_b) = (32, 64);
+    assert_eq!(
+        blocks[index_a].slot().epoch(E::slots_per_epoch()) + 1,
+        blocks[index_b].slot().epoch(E::slots_per_epoch()),
+        "segments should be from consecutive epochs"
+    );
+
+    // Import the blocks prior to the second segment so that each segment has a known parent.
+    harness
+        .chain
+        .process_chain_segment(blocks[..index_b].to_vec(), NotifyExecutionLayer::Yes)
+        .await
+        .into_block_error()
+        .expect("should import ancestors");
+
+    let with_roots = |blocks: &[Arc<SignedBeaconBlock<E>>]| {
+        blocks
+            .iter()
+            .map(|block| (block.canonical_root(), block.clone()))
+            .collect::<Vec<_>>()
+    };
+    let segment_a = with_roots(&blocks[index_a..index_a + 3]);
+    let segment_b = with_roots(&blocks[index_b..index_b + 2]);
+
+    let results = signature_verify_chain_segments(
+        vec![segment_a.clone(), vec![], segment_b.clone()],
+        &harness.chain,
+    )
+    .expect("should read pubkey cache");
+    let roots =
+        |result: &Result<Vec<SignatureVerifiedBlock<EphemeralHarnessType<E>>>, BlockError<E>>| {
+            result
+                .as_ref()
+                .expect("should verify segment")
+                .iter()
+                .map(|block| block.block_root())
+                .collect::<Vec<_>>()
+        };
+    assert_eq!(results.len(), 3, "should return a result per segment");
+    assert_eq!(
+        roots(&results[0]),
+        segment_a.iter().map(|(root, _)| *root).collect::<Vec<_>>()
+    );
+    assert!(roots(&results[1]).is_empty());
+    assert_eq!(
+        roots(&results[2]),
+        segment_b.iter().map(|(root, _)| *root).collect::<Vec<_>>()
+    );
+
+    // A single invalid signature in the second segment should fail only that segment.
+    let (block, _) = blocks[index_b].as_ref().clone().deconstruct();
+    let junk_block = Arc::new(SignedBeaconBlock::from_block(block, junk_signature()));
+    let invalid_segment_b = vec![(junk_block.canonical_root(), junk_block)];
+    let results =
+        signature_verify_chain_segments(vec![segment_a.clone(), invalid_segment_b], &harness.chain)
+            .expect("should read pubkey cache");
+    assert_eq!(
+        roots(&results[0]),
+        segment_a.iter().map(|(root, _)| *root).collect::<Vec<_>>(),
+        "should accept the segment without an invalid signature"
+    );
+    assert!(
+        matches!(
+            results[1],
+            Err(BlockError::InvalidSignature {
+                kind: SignatureKind::BlockProposal
+            })
+        ),
+        "should reject the segment with an invalid signature"
+    );
+}
+
+#[tokio::test]
+async fn chain_segment_with_independent_forks() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let state = harness.get_current_state();
+
+    // Two forks which diverge from the genesis block, one of which is two blocks long.
+    let (fork_a_1, state_a) = harness.make_block(state.clone(), Slot::new(1)).await;
+    let (fork_a_2, _) = harness.make_block(state_a, Slot::new(3)).await;
+    let (fork_b_1, _) = harness.make_block(state, Slot::new(2)).await;
+    let (fork_a_1, fork_a_2, fork_b_1) =
+        (Arc::new(fork_a_1), Arc::new(fork_a_2), Arc::new(fork_b_1));
+    harness.chain.slot_clock.set_slot(3);
+
+    // A linear segment is not partitioned.
+    assert_eq!(
+        harness
+            .chain
+            .partition_chain_segment(&[fork_a_1.clone(), fork_a_2.clone()]),
+        None
+    );
+
+    // A segment containing the same block twice is not partitioned.
+    assert_eq!(
+        harness
+            .chain
+            .partition_chain_segment(&[fork_a_1.clone(), fork_a_1.clone()]),
+        None
+    );
+
+    let blocks = vec![fork_a_1.clone(), fork_b_1.clone(), fork_a_2.clone()];
+    let chain_segments = harness
+        .chain
+        .partition_chain_segment(&blocks)
+        .expect("should partition independent forks");
+    assert_eq!(
+        chain_segments,
+        vec![
+            vec![fork_a_1.clone(), fork_a_2.clone()],
+            vec![fork_b_1.clone()]
+        ]
+    );
+
+    assert!(matches!(
+        harness
+            .chain
+            .process_chain_segments_concurrently(
+                chain_segments,
+                NotifyExecutionLayer::Yes,
+                None,
+                None,
+            )
+            .await,
+        ChainSegmentResult::Successful { imported_blocks: 3 }
+    ));
+
+    let fork_choice = harness.chain.canonical_head.fork_choice_read_lock();
+    for block in &blocks {
+        assert!(fork_choice.contains_block(&block.canonical_root()));
+    }
+}
+
+async fn assert_invalid_signature(
+    chain_segment: &[BeaconSnapshot<E>],
+    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+    block_index: usize,
+    snapshots: &[BeaconSnapshot<E>],
+    item: &str,
+    kind: SignatureKind,
+) {
+    let blocks = snapshots
+        .iter()
+        .map(|snapshot| snapshot.beacon_block.clone())
+        .collect();
+
+    // Ensure the block will be rejected if imported in a chain segment.
+    let segment_res = harness
+        .chain
+        .process_chain_segment(blocks, NotifyExecutionLayer::Yes)
+        .await
+        .into_block_error();
+    assert!(
+        matches!(
+            segment_res,
+            Err(BlockError::InvalidSignature { kind: actual }) if actual == kind
+        ),
+        "should not import chain segment with an invalid {} signature",
+        item
+    );
+    // The peer which sent the segment should be penalized.
+    assert_eq!(
+        segment_res.unwrap_err().penalize_peer(),
+        Some(PeerPenalty::LowToleranceError)
+    );
+
+    // Call fork choice to update cached head (including finalization).
+    harness.chain.recompute_head_at_current_slot().await;
+
+    // Ensure the block will be rejected if imported on its own (without gossip checking).
+    let ancestor_blocks = chain_segment
+        .iter()
+        .take(block_index)
+        .map(|snapshot| snapshot.beacon_block.clone())
+        .collect();
+    // We don't care if this fails, we just call this to ensure that all prior blocks have been
+    // imported prior to this test.
+    let _ = harness
+        .chain
+        .process_chain_segment(ancestor_blocks, NotifyExecutionLayer::Yes)
+        .await;
+    harness.chain.recompute_head_at_current_slot().await;
+
+    let process_res = harness
+        .chain
+        .process_block(
+            snapshots[block_index].beacon_block.canonical_root(),
+            snapshots[block_index].beacon_block.clone(),
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await;
+    assert!(
+        matches!(
+            process_res,
+            Err(BlockError::InvalidSignature { kind: actual }) if actual == kind
+        ),
+        "should not import individual block with an invalid {} signature, got: {:?}",
+        item,
+        process_res
+    );
+
+    // NOTE: we choose not to check gossip verification here. It only checks one signature
+    // (proposal) and that is already tested elsewhere in this file.
+    //
+    // It's not trivial to just check gossip verification since it will start refusing
+    // blocks as soon as it has seen one valid proposal signature for a given (validator,
+    // slot) tuple.
+}
+
+#[test]
+fn signature_errors_penalize_peer() {
+    assert_eq!(
+        BlockError::<E>::ProposalSignatureInvalid.penalize_peer(),
+        Some(PeerPenalty::LowToleranceError)
+    );
+    assert_eq!(
+        BlockError::<E>::InvalidSignature {
+            kind: SignatureKind::Unknown
+        }
+        .penalize_peer(),
+        Some(PeerPenalty::LowToleranceError)
+    );
+}
+
+#[test]
+fn recent_not_finalized_descendant_is_not_penalized() {
+    let error = BlockError::<E>::NotFinalizedDescendant {
+        block_parent_root: Hash256::zero(),
+    };
+    let current_slot = Slot::new(100);
+
+    assert_eq!(
+        error.penalize_gossip_peer(Slot::new(98), current_slot, 2),
+        None,
+        "should not penalize a block within the grace window"
+    );
+    assert_eq!(
+        error.penalize_gossip_peer(Slot::new(97), current_slot, 2),
+        Some(PeerPenalty::HighToleranceError),
+        "should penalize a block outside the grace window"
+    );
+    assert_eq!(
+        BlockError::<E>::ProposalSignatureInvalid.penalize_gossip_peer(
+            Slot::new(100),
+            current_slot,
+            2
+        ),
+        Some(PeerPenalty::LowToleranceError),
+        "should defer to the usual penalty for other errors"
+    );
+}
+
+async fn get_invalid_sigs_harness(
+    chain_segment: &[BeaconSnapshot<E>],
+) -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .chain
+        .slot_clock
+        .set_slot(chain_segment.last().unwrap().beacon_block.slot().as_u64());
+    harness
+}
+#[tokio::test]
+async fn invalid_signature_gossip_block() {
+    let chain_segment = get_chain_segment().await;
+    for &block_index in BLOCK_INDICES {
+        // Ensure the block will be rejected if imported on its own (without gossip checking).
         let harness = get_invalid_sigs_harness(&chain_segment).await;
         let mut snapshots = chain_segment.clone();
         let (block, _) = snapshots[block_index]
@@ -424,7 +1159,9 @@ async fn invalid_signature_gossip_block() {
                         || Ok(()),
                     )
                     .await,
-                Err(BlockError::InvalidSignature)
+                Err(BlockError::InvalidSignature {
+                    kind: SignatureKind::BlockProposal
+                })
             ),
             "should not import individual block with an invalid gossip signature",
         );
@@ -458,7 +1195,9 @@ async fn invalid_signature_block_proposal() {
                     .process_chain_segment(blocks, NotifyExecutionLayer::Yes)
                     .await
                     .into_block_error(),
-                Err(BlockError::InvalidSignature)
+                Err(BlockError::InvalidSignature {
+                    kind: SignatureKind::BlockProposal
+                })
             ),
             "should not import chain segment with an invalid block signature",
         );
@@ -481,7 +1220,15 @@ async fn invalid_signature_randao_reveal() {
             Arc::new(SignedBeaconBlock::from_block(block, signature));
         update_parent_roots(&mut snapshots);
         update_proposal_signatures(&mut snapshots, &harness);
-        assert_invalid_signature(&chain_segment, &harness, block_index, &snapshots, "randao").await;
+        assert_invalid_signature(
+            &chain_segment,
+            &harness,
+            block_index,
+            &snapshots,
+            "randao",
+            SignatureKind::RandaoReveal,
+        )
+        .await;
     }
 }
 
@@ -521,6 +1268,7 @@ async fn invalid_signature_proposer_slashing() {
             block_index,
             &snapshots,
             "proposer slashing",
+            SignatureKind::ProposerSlashing,
         )
         .await;
     }
@@ -573,6 +1321,7 @@ async fn invalid_signature_attester_slashing() {
             block_index,
             &snapshots,
             "attester slashing",
+            SignatureKind::AttesterSlashing,
         )
         .await;
     }
@@ -603,6 +1352,7 @@ async fn invalid_signature_attestation() {
                 block_index,
                 &snapshots,
                 "attestation",
+                SignatureKind::Attestation,
             )
             .await;
             checked_attestation = true;
@@ -656,13 +1406,59 @@ async fn invalid_signature_deposit() {
                     .process_chain_segment(blocks, NotifyExecutionLayer::Yes)
                     .await
                     .into_block_error(),
-                Err(BlockError::InvalidSignature)
+                Err(BlockError::InvalidSignature { .. })
             ),
             "should not throw an invalid signature error for a bad deposit signature"
         );
     }
 }
 
+#[tokio::test]
+async fn verify_deposit_signatures_reports_each_deposit() {
+    let spec = E::default_spec();
+    let chain_segment = get_chain_segment().await;
+
+    let deposit = |data| Deposit {
+        proof: vec![Hash256::zero(); DEPOSIT_TREE_DEPTH + 1].into(),
+        data,
+    };
+    let keypair = Keypair::random();
+    let mut valid_data = DepositData {
+        pubkey: keypair.pk.clone().into(),
+        withdrawal_credentials: Hash256::zero(),
+        amount: spec.max_effective_balance,
+        signature: SignatureBytes::empty(),
+    };
+    valid_data.signature = valid_data.create_signature(&keypair.sk, &spec);
+    let bad_signature_data = DepositData {
+        signature: junk_signature().into(),
+        ..valid_data.clone()
+    };
+    let bad_pubkey_data = DepositData {
+        pubkey: PublicKeyBytes::empty(),
+        ..valid_data.clone()
+    };
+
+    let (mut block, signature) = chain_segment[0].beacon_block.as_ref().clone().deconstruct();
+    for data in [bad_signature_data, valid_data, bad_pubkey_data] {
+        block
+            .body_mut()
+            .deposits_mut()
+            .push(deposit(data))
+            .expect("should add deposit");
+    }
+    let block = SignedBeaconBlock::from_block(block, signature);
+
+    assert_eq!(
+        verify_deposit_signatures(&block, &spec),
+        vec![(0, false), (1, true), (2, false)]
+    );
+    assert!(
+        verify_deposit_signatures(chain_segment[1].beacon_block.as_ref(), &spec).is_empty(),
+        "a block without deposits should report nothing"
+    );
+}
+
 #[tokio::test]
 async fn invalid_signature_exit() {
     let chain_segment = get_chain_segment().await;
@@ -696,6 +1492,7 @@ async fn invalid_signature_exit() {
             block_index,
             &snapshots,
             "voluntary exit",
+            SignatureKind::VoluntaryExit,
         )
         .await;
     }
@@ -766,8 +1563,11 @@ async fn block_gossip_verification() {
             BlockError::FutureSlot {
                 present_slot,
                 block_slot,
+                tolerance,
             }
-            if present_slot == expected_block_slot - 1 && block_slot == expected_block_slot
+            if present_slot == expected_block_slot - 1
+                && block_slot == expected_block_slot
+                && tolerance == harness.chain.spec.maximum_gossip_clock_disparity()
         ),
         "should not import a block with a future slot"
     );
@@ -914,60 +1714,1637 @@ async fn block_gossip_verification() {
         &generate_deterministic_keypair(other_proposer as usize).sk,
         &harness.chain.canonical_head.cached_head().head_fork(),
         harness.chain.genesis_validators_root,
-        &harness.chain.spec,
-    );
-    assert!(
-        matches!(
-            unwrap_err(harness.chain.verify_block_for_gossip(Arc::new(block.clone())).await),
-            BlockError::IncorrectBlockProposer {
-                block,
-                local_shuffling,
-            }
-            if block == other_proposer && local_shuffling == expected_proposer
-        ),
-        "should not import a block with the wrong proposer index"
-    );
-    // Check to ensure that we registered this is a valid block from this proposer.
-    assert!(
-        matches!(
-            unwrap_err(harness.chain.verify_block_for_gossip(Arc::new(block.clone())).await),
-            BlockError::BlockIsAlreadyKnown,
-        ),
-        "should register any valid signature against the proposer, even if the block failed later verification"
-    );
+        &harness.chain.spec,
+    );
+    assert!(
+        matches!(
+            unwrap_err(harness.chain.verify_block_for_gossip(Arc::new(block.clone())).await),
+            BlockError::IncorrectBlockProposer {
+                block,
+                local_shuffling,
+            }
+            if block == other_proposer && local_shuffling == expected_proposer
+        ),
+        "should not import a block with the wrong proposer index"
+    );
+    // Check to ensure that we registered this is a valid block from this proposer.
+    assert!(
+        matches!(
+            unwrap_err(harness.chain.verify_block_for_gossip(Arc::new(block.clone())).await),
+            BlockError::BlockIsAlreadyKnown,
+        ),
+        "should register any valid signature against the proposer, even if the block failed later verification"
+    );
+
+    let block = chain_segment[block_index].beacon_block.clone();
+    assert!(
+        harness.chain.verify_block_for_gossip(block).await.is_ok(),
+        "the valid block should be processed"
+    );
+
+    /*
+     * This test ensures that:
+     *
+     * Spec v0.12.1
+     *
+     * The block is the first block with valid signature received for the proposer for the slot,
+     * signed_beacon_block.message.slot.
+     */
+
+    let block = chain_segment[block_index].beacon_block.clone();
+    assert!(
+        matches!(
+            harness
+                .chain
+                .verify_block_for_gossip(block.clone())
+                .await
+                .err()
+                .expect("should error when processing known block"),
+            BlockError::BlockIsAlreadyKnown
+        ),
+        "the second proposal by this validator should be rejected"
+    );
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_slashing_detection() {
+    let slasher_dir = tempdir().unwrap();
+    let slasher = Arc::new(
+        Slasher::open(SlasherConfig::new(slasher_dir.path().into()), test_logger()).unwrap(),
+    );
+
+    let inner_slasher = slasher.clone();
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .initial_mutator(Box::new(move |builder| builder.slasher(inner_slasher)))
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block1, _) = harness.make_block(state.clone(), Slot::new(1)).await;
+    let (block2, _) = harness.make_block(state, Slot::new(1)).await;
+
+    let verified_block = harness
+        .chain
+        .verify_block_for_gossip(Arc::new(block1))
+        .await
+        .unwrap();
+    harness
+        .chain
+        .process_block(
+            verified_block.block_root,
+            verified_block,
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+        .unwrap();
+    unwrap_err(
+        harness
+            .chain
+            .verify_block_for_gossip(Arc::new(block2))
+            .await,
+    );
+
+    // Slasher should have been handed the two conflicting blocks and crafted a slashing.
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    let proposer_slashings = slasher.get_proposer_slashings();
+    assert_eq!(proposer_slashings.len(), 1);
+    // windows won't delete the temporary directory if you don't do this..
+    drop(harness);
+    drop(slasher);
+    slasher_dir.close().unwrap();
+}
+
+#[tokio::test]
+async fn verify_block_dry_run_has_no_side_effects() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+
+    let dry_run = harness
+        .chain
+        .verify_block_dry_run(block.clone(), NotifyExecutionLayer::Yes)
+        .await
+        .unwrap();
+    assert_eq!(dry_run.block_root, block_root);
+    assert_eq!(dry_run.state.slot(), Slot::new(1));
+    assert!(dry_run.payload_verification_handle.outcome().await.is_ok());
+
+    // The block must not have been imported.
+    assert!(!harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&block_root));
+
+    // The proposal must not have been observed, so the block is still valid on gossip.
+    let verified_block = harness.chain.verify_block_for_gossip(block).await.unwrap();
+    harness
+        .chain
+        .process_block(
+            verified_block.block_root,
+            verified_block,
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn verify_block_against_parent() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    // Two competing forks from genesis.
+    let genesis_state = harness.get_current_state();
+    let (fork_a_root, _, fork_a_state) = harness
+        .add_block_at_slot(Slot::new(1), genesis_state.clone())
+        .await
+        .unwrap();
+    let (fork_b_root, _, _) = harness
+        .add_block_at_slot(Slot::new(2), genesis_state)
+        .await
+        .unwrap();
+    let fork_a_root: Hash256 = fork_a_root.into();
+    let fork_b_root: Hash256 = fork_b_root.into();
+
+    // A block built on fork A, which is not the head.
+    harness.set_current_slot(Slot::new(3));
+    let (block, _) = harness.make_block(fork_a_state, Slot::new(3)).await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+
+    let verify = |parent_root| {
+        harness.chain.verify_block_against_parent(
+            block.clone(),
+            parent_root,
+            NotifyExecutionLayer::Yes,
+        )
+    };
+
+    // The block is valid against its own parent.
+    let dry_run = verify(fork_a_root).await.unwrap();
+    assert_eq!(dry_run.block_root, block_root);
+    assert_eq!(dry_run.state.slot(), Slot::new(3));
+
+    // The block is invalid on the other fork, since it commits to its parent.
+    assert!(matches!(
+        verify(fork_b_root).await,
+        Err(BlockError::PerBlockProcessingError(
+            BlockProcessingError::HeaderInvalid { .. }
+        ))
+    ));
+
+    // A candidate parent which is not in the database is unknown.
+    assert!(matches!(
+        verify(Hash256::repeat_byte(42)).await,
+        Err(BlockError::ParentUnknown(_))
+    ));
+
+    // Nothing was imported.
+    assert!(!harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&block_root));
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_concurrent_equivocation() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let genesis_state = harness.get_current_state();
+
+    // Repeat the race a few times, each at a new slot (and so possibly a new proposer).
+    for slot in (1..=4).map(Slot::new) {
+        harness.set_current_slot(slot);
+        let (block, _) = harness.make_block(genesis_state.clone(), slot).await;
+        let (equivocating_block, _) = harness
+            .make_block_with_modifier(genesis_state.clone(), slot, |block| {
+                *block.body_mut().graffiti_mut() = Graffiti::from([42; GRAFFITI_BYTES_LEN]);
+            })
+            .await;
+        assert_ne!(block.canonical_root(), equivocating_block.canonical_root());
+
+        // Release both verifications at once.
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+        let handles = [block, equivocating_block].map(|block| {
+            let chain = harness.chain.clone();
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                GossipVerifiedBlock::new(Arc::new(block), &chain).map(|_| ())
+            })
+        });
+        let results = handles.map(|handle| handle.join().unwrap());
+
+        // Exactly one of the blocks is accepted, the other is an equivocation.
+        assert_eq!(
+            results.iter().filter(|result| result.is_ok()).count(),
+            1,
+            "slot {slot}"
+        );
+        assert_eq!(
+            results
+                .iter()
+                .filter(|result| matches!(result, Err(BlockError::Slashable)))
+                .count(),
+            1,
+            "slot {slot}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_proposer_filter() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .initial_mutator(Box::new(|builder| {
+            builder.proposer_filter(Arc::new(|_proposer_index: u64| false))
+        }))
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let proposer_index = block.message().proposer_index();
+
+    assert!(matches!(
+        unwrap_err(
+            harness
+                .chain
+                .verify_block_for_gossip(Arc::new(block))
+                .await
+        ),
+        BlockError::ProposerFiltered { proposer_index: index } if index == proposer_index
+    ));
+}
+
+#[tokio::test]
+async fn execution_pending_hook_observes_verified_blocks() {
+    let observed = Arc::new(Mutex::new(vec![]));
+    let hook_observed = observed.clone();
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .initial_mutator(Box::new(move |builder| {
+            builder.on_execution_pending(Arc::new(
+                move |execution_pending: &ExecutionPendingBlock<_>| {
+                    hook_observed
+                        .lock()
+                        .unwrap()
+                        .push((execution_pending.block_root, execution_pending.state.slot()))
+                },
+            ))
+        }))
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state.clone(), Slot::new(1)).await;
+
+    let invalid_block = with_state_root(&block, Hash256::repeat_byte(42), &state, &harness);
+
+    // The hook is not run for blocks which fail verification.
+    assert!(matches!(
+        harness
+            .chain
+            .process_block(
+                invalid_block.canonical_root(),
+                invalid_block,
+                NotifyExecutionLayer::Yes,
+                || Ok(()),
+            )
+            .await,
+        Err(BlockError::StateRootMismatch { .. })
+    ));
+    assert!(observed.lock().unwrap().is_empty());
+
+    let block_root = block.canonical_root();
+    harness
+        .chain
+        .process_block(
+            block_root,
+            Arc::new(block),
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(*observed.lock().unwrap(), vec![(block_root, Slot::new(1))]);
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_rechecks_cached_proposer() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            recheck_cached_proposer_on_mismatch: true,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state.clone(), Slot::new(1)).await;
+    let proposer_index = block.message().proposer_index();
+
+    // Poison the proposer cache so that it disagrees with the parent state.
+    let decision_block = harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&block.parent_root())
+        .unwrap()
+        .next_epoch_shuffling_id
+        .shuffling_decision_block;
+    let wrong_proposer = (proposer_index as usize + 1) % VALIDATOR_COUNT;
+    harness
+        .chain
+        .beacon_proposer_cache
+        .lock()
+        .insert(
+            Epoch::new(0),
+            decision_block,
+            vec![wrong_proposer; E::slots_per_epoch() as usize],
+            state.fork(),
+        )
+        .unwrap();
+
+    let inconsistent_rechecks = || {
+        metrics::GOSSIP_BEACON_BLOCK_PROPOSER_RECHECKS
+            .as_ref()
+            .unwrap()
+            .with_label_values(&["inconsistent"])
+            .get()
+    };
+    let inconsistent_rechecks_before = inconsistent_rechecks();
+
+    // The recheck is diagnostic only, the block is still rejected.
+    assert!(matches!(
+        unwrap_err(
+            harness
+                .chain
+                .verify_block_for_gossip(Arc::new(block))
+                .await
+        ),
+        BlockError::IncorrectBlockProposer { block, local_shuffling }
+            if block == proposer_index && local_shuffling == wrong_proposer as u64
+    ));
+
+    // The recheck detected that the cache disagrees with the parent state.
+    assert_eq!(inconsistent_rechecks(), inconsistent_rechecks_before + 1);
+}
+
+#[tokio::test]
+async fn block_relevancy_with_finalized_checkpoint_override() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let slots_per_epoch = E::slots_per_epoch();
+    harness.set_current_slot(Slot::new(2 * slots_per_epoch));
+
+    let finalized_checkpoint = Checkpoint {
+        epoch: Epoch::new(1),
+        root: Hash256::repeat_byte(1),
+    };
+    let finalized_slot = finalized_checkpoint.epoch.start_slot(slots_per_epoch);
+
+    let state = harness.get_current_state();
+    let (finalized_block, _) = harness.make_block(state.clone(), finalized_slot).await;
+    let (later_block, _) = harness.make_block(state, finalized_slot + 1).await;
+
+    // Both blocks are relevant to the live chain, which is not finalized.
+    for block in [&finalized_block, &later_block] {
+        assert!(check_block_relevancy(block, None, &harness.chain, None).is_ok());
+    }
+
+    assert!(matches!(
+        check_block_relevancy(
+            &finalized_block,
+            None,
+            &harness.chain,
+            Some(finalized_checkpoint)
+        ),
+        Err(BlockError::WouldRevertFinalizedSlot { block_slot, finalized_slot: slot })
+            if block_slot == finalized_slot && slot == finalized_slot
+    ));
+    assert_eq!(
+        check_block_relevancy(
+            &later_block,
+            None,
+            &harness.chain,
+            Some(finalized_checkpoint)
+        )
+        .unwrap(),
+        later_block.canonical_root()
+    );
+}
+
+#[tokio::test]
+async fn block_relevancy_at_finalized_slot_boundary() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let slots_per_epoch = E::slots_per_epoch();
+    harness.set_current_slot(Slot::new(2 * slots_per_epoch));
+
+    let finalized_slot = Epoch::new(1).start_slot(slots_per_epoch);
+    let state = harness.get_current_state();
+    let (before_block, _) = harness.make_block(state.clone(), finalized_slot - 1).await;
+    let (finalized_block, _) = harness.make_block(state.clone(), finalized_slot).await;
+    let (conflicting_block, _) = harness.make_block(state.clone(), finalized_slot).await;
+    let (after_block, _) = harness.make_block(state, finalized_slot + 1).await;
+    assert_ne!(
+        finalized_block.canonical_root(),
+        conflicting_block.canonical_root()
+    );
+
+    let finalized_checkpoint = Checkpoint {
+        epoch: Epoch::new(1),
+        root: finalized_block.canonical_root(),
+    };
+    let check = |block: &SignedBeaconBlock<E>| {
+        check_block_relevancy(block, None, &harness.chain, Some(finalized_checkpoint))
+    };
+
+    // Blocks prior to the finalized slot always revert finality.
+    assert!(matches!(
+        check(&before_block),
+        Err(BlockError::WouldRevertFinalizedSlot { block_slot, finalized_slot: slot })
+            if block_slot == finalized_slot - 1 && slot == finalized_slot
+    ));
+    // The finalized block itself is already known.
+    assert!(matches!(
+        check(&finalized_block),
+        Err(BlockError::BlockAtFinalizedSlot { block_root, block_slot })
+            if block_root == finalized_checkpoint.root && block_slot == finalized_slot
+    ));
+    // Any other block at the finalized slot conflicts with finality.
+    assert!(matches!(
+        check(&conflicting_block),
+        Err(BlockError::WouldRevertFinalizedSlot { block_slot, finalized_slot: slot })
+            if block_slot == finalized_slot && slot == finalized_slot
+    ));
+    // Blocks after the finalized slot are relevant.
+    assert_eq!(check(&after_block).unwrap(), after_block.canonical_root());
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_replay() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let block = Arc::new(block);
+
+    // Replaying the same block is idempotent.
+    for _ in 0..2 {
+        let verified_block = GossipVerifiedBlock::new_for_replay(block.clone(), &harness.chain)
+            .expect("should verify block for replay");
+        assert_eq!(verified_block.block_root, block.canonical_root());
+    }
+
+    // The proposal was never recorded, so the block is still valid on live gossip.
+    harness
+        .chain
+        .verify_block_for_gossip(block.clone())
+        .await
+        .expect("should verify block for gossip");
+    assert!(matches!(
+        unwrap_err(harness.chain.verify_block_for_gossip(block).await),
+        BlockError::BlockIsAlreadyKnown
+    ));
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_proposer_rate_limit() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            gossip_proposer_rate_limit: Some(0),
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let proposer_index = block.message().proposer_index();
+
+    assert!(matches!(
+        unwrap_err(
+            harness
+                .chain
+                .verify_block_for_gossip(Arc::new(block))
+                .await
+        ),
+        BlockError::ProposerRateLimited { proposer_index: index } if index == proposer_index
+    ));
+}
+
+#[tokio::test]
+async fn verify_block_too_many_skip_slots() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            block_max_skip_slots: Some(2),
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    for _ in 0..4 {
+        harness.advance_slot();
+    }
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state.clone(), Slot::new(4)).await;
+    assert!(matches!(
+        unwrap_err(
+            harness
+                .chain
+                .verify_block_for_gossip(Arc::new(block))
+                .await
+        ),
+        BlockError::TooManySkipSlots {
+            block_slot,
+            parent_slot,
+            max: 2,
+        } if block_slot == 4 && parent_slot == 0
+    ));
+
+    // Skipping exactly the maximum number of slots is permitted.
+    let (block, _) = harness.make_block(state, Slot::new(3)).await;
+    assert!(harness
+        .chain
+        .verify_block_for_gossip(Arc::new(block))
+        .await
+        .is_ok());
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_slot_limit() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let (mut block, signature) = block.deconstruct();
+
+    // The slot limit takes precedence over the future slot check.
+    *block.slot_mut() = Slot::new(1 << 32);
+    assert!(
+        matches!(
+            unwrap_err(
+                harness
+                    .chain
+                    .verify_block_for_gossip(Arc::new(SignedBeaconBlock::from_block(
+                        block, signature
+                    )))
+                    .await
+            ),
+            BlockError::BlockSlotLimitReached
+        ),
+        "should not import a block at the slot limit"
+    );
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_bogus_proposer_index() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let (mut block, signature) = block.deconstruct();
+
+    let bogus_proposer_index = VALIDATOR_COUNT as u64 * 1_000;
+    *block.proposer_index_mut() = bogus_proposer_index;
+    assert!(
+        matches!(
+            unwrap_err(
+                harness
+                    .chain
+                    .verify_block_for_gossip(Arc::new(SignedBeaconBlock::from_block(
+                        block, signature
+                    )))
+                    .await
+            ),
+            BlockError::UnknownValidator(index) if index == bogus_proposer_index
+        ),
+        "should not import a block from an unknown proposer"
+    );
+}
+
+#[tokio::test]
+async fn verify_block_for_gossip_rejected_block_cache() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+
+    // A block with an unknown parent is never cached, since the parent may arrive later.
+    let (mut unknown_parent_block, signature) = block.clone().deconstruct();
+    *unknown_parent_block.parent_root_mut() = Hash256::from_low_u64_be(42);
+    let unknown_parent_block = Arc::new(SignedBeaconBlock::from_block(
+        unknown_parent_block,
+        signature,
+    ));
+    for _ in 0..2 {
+        assert!(
+            matches!(
+                unwrap_err(
+                    harness
+                        .chain
+                        .verify_block_for_gossip(unknown_parent_block.clone())
+                        .await
+                ),
+                BlockError::ParentUnknown(_)
+            ),
+            "should not cache a block with an unknown parent"
+        );
+    }
+
+    // A block from an unknown proposer is refused without re-verification the second time.
+    let (mut bogus_block, signature) = block.deconstruct();
+    *bogus_block.proposer_index_mut() = VALIDATOR_COUNT as u64 * 1_000;
+    let bogus_block = Arc::new(SignedBeaconBlock::from_block(bogus_block, signature));
+    let bogus_block_root = bogus_block.canonical_root();
+    assert!(
+        matches!(
+            unwrap_err(
+                harness
+                    .chain
+                    .verify_block_for_gossip(bogus_block.clone())
+                    .await
+            ),
+            BlockError::UnknownValidator(_)
+        ),
+        "should not import a block from an unknown proposer"
+    );
+    assert!(
+        matches!(
+            unwrap_err(harness.chain.verify_block_for_gossip(bogus_block).await),
+            BlockError::RecentlyRejected { block_root, rejection }
+            if block_root == bogus_block_root && rejection.reason == "unknown_validator"
+        ),
+        "should refuse a recently rejected block from the cache"
+    );
+}
+
+#[tokio::test]
+async fn verify_block_with_provided_parent_state() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(1))
+        .await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+
+    // Verify the block against a parent which is supplied directly, rather than being loaded from
+    // the snapshot cache or database.
+    let parent = PreProcessingSnapshot::from(head.as_ref().clone());
+    let dry_run = DryRunVerifiedBlock::from_components_with_parent_state(
+        block.clone(),
+        block_root,
+        parent,
+        ConsensusContext::new(Slot::new(1)).set_current_block_root(block_root),
+        &harness.chain,
+        NotifyExecutionLayer::Yes,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(dry_run.block_root, block_root);
+    assert_eq!(dry_run.state.slot(), Slot::new(1));
+    assert_eq!(dry_run.parent_execution_status, None);
+
+    // Verification is a dry run, so the block is neither imported nor is its proposal observed.
+    assert!(!harness.chain.block_is_known_to_fork_choice(&block_root));
+    harness.chain.verify_block_for_gossip(block).await.unwrap();
+}
+
+#[tokio::test]
+async fn verify_block_reports_catchup() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+    harness.advance_slot();
+    let head = harness.chain.head_snapshot();
+
+    let verify = |block: Arc<SignedBeaconBlock<E>>, parent: PreProcessingSnapshot<E>| {
+        let block_root = block.canonical_root();
+        DryRunVerifiedBlock::from_components_with_parent_state(
+            block.clone(),
+            block_root,
+            parent,
+            ConsensusContext::new(block.slot()).set_current_block_root(block_root),
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+            true,
+        )
+        .unwrap()
+    };
+
+    // A block following two skipped slots requires the parent state to be advanced through them.
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(3))
+        .await;
+    let dry_run = verify(
+        Arc::new(block),
+        PreProcessingSnapshot::from(head.as_ref().clone()),
+    );
+    assert_eq!(dry_run.skipped_slots, 2);
+    assert_eq!(dry_run.intermediate_state_roots.len(), 2);
+    assert_eq!(
+        dry_run.parent_execution_status,
+        harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .get_block_execution_status(&head.beacon_block_root),
+        "the parent execution status should be retained from fork choice"
+    );
+
+    // No catchup is required if the parent state has already been advanced to the block's slot.
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(1))
+        .await;
+    let advanced_parent = || {
+        let mut parent = PreProcessingSnapshot::from(head.as_ref().clone());
+        per_slot_processing(
+            &mut parent.pre_state,
+            Some(head.beacon_block.state_root()),
+            &harness.spec,
+        )
+        .unwrap();
+        parent.beacon_state_root = None;
+        parent
+    };
+    let dry_run = verify(Arc::new(block), advanced_parent());
+    assert_eq!(dry_run.skipped_slots, 0);
+    assert!(dry_run.intermediate_state_roots.is_empty());
+
+    // The skipped slots are counted from the parent block, not from the advanced parent state.
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(3))
+        .await;
+    let dry_run = verify(Arc::new(block), advanced_parent());
+    assert_eq!(dry_run.skipped_slots, 2);
+    assert_eq!(dry_run.intermediate_state_roots.len(), 2);
+}
+
+#[tokio::test]
+async fn verify_block_with_relaxed_state_root_check() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let state = head.beacon_state.clone();
+    let (block, _) = harness.make_block(state.clone(), Slot::new(1)).await;
+    let block = with_state_root(&block, Hash256::repeat_byte(42), &state, &harness);
+    let block_root = block.canonical_root();
+
+    let parent = || PreProcessingSnapshot::from(head.as_ref().clone());
+    let consensus_context =
+        || ConsensusContext::new(Slot::new(1)).set_current_block_root(block_root);
+
+    assert!(matches!(
+        ExecutionPendingBlock::from_signature_verified_components(
+            block.clone(),
+            block_root,
+            parent(),
+            consensus_context(),
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+            None,
+        ),
+        Err(BlockError::StateRootMismatch { block, .. }) if block == Hash256::repeat_byte(42)
+    ));
+
+    // The mismatch is only logged by the dedicated entry point.
+    let execution_pending =
+        ExecutionPendingBlock::from_signature_verified_components_unchecked_state_root(
+            block.clone(),
+            block_root,
+            parent(),
+            consensus_context(),
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+        )
+        .unwrap();
+    assert_eq!(
+        harness
+            .chain
+            .clone()
+            .import_execution_pending_block(execution_pending)
+            .await
+            .unwrap(),
+        block_root
+    );
+    assert!(harness.chain.block_is_known_to_fork_choice(&block_root));
+}
+
+#[tokio::test]
+async fn verify_block_with_inconsistent_state_processing() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let state = head.beacon_state.clone();
+    let (valid_block, _) = harness.make_block(state.clone(), Slot::new(1)).await;
+    let invalid_block = with_state_root(&valid_block, Hash256::repeat_byte(42), &state, &harness);
+    let valid_block = Arc::new(valid_block);
+
+    let verify = |block: &Arc<SignedBeaconBlock<E>>, state_processing_strategy| {
+        let block_root = block.canonical_root();
+        ExecutionPendingBlock::from_signature_verified_components_with_strategy(
+            block.clone(),
+            block_root,
+            PreProcessingSnapshot::from(head.as_ref().clone()),
+            ConsensusContext::new(Slot::new(1)).set_current_block_root(block_root),
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+            state_processing_strategy,
+        )
+    };
+
+    // The state root is checked regardless of the strategy.
+    for strategy in [
+        StateProcessingStrategy::Accurate,
+        StateProcessingStrategy::Inconsistent,
+    ] {
+        assert!(matches!(
+            verify(&invalid_block, strategy),
+            Err(BlockError::StateRootMismatch { block, .. }) if block == Hash256::repeat_byte(42)
+        ));
+    }
+
+    // The skipped processing does not affect this block, so its state root matches.
+    let execution_pending = verify(&valid_block, StateProcessingStrategy::Inconsistent).unwrap();
+    assert_eq!(
+        execution_pending.state.canonical_root(),
+        valid_block.state_root()
+    );
+}
+
+#[tokio::test]
+async fn verify_block_with_deferred_fork_choice() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(2))
+        .await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+    assert!(!block.message().body().attestations().is_empty());
+
+    let execution_pending =
+        ExecutionPendingBlock::from_signature_verified_components_with_deferred_fork_choice(
+            block.clone(),
+            block_root,
+            PreProcessingSnapshot::from(head.as_ref().clone()),
+            ConsensusContext::new(block.slot()).set_current_block_root(block_root),
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+        )
+        .unwrap();
+
+    let deferred = execution_pending
+        .deferred_fork_choice_updates
+        .as_ref()
+        .expect("updates should be deferred");
+    assert_eq!(
+        deferred.indexed_attestations.len(),
+        block.message().body().attestations().len()
+    );
+    assert_eq!(
+        deferred.attester_slashings.len(),
+        block.message().body().attester_slashings().len()
+    );
+    deferred.apply(&harness.chain).unwrap();
+
+    assert_eq!(
+        harness
+            .chain
+            .clone()
+            .import_execution_pending_block(execution_pending)
+            .await
+            .unwrap(),
+        block_root
+    );
+}
+
+#[tokio::test]
+async fn verify_block_counts_fork_choice_updates() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(2))
+        .await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+    let num_attestations = block.message().body().attestations().len();
+    assert!(num_attestations > 0);
+
+    let execution_pending = block
+        .clone()
+        .into_execution_pending_block(block_root, &harness.chain, NotifyExecutionLayer::Yes)
+        .unwrap();
+
+    // Each of the attestations is valid and recent, so all should be applied.
+    assert_eq!(
+        execution_pending.fork_choice_attestations_applied,
+        num_attestations
+    );
+    assert_eq!(
+        execution_pending.attester_slashings_applied,
+        block.message().body().attester_slashings().len()
+    );
+}
+
+#[tokio::test]
+async fn verify_and_import_block() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let (block, _) = harness
+        .make_block(harness.get_current_state(), Slot::new(1))
+        .await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+
+    assert_eq!(
+        harness
+            .chain
+            .verify_and_import_block(block.clone(), NotifyExecutionLayer::Yes)
+            .await
+            .unwrap(),
+        block_root
+    );
+    assert!(harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&block_root));
+
+    // The block is fully imported, so it may become the head.
+    harness.chain.recompute_head_at_current_slot().await;
+    assert_eq!(harness.head_block_root(), block_root);
+
+    assert!(matches!(
+        harness
+            .chain
+            .verify_and_import_block(block, NotifyExecutionLayer::Yes)
+            .await,
+        Err(BlockError::BlockIsAlreadyKnown)
+    ));
+}
+
+#[tokio::test]
+async fn verify_gossip_block_header_only() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let (block, _) = harness
+        .make_block(harness.get_current_state(), Slot::new(1))
+        .await;
+    let header = block.signed_block_header();
+    let proposer_index = block.message().proposer_index();
+
+    // Verify twice, so that the proposer is computed from the parent state and then read from the
+    // proposer cache.
+    for _ in 0..2 {
+        assert_eq!(
+            harness
+                .chain
+                .verify_gossip_block_header_only(&header)
+                .unwrap(),
+            proposer_index
+        );
+    }
+
+    let mut wrong_proposer = header.clone();
+    wrong_proposer.message.proposer_index = (proposer_index + 1) % VALIDATOR_COUNT as u64;
+    assert!(matches!(
+        harness
+            .chain
+            .verify_gossip_block_header_only(&wrong_proposer),
+        Err(BlockError::IncorrectBlockProposer { block, local_shuffling })
+            if block == wrong_proposer.message.proposer_index && local_shuffling == proposer_index
+    ));
+
+    let mut bad_signature = header.clone();
+    bad_signature.signature = junk_signature();
+    assert!(matches!(
+        harness
+            .chain
+            .verify_gossip_block_header_only(&bad_signature),
+        Err(BlockError::ProposalSignatureInvalid)
+    ));
+
+    let mut unknown_parent = header;
+    unknown_parent.message.parent_root = Hash256::repeat_byte(42);
+    assert!(matches!(
+        harness
+            .chain
+            .verify_gossip_block_header_only(&unknown_parent),
+        Err(BlockError::BeaconChainError(
+            BeaconChainError::MissingBeaconBlock(_)
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn verify_block_retains_pre_block_state() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            retain_pre_block_state: true,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(1))
+        .await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+
+    let execution_pending = ExecutionPendingBlock::from_signature_verified_components(
+        block,
+        block_root,
+        PreProcessingSnapshot::from(head.as_ref().clone()),
+        ConsensusContext::new(Slot::new(1)).set_current_block_root(block_root),
+        &harness.chain,
+        NotifyExecutionLayer::Yes,
+        None,
+    )
+    .unwrap();
+
+    // The pre-block state has been advanced to the block's slot, but the block is not applied.
+    let pre_block_state = execution_pending.pre_block_state.unwrap();
+    assert_eq!(pre_block_state.slot(), Slot::new(1));
+    assert_ne!(
+        pre_block_state.latest_block_header().canonical_root(),
+        execution_pending
+            .state
+            .latest_block_header()
+            .canonical_root()
+    );
+}
+
+#[tokio::test]
+async fn compute_execution_pending_block_reward() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            retain_pre_block_state: true,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    // Produce some attestations for the next block to include.
+    harness
+        .extend_chain(
+            2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let slot = harness.get_current_slot();
+    let (block, _) = harness.make_block(head.beacon_state.clone(), slot).await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+    assert!(!block.message().body().attestations().is_empty());
+
+    // The reward which is always computed during a dry run.
+    let expected_reward =
+        SignatureVerifiedBlock::new_for_dry_run(block.clone(), block_root, &harness.chain)
+            .unwrap()
+            .into_dry_run_verified_block(&harness.chain, NotifyExecutionLayer::Yes)
+            .unwrap()
+            .block_reward;
+    assert!(expected_reward.total > 0);
+
+    let execution_pending = ExecutionPendingBlock::from_signature_verified_components(
+        block,
+        block_root,
+        PreProcessingSnapshot::from(head.as_ref().clone()),
+        ConsensusContext::new(slot).set_current_block_root(block_root),
+        &harness.chain,
+        NotifyExecutionLayer::Yes,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        harness
+            .chain
+            .compute_execution_pending_block_reward(&execution_pending)
+            .unwrap(),
+        expected_reward
+    );
+
+    // Without the pre-block state, it is reconstructed from the parent state.
+    let execution_pending = ExecutionPendingBlock {
+        pre_block_state: None,
+        ..execution_pending
+    };
+    assert_eq!(
+        harness
+            .chain
+            .compute_execution_pending_block_reward(&execution_pending)
+            .unwrap(),
+        expected_reward
+    );
+}
+
+#[tokio::test]
+async fn verify_block_with_mismatched_consensus_context() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            verify_consensus_context: true,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(1))
+        .await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+    let proposer_index = block.message().proposer_index();
+
+    let verify = |consensus_context| {
+        ExecutionPendingBlock::from_signature_verified_components(
+            block.clone(),
+            block_root,
+            PreProcessingSnapshot::from(head.as_ref().clone()),
+            consensus_context,
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+            None,
+        )
+    };
+
+    let mismatched_contexts = [
+        ConsensusContext::new(Slot::new(2)),
+        ConsensusContext::new(Slot::new(1)).set_current_block_root(Hash256::repeat_byte(42)),
+        ConsensusContext::new(Slot::new(1)).set_proposer_index(proposer_index + 1),
+    ];
+    for consensus_context in mismatched_contexts {
+        assert!(matches!(
+            verify(consensus_context),
+            Err(BlockError::BeaconChainError(
+                BeaconChainError::ConsensusContextMismatch { .. }
+            ))
+        ));
+    }
+
+    assert!(verify(
+        ConsensusContext::new(Slot::new(1))
+            .set_current_block_root(block_root)
+            .set_proposer_index(proposer_index)
+    )
+    .is_ok());
+}
+
+#[tokio::test]
+async fn verify_block_with_inline_payload_verification() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(1))
+        .await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+
+    let execution_pending = ExecutionPendingBlock::from_signature_verified_components_inline(
+        block,
+        block_root,
+        PreProcessingSnapshot::from(head.as_ref().clone()),
+        ConsensusContext::new(Slot::new(1)).set_current_block_root(block_root),
+        &harness.chain,
+        NotifyExecutionLayer::Yes,
+    )
+    .await
+    .unwrap();
+
+    // The payload verification is not spawned, it has already completed.
+    assert!(!execution_pending.imported_optimistically);
+    assert!(matches!(
+        execution_pending.payload_verification_handle,
+        PayloadVerificationHandle::Deferred(_)
+    ));
+    assert!(matches!(
+        execution_pending
+            .payload_verification_handle
+            .outcome()
+            .now_or_never(),
+        Some(Ok(_))
+    ));
+}
+
+#[tokio::test]
+async fn verify_block_reports_progress() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let gossip_verified = GossipVerifiedBlock::new(Arc::new(block), &harness.chain).unwrap();
+    let block_root = gossip_verified.block_root;
+
+    let stages = RefCell::new(vec![]);
+    gossip_verified
+        .into_execution_pending_block_with_progress(
+            block_root,
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+            &VerificationProgress::new(&|stage, _| stages.borrow_mut().push(stage)),
+        )
+        .unwrap();
+
+    assert_eq!(
+        stages.into_inner(),
+        vec![
+            VerificationStage::GossipOk,
+            VerificationStage::SignaturesOk,
+            VerificationStage::PayloadSubmitted,
+            VerificationStage::CatchupDone,
+            VerificationStage::PerBlockDone,
+            VerificationStage::StateRootOk,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn verify_block_with_report() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state.clone(), Slot::new(2)).await;
+    let block_root = block.canonical_root();
+
+    let invalid_block = with_state_root(&block, Hash256::repeat_byte(42), &state, &harness);
+
+    let (result, report) = harness.chain.verify_block_with_report(invalid_block).await;
+    assert!(matches!(result, Err(BlockError::StateRootMismatch { .. })));
+    assert!(report.per_block_processing.is_some());
+    assert!(report.state_root.is_none());
+    assert!(report.error.unwrap().starts_with("StateRootMismatch"));
+
+    let (result, report) = harness
+        .chain
+        .verify_block_with_report(Arc::new(block))
+        .await;
+    let execution_pending = result.unwrap();
+    assert_eq!(report.block_root, Some(block_root));
+    assert_eq!(report.slot, Slot::new(2));
+    assert!(report.relevancy.is_some());
+    assert!(report.parent_load.is_some());
+    assert!(report.parent_snapshot_cache_hit.is_some());
+    assert!(report.signatures.is_some());
+    assert_eq!(report.catchup_slots, Some(2));
+    assert!(report.catchup.is_some());
+    assert!(report.per_block_processing.is_some());
+    assert!(report.state_root.is_some());
+    assert!(report.payload.is_some());
+    assert!(report.payload_status.is_some());
+    assert_eq!(report.error, None);
+    assert!(serde_json::to_string(&report).is_ok());
+
+    // The verified block can still be imported.
+    assert_eq!(
+        harness
+            .chain
+            .clone()
+            .import_execution_pending_block(execution_pending)
+            .await
+            .unwrap(),
+        block_root
+    );
+}
+
+#[tokio::test]
+async fn verify_block_with_parent_from_db() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let db_harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            load_parent_from_db: true,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+    db_harness.advance_slot();
+    db_harness.advance_slot();
+
+    let (block, _) = harness
+        .make_block(harness.get_current_state(), Slot::new(2))
+        .await;
+    let block = Arc::new(block);
+
+    let (cache_result, cache_report) = harness.chain.verify_block_with_report(block.clone()).await;
+    let (db_result, db_report) = db_harness.chain.verify_block_with_report(block).await;
+    assert_eq!(cache_report.parent_snapshot_cache_hit, Some(true));
+    assert_eq!(db_report.parent_snapshot_cache_hit, Some(false));
+
+    // Both paths must produce the same post-state.
+    let cache_pending = cache_result.unwrap();
+    let db_pending = db_result.unwrap();
+    assert_eq!(
+        cache_pending.intermediate_state_roots,
+        db_pending.intermediate_state_roots
+    );
+    assert_eq!(
+        cache_pending.state.latest_block_header(),
+        db_pending.state.latest_block_header()
+    );
+}
+
+#[tokio::test]
+async fn verify_block_with_mismatched_parent_from_db() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            load_parent_from_db: true,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    harness
+        .extend_chain(
+            2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), harness.get_current_slot())
+        .await;
+
+    // Corrupt the database so that the head block root refers to the head's parent.
+    let grandparent_root = head.beacon_block.parent_root();
+    let grandparent = harness
+        .chain
+        .get_block(&grandparent_root)
+        .await
+        .unwrap()
+        .unwrap();
+    harness
+        .chain
+        .store
+        .put_block(&head.beacon_block_root, grandparent)
+        .unwrap();
+
+    let result = harness
+        .chain
+        .process_block(
+            block.canonical_root(),
+            Arc::new(block),
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await;
+    assert!(
+        matches!(
+            result,
+            Err(BlockError::BeaconChainError(
+                BeaconChainError::DBInconsistent(_)
+            ))
+        ),
+        "should not verify a block against the wrong parent, got {:?}",
+        result
+    );
+}
+
+#[tokio::test]
+async fn import_block_with_externally_verified_signatures() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let head = harness.chain.head_snapshot();
+    let (block, _) = harness
+        .make_block(head.beacon_state.clone(), Slot::new(1))
+        .await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+
+    // The signatures are valid, as attested by the context.
+    let consensus_context = || {
+        ConsensusContext::new(block.slot())
+            .set_current_block_root(block_root)
+            .set_proposer_index(block.message().proposer_index())
+    };
+
+    // The supplied parent must be the parent of the block.
+    let mut wrong_parent = PreProcessingSnapshot::from(head.as_ref().clone());
+    wrong_parent.beacon_block_root = Hash256::repeat_byte(42);
+    assert!(matches!(
+        SignatureVerifiedBlock::from_signatures_verified_externally(
+            block.clone(),
+            block_root,
+            wrong_parent,
+            consensus_context(),
+        ),
+        Err(BlockError::NonLinearParentRoots)
+    ));
+
+    let signature_verified = SignatureVerifiedBlock::from_signatures_verified_externally(
+        block.clone(),
+        block_root,
+        PreProcessingSnapshot::from(head.as_ref().clone()),
+        consensus_context(),
+    )
+    .unwrap();
+
+    let imported_root = harness
+        .chain
+        .process_block(
+            block_root,
+            signature_verified,
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(imported_root, block_root);
+
+    harness.chain.recompute_head_at_current_slot().await;
+    assert_eq!(harness.chain.head_snapshot().beacon_block_root, block_root);
+}
+
+#[tokio::test]
+async fn verify_proposal_signature_only_across_fork_boundary() {
+    let mut spec = MainnetEthSpec::default_spec();
+    let slots_per_epoch = MainnetEthSpec::slots_per_epoch();
+
+    // The Altair fork happens at epoch 1.
+    spec.altair_fork_epoch = Some(Epoch::new(1));
+
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec)
+        .keypairs(KEYPAIRS[..].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    // Build all the blocks in epoch 0 so that the head remains in the base fork.
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            slots_per_epoch as usize - 1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Produce the first Altair block.
+    harness.advance_slot();
+    let state = harness.get_current_state();
+    let slot = harness.get_current_slot();
+    let (block, _) = harness.make_block(state, slot).await;
+    assert!(block.as_altair().is_ok(), "test expects an altair block");
+
+    // Checking the signature against the fork of the head fails.
+    let head_fork = harness.chain.canonical_head.cached_head().head_fork();
+    let proposer_index = block.message().proposer_index() as usize;
+    assert_ne!(head_fork.current_version, harness.spec.altair_fork_version);
+    assert!(!block.signed_block_header().verify_signature::<E>(
+        &KEYPAIRS[proposer_index].pk,
+        &head_fork,
+        harness.chain.genesis_validators_root,
+        &harness.spec,
+    ));
 
-    let block = chain_segment[block_index].beacon_block.clone();
-    assert!(
-        harness.chain.verify_block_for_gossip(block).await.is_ok(),
-        "the valid block should be processed"
-    );
+    // Checking the signature against the fork of the advanced parent state succeeds.
+    let mut gossip_verified = harness
+        .chain
+        .verify_block_for_gossip(Arc::new(block))
+        .await
+        .unwrap();
+    assert!(gossip_verified
+        .verify_proposal_signature_only(&harness.chain)
+        .unwrap());
+}
 
-    /*
-     * This test ensures that:
-     *
-     * Spec v0.12.1
-     *
-     * The block is the first block with valid signature received for the proposer for the slot,
-     * signed_beacon_block.message.slot.
-     */
+#[tokio::test]
+async fn verify_block_with_spec_override() {
+    let mut spec = MainnetEthSpec::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(1));
 
-    let block = chain_segment[block_index].beacon_block.clone();
-    assert!(
-        matches!(
-            harness
-                .chain
-                .verify_block_for_gossip(block.clone())
-                .await
-                .err()
-                .expect("should error when processing known block"),
-            BlockError::BlockIsAlreadyKnown
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec.clone())
+        .keypairs(KEYPAIRS[..].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+    assert!(block.as_base().is_ok(), "test expects a base block");
+
+    // Under a schedule where Altair activates at genesis the base block has the wrong structure.
+    let mut altair_spec = spec;
+    altair_spec.altair_fork_epoch = Some(Epoch::new(0));
+
+    assert!(matches!(
+        GossipVerifiedBlock::new_with_spec_override(
+            block.clone(),
+            &harness.chain,
+            Some(&altair_spec)
         ),
-        "the second proposal by this validator should be rejected"
-    );
+        Err(BlockError::InconsistentFork(_))
+    ));
+    assert!(matches!(
+        SignatureVerifiedBlock::new_with_spec_override(
+            block.clone(),
+            block_root,
+            &harness.chain,
+            Some(&altair_spec)
+        ),
+        Err(BlockError::InconsistentFork(_))
+    ));
+
+    // Without an override the block is valid.
+    assert!(SignatureVerifiedBlock::new_with_spec_override(
+        block.clone(),
+        block_root,
+        &harness.chain,
+        None
+    )
+    .is_ok());
+    assert!(GossipVerifiedBlock::new_with_spec_override(block, &harness.chain, None).is_ok());
 }
 
 #[tokio::test]
-async fn verify_block_for_gossip_slashing_detection() {
+async fn verify_block_with_spec_override_has_no_side_effects() {
     let slasher_dir = tempdir().unwrap();
     let slasher = Arc::new(
         Slasher::open(SlasherConfig::new(slasher_dir.path().into()), test_logger()).unwrap(),
@@ -983,42 +3360,132 @@ async fn verify_block_for_gossip_slashing_detection() {
         .build();
     harness.advance_slot();
 
+    // A block from the next slot is from the future under `chain.spec`, but is valid under an
+    // override which tolerates a whole slot of clock disparity.
     let state = harness.get_current_state();
-    let (block1, _) = harness.make_block(state.clone(), Slot::new(1)).await;
-    let (block2, _) = harness.make_block(state, Slot::new(1)).await;
+    let (block, _) = harness.make_block(state, Slot::new(2)).await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
 
-    let verified_block = harness
-        .chain
-        .verify_block_for_gossip(Arc::new(block1))
-        .await
-        .unwrap();
-    harness
+    let mut spec_override = harness.spec.clone();
+    spec_override.maximum_gossip_clock_disparity_millis = spec_override.seconds_per_slot * 1_000;
+
+    let gossip_verified = GossipVerifiedBlock::new_with_spec_override(
+        block.clone(),
+        &harness.chain,
+        Some(&spec_override),
+    )
+    .unwrap();
+    assert_eq!(gossip_verified.block_root, block_root);
+    assert!(SignatureVerifiedBlock::new_with_spec_override(
+        block.clone(),
+        block_root,
+        &harness.chain,
+        Some(&spec_override)
+    )
+    .is_ok());
+
+    // The proposal was neither observed nor supplied to the slasher.
+    assert!(!harness
         .chain
-        .process_block(
-            verified_block.block_root,
-            verified_block,
-            NotifyExecutionLayer::Yes,
-            || Ok(()),
-        )
-        .await
-        .unwrap();
-    unwrap_err(
-        harness
-            .chain
-            .verify_block_for_gossip(Arc::new(block2))
-            .await,
-    );
+        .observed_block_producers
+        .read()
+        .proposer_has_been_observed(block.message(), block_root)
+        .unwrap()
+        .proposer_previously_observed());
+    let stats = slasher.process_queued(Epoch::new(0)).unwrap();
+    assert_eq!(stats.block_stats.num_processed, 0);
+
+    // Under `chain.spec` the block is from the future.
+    assert!(matches!(
+        GossipVerifiedBlock::new_with_spec_override(block.clone(), &harness.chain, None),
+        Err(BlockError::FutureSlot { .. })
+    ));
+
+    // The parent was left in the snapshot cache.
+    harness.advance_slot();
+    let (result, report) = harness.chain.verify_block_with_report(block).await;
+    assert!(result.is_ok());
+    assert_eq!(report.parent_snapshot_cache_hit, Some(true));
 
-    // Slasher should have been handed the two conflicting blocks and crafted a slashing.
-    slasher.process_queued(Epoch::new(0)).unwrap();
-    let proposer_slashings = slasher.get_proposer_slashings();
-    assert_eq!(proposer_slashings.len(), 1);
-    // windows won't delete the temporary directory if you don't do this..
     drop(harness);
     drop(slasher);
     slasher_dir.close().unwrap();
 }
 
+#[tokio::test]
+async fn gossip_verify_block_assuming_valid_signature() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let (block, _) = block.deconstruct();
+    let block = Arc::new(SignedBeaconBlock::from_block(block, junk_signature()));
+    let block_root = block.canonical_root();
+
+    // The forged signature is detected by regular gossip verification.
+    assert!(matches!(
+        GossipVerifiedBlock::new(block.clone(), &harness.chain),
+        Err(BlockError::ProposalSignatureInvalid)
+    ));
+    assert!(!harness
+        .chain
+        .observed_block_producers
+        .read()
+        .proposer_has_been_observed(block.message(), block_root)
+        .unwrap()
+        .proposer_previously_observed());
+
+    // It is not checked when the signature is assumed to be valid.
+    let gossip_verified =
+        GossipVerifiedBlock::new_assume_signature_valid(block.clone(), &harness.chain).unwrap();
+    assert_eq!(gossip_verified.block_root, block_root);
+
+    // The proposal was recorded, so the block is now a duplicate.
+    assert!(harness
+        .chain
+        .observed_block_producers
+        .read()
+        .proposer_has_been_observed(block.message(), block_root)
+        .unwrap()
+        .proposer_previously_observed());
+    assert!(matches!(
+        GossipVerifiedBlock::new_assume_signature_valid(block, &harness.chain),
+        Err(BlockError::BlockIsAlreadyKnown)
+    ));
+}
+
+#[tokio::test]
+async fn expected_proposer_for_block() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness.advance_slot();
+
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    let proposer_index = block.message().proposer_index();
+
+    // Claim a different proposer, the expected proposer is derived from the shuffling.
+    let (mut block, signature) = block.deconstruct();
+    *block.proposer_index_mut() = (proposer_index + 1) % VALIDATOR_COUNT as u64;
+    let block = SignedBeaconBlock::from_block(block, signature);
+
+    assert_eq!(
+        harness.chain.expected_proposer_for_block(&block).unwrap(),
+        proposer_index
+    );
+
+    // The parent must be known.
+    let (mut orphan, signature) = block.deconstruct();
+    *orphan.parent_root_mut() = Hash256::repeat_byte(42);
+    assert!(matches!(
+        harness
+            .chain
+            .expected_proposer_for_block(&SignedBeaconBlock::from_block(orphan, signature)),
+        Err(BlockError::ParentUnknown(_))
+    ));
+}
+
 #[tokio::test]
 async fn verify_block_for_gossip_doppelganger_detection() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -1165,18 +3632,24 @@ async fn add_base_block_to_altair_chain() {
     }
 
     // Ensure that it would be impossible to verify this block for gossip.
+    let gossip_error = harness
+        .chain
+        .verify_block_for_gossip(Arc::new(base_block.clone()))
+        .await
+        .err()
+        .expect("should error when processing base block");
     assert!(matches!(
-        harness
-            .chain
-            .verify_block_for_gossip(Arc::new(base_block.clone()))
-            .await
-            .err()
-            .expect("should error when processing base block"),
+        gossip_error,
         BlockError::InconsistentFork(InconsistentFork {
             fork_at_slot: ForkName::Altair,
             object_fork: ForkName::Base,
         })
     ));
+    assert_eq!(
+        gossip_error.to_string(),
+        "InconsistentFork(block structure is for fork phase0 but the spec expects fork altair at \
+         the block's slot)"
+    );
 
     // Ensure that it would be impossible to import via `BeaconChain::process_block`.
     assert!(matches!(
@@ -1444,3 +3917,207 @@ async fn import_duplicate_block_unrealized_justification() {
         Some(unrealized_justification)
     );
 }
+
+#[tokio::test]
+async fn payload_verification_permits_are_released() {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    // With a single permit, each block can only be verified once the permit of the previous block
+    // has been released.
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec)
+        .chain_config(ChainConfig {
+            max_concurrent_payload_verifications: 1,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness
+        .execution_block_generator()
+        .move_to_terminal_block()
+        .unwrap();
+    harness.advance_slot();
+
+    let num_blocks = E::slots_per_epoch() as usize;
+    harness
+        .extend_chain(
+            num_blocks,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let head = harness.chain.head_snapshot();
+    assert_eq!(head.beacon_block.slot(), Slot::new(num_blocks as u64));
+    assert_ne!(
+        head.beacon_block
+            .message()
+            .execution_payload()
+            .unwrap()
+            .block_hash(),
+        ExecutionBlockHash::zero(),
+        "blocks should have been verified by the execution layer"
+    );
+}
+
+#[tokio::test]
+async fn import_post_merge_block_with_blinded_parent() {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec)
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness
+        .execution_block_generator()
+        .move_to_terminal_block()
+        .unwrap();
+    harness.advance_slot();
+
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Choose a parent that is old enough to have been evicted from the snapshot cache, so that it
+    // must be loaded from the database in its blinded form.
+    let chain_dump = harness.chain.chain_dump().unwrap();
+    let parent = &chain_dump[chain_dump.len() - 8];
+    let parent_payload_hash = parent
+        .beacon_block
+        .message()
+        .execution_payload()
+        .unwrap()
+        .block_hash();
+    assert_ne!(
+        parent_payload_hash,
+        ExecutionBlockHash::zero(),
+        "parent should be post-merge"
+    );
+
+    // Build a fork block atop the blinded parent.
+    harness.advance_slot();
+    let slot = harness.get_current_slot();
+    let (block, _) = harness.make_block(parent.beacon_state.clone(), slot).await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+    assert_eq!(block.parent_root(), parent.beacon_block_root);
+
+    let import_result = harness
+        .chain
+        .process_block(block_root, block, NotifyExecutionLayer::Yes, || Ok(()))
+        .await;
+    assert!(
+        matches!(import_result, Ok(root) if root == block_root),
+        "should import a post-merge block with a blinded parent, got: {:?}",
+        import_result
+    );
+}
+
+#[tokio::test]
+async fn known_payload_statuses_skip_execution_layer() {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec)
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness
+        .execution_block_generator()
+        .move_to_terminal_block()
+        .unwrap();
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    harness.advance_slot();
+    let head = harness.chain.head_snapshot();
+    let slot = harness.get_current_slot();
+    let (block, _) = harness.make_block(head.beacon_state.clone(), slot).await;
+    let block = Arc::new(block);
+    let block_root = block.canonical_root();
+    let block_hash = block.message().execution_payload().unwrap().block_hash();
+
+    // The EL rejects every payload, so any payload which reaches it fails verification.
+    harness
+        .mock_execution_layer
+        .as_ref()
+        .unwrap()
+        .server
+        .all_payloads_invalid_on_new_payload(ExecutionBlockHash::zero());
+
+    let verify = |known_payload_statuses: Option<&KnownPayloadStatuses>| {
+        ExecutionPendingBlock::from_signature_verified_components(
+            block.clone(),
+            block_root,
+            PreProcessingSnapshot::from(head.as_ref().clone()),
+            ConsensusContext::new(slot).set_current_block_root(block_root),
+            &harness.chain,
+            NotifyExecutionLayer::Yes,
+            known_payload_statuses,
+        )
+    };
+
+    // Without a known status the payload is sent to the EL.
+    assert!(matches!(
+        verify(None).unwrap().resolve_payload_verification().await,
+        Err(BlockError::ExecutionPayloadError(
+            ExecutionPayloadError::RejectedByExecutionEngine { .. }
+        ))
+    ));
+
+    // A known status is used instead of asking the EL.
+    let known_payload_statuses =
+        KnownPayloadStatuses::from([(block_hash, PayloadVerificationStatus::Verified)]);
+    let execution_pending = verify(Some(&known_payload_statuses))
+        .unwrap()
+        .resolve_payload_verification()
+        .await
+        .unwrap();
+    assert!(!execution_pending.imported_optimistically);
+
+    // Statuses for other payloads are ignored.
+    let unrelated_payload_statuses = KnownPayloadStatuses::from([(
+        ExecutionBlockHash::repeat_byte(42),
+        PayloadVerificationStatus::Verified,
+    )]);
+    assert!(matches!(
+        verify(Some(&unrelated_payload_statuses))
+            .unwrap()
+            .resolve_payload_verification()
+            .await,
+        Err(BlockError::ExecutionPayloadError(
+            ExecutionPayloadError::RejectedByExecutionEngine { .. }
+        ))
+    ));
+
+    // A post-merge payload cannot be irrelevant, so such a status is rejected.
+    let irrelevant_payload_statuses =
+        KnownPayloadStatuses::from([(block_hash, PayloadVerificationStatus::Irrelevant)]);
+    assert!(matches!(
+        verify(Some(&irrelevant_payload_statuses)),
+        Err(BlockError::ExecutionPayloadError(
+            ExecutionPayloadError::IrrelevantKnownPayloadStatus { block_hash: hash }
+        )) if hash == block_hash
+    ));
+}
@@ -1,7 +1,9 @@
 #![cfg(not(debug_assertions))] // Tests run too slow in debug.
 
 use beacon_chain::test_utils::BeaconChainHarness;
+use beacon_chain::{BlockError, ExecutionPayloadError, NotifyExecutionLayer};
 use execution_layer::test_utils::{generate_pow_block, Block, DEFAULT_TERMINAL_BLOCK};
+use std::sync::Arc;
 use types::*;
 
 const VALIDATOR_COUNT: usize = 32;
@@ -220,3 +222,63 @@ async fn base_altair_merge_with_terminal_block_after_fork() {
 
     verify_execution_payload_chain(execution_payloads.as_slice());
 }
+
+#[tokio::test]
+async fn merge_transition_block_with_unreachable_execution_layer() {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    let harness = BeaconChainHarness::builder(E::default())
+        .spec(spec.clone())
+        .logger(logging::test_logger())
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    // Produce the merge transition block with an execution layer which is online.
+    harness
+        .execution_block_generator()
+        .move_to_terminal_block()
+        .unwrap();
+    harness.advance_slot();
+    let slot = harness.get_current_slot();
+    let (block, _) = harness.make_block(harness.get_current_state(), slot).await;
+    assert!(!block
+        .message()
+        .body()
+        .execution_payload()
+        .unwrap()
+        .is_default_with_empty_roots());
+
+    // Nothing is listening on this port, so the execution layer is unreachable.
+    let offline_harness = BeaconChainHarness::builder(E::default())
+        .spec(spec)
+        .logger(logging::test_logger())
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .fresh_ephemeral_store()
+        .execution_layer(&["http://127.0.0.1:1"])
+        .build();
+    offline_harness.set_current_slot(slot);
+
+    let block = Arc::new(block);
+    let result = offline_harness
+        .chain
+        .process_block(
+            block.canonical_root(),
+            block,
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await;
+    assert!(
+        matches!(
+            result,
+            Err(BlockError::ExecutionPayloadError(
+                ExecutionPayloadError::MergeTransitionExecutionLayerOffline
+            ))
+        ),
+        "{result:?}"
+    );
+}
@@ -7,8 +7,8 @@ use beacon_chain::otb_verification_service::{
 use beacon_chain::{
     canonical_head::{CachedHead, CanonicalHead},
     test_utils::{BeaconChainHarness, EphemeralHarnessType},
-    BeaconChainError, BlockError, ChainConfig, ExecutionPayloadError, NotifyExecutionLayer,
-    OverrideForkchoiceUpdate, StateSkipConfig, WhenSlotSkipped,
+    BeaconChainError, BlockError, ChainConfig, ExecutionPayloadError, IntoExecutionPendingBlock,
+    NotifyExecutionLayer, OverrideForkchoiceUpdate, StateSkipConfig, WhenSlotSkipped,
     INVALID_FINALIZED_MERGE_TRANSITION_BLOCK_SHUTDOWN_REASON,
     INVALID_JUSTIFIED_PAYLOAD_SHUTDOWN_REASON,
 };
@@ -411,6 +411,38 @@ async fn valid_invalid_syncing() {
     rig.import_block(Payload::Syncing).await;
 }
 
+/// Ensure that `INVALID` and `INVALID_BLOCK_HASH` responses are reported as distinct errors.
+#[tokio::test]
+async fn invalid_and_invalid_block_hash_are_distinct() {
+    let mut rig = InvalidPayloadRig::new();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await;
+
+    let invalid = Payload::Invalid {
+        latest_valid_hash: None,
+    };
+    rig.import_block_parametric(invalid, invalid, None, |error| {
+        matches!(
+            error,
+            BlockError::ExecutionPayloadError(
+                ExecutionPayloadError::RejectedByExecutionEngine { .. }
+            )
+        )
+    })
+    .await;
+
+    let invalid_block_hash = Payload::InvalidBlockHash;
+    rig.import_block_parametric(invalid_block_hash, invalid_block_hash, None, |error| {
+        matches!(
+            error,
+            BlockError::ExecutionPayloadError(
+                ExecutionPayloadError::BlockHashRejectedByExecutionEngine { .. }
+            )
+        )
+    })
+    .await;
+}
+
 /// Ensure that an invalid payload can invalidate its parent too (given the right
 /// `latest_valid_hash`.
 #[tokio::test]
@@ -959,6 +991,58 @@ async fn manually_validate_parent() {
     assert!(rig.execution_status(child).is_strictly_optimistic());
 }
 
+#[tokio::test]
+async fn optimistic_import_is_listed_until_validated() {
+    let mut rig = InvalidPayloadRig::new().enable_attestations();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+    assert!(rig.harness.chain.list_optimistic_blocks().is_empty());
+
+    let state = rig
+        .harness
+        .chain
+        .head_snapshot()
+        .beacon_state
+        .clone_with_only_committee_caches();
+    let slot = state.slot() + 1;
+    let (block, _) = rig.harness.make_block(state, slot).await;
+    let block_root = block.canonical_root();
+
+    let mock_execution_layer = rig.harness.mock_execution_layer.as_ref().unwrap();
+    mock_execution_layer
+        .server
+        .all_payloads_syncing_on_new_payload(true);
+    mock_execution_layer
+        .server
+        .all_payloads_syncing_on_forkchoice_updated();
+
+    rig.harness.set_current_slot(slot);
+    let execution_pending = Arc::new(block)
+        .into_execution_pending_block(block_root, &rig.harness.chain, NotifyExecutionLayer::Yes)
+        .unwrap();
+    assert!(!execution_pending.imported_optimistically);
+    let execution_pending = execution_pending
+        .resolve_payload_verification()
+        .await
+        .unwrap();
+    assert!(execution_pending.imported_optimistically);
+
+    rig.harness
+        .chain
+        .clone()
+        .import_execution_pending_block(execution_pending)
+        .await
+        .unwrap();
+    assert!(rig.execution_status(block_root).is_strictly_optimistic());
+    assert_eq!(rig.harness.chain.list_optimistic_blocks(), vec![block_root]);
+
+    rig.validate_manually(block_root);
+    assert!(rig
+        .execution_status(block_root)
+        .is_valid_and_post_bellatrix());
+    assert!(rig.harness.chain.list_optimistic_blocks().is_empty());
+}
+
 #[tokio::test]
 async fn payload_preparation() {
     let mut rig = InvalidPayloadRig::new();
@@ -10,7 +10,8 @@ use beacon_chain::validator_monitor::DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD;
 use beacon_chain::{
     historical_blocks::HistoricalBlockError, migrate::MigratorConfig, BeaconChain,
     BeaconChainError, BeaconChainTypes, BeaconSnapshot, BlockError, ChainConfig,
-    NotifyExecutionLayer, ServerSentEventHandler, WhenSlotSkipped,
+    GossipVerifiedBlock, IntoExecutionPendingBlock, NotifyExecutionLayer, ServerSentEventHandler,
+    SignatureVerifiedBlock, WhenSlotSkipped,
 };
 use lazy_static::lazy_static;
 use logging::test_logger;
@@ -578,6 +579,48 @@ async fn block_replayer_hooks() {
     assert_eq!(end_state, replay_state);
 }
 
+#[tokio::test]
+async fn reverify_block_root() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let head = harness.chain.head_snapshot();
+    assert_eq!(
+        harness
+            .chain
+            .reverify_block_root(&head.beacon_block_root)
+            .unwrap(),
+        Some(true)
+    );
+
+    // Store the head block under the wrong root to simulate corruption.
+    let corrupt_root = Hash256::repeat_byte(0xaa);
+    store
+        .put_block(&corrupt_root, head.beacon_block.as_ref().clone())
+        .unwrap();
+    assert_eq!(
+        harness.chain.reverify_block_root(&corrupt_root).unwrap(),
+        Some(false)
+    );
+
+    assert_eq!(
+        harness
+            .chain
+            .reverify_block_root(&Hash256::repeat_byte(0xbb))
+            .unwrap(),
+        None
+    );
+}
+
 #[tokio::test]
 async fn delete_blocks_and_states() {
     let db_path = tempdir().unwrap();
@@ -2038,6 +2081,85 @@ async fn garbage_collect_temp_states_from_failed_block() {
     assert_eq!(store.iter_temporary_state_roots().count(), 0);
 }
 
+#[tokio::test]
+async fn garbage_collect_temp_states_from_block_aborted_by_shutdown() {
+    let db_path = tempdir().unwrap();
+    let mut spec = test_spec::<E>();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    // Wrap these functions to ensure the variables are dropped before we try to open another
+    // instance of the store.
+    let mut store = {
+        let store = get_store_with_spec(&db_path, spec.clone());
+        let harness = BeaconChainHarness::builder(MinimalEthSpec)
+            .spec(spec)
+            .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+            .logger(store.logger().clone())
+            .fresh_disk_store(store.clone())
+            .mock_execution_layer()
+            .build();
+        harness
+            .execution_block_generator()
+            .move_to_terminal_block()
+            .unwrap();
+
+        // The block's payload must be sent to the execution layer, so its verification cannot
+        // complete before the shutdown is noticed.
+        let genesis_state = harness.get_current_state();
+        let block_slot = Slot::new(E::slots_per_epoch());
+        let (signed_block, _) = harness.make_block(genesis_state, block_slot).await;
+        let signed_block = Arc::new(signed_block);
+        let block_root = signed_block.canonical_root();
+        harness.set_current_slot(block_slot);
+
+        // Shut down the executor, as happens if the node is stopped whilst the block is being
+        // verified.
+        let chain = harness.chain.clone();
+        drop(harness.runtime);
+
+        // The block is valid, so verification should store a bunch of temporary states before
+        // the payload verification is aborted.
+        let execution_pending = signed_block
+            .into_execution_pending_block(block_root, &chain, NotifyExecutionLayer::Yes)
+            .unwrap();
+        assert!(!execution_pending.confirmed_state_roots.is_empty());
+        assert_eq!(
+            store.iter_temporary_state_roots().count(),
+            execution_pending.confirmed_state_roots.len()
+        );
+        assert!(matches!(
+            execution_pending
+                .payload_verification_handle
+                .outcome()
+                .await,
+            Err(BlockError::BeaconChainError(
+                BeaconChainError::RuntimeShutdown
+            ))
+        ));
+
+        // The block cannot be imported, so the temporary states are left in the database.
+        assert!(!chain.block_is_known_to_fork_choice(&block_root));
+        assert_ne!(store.iter_temporary_state_roots().count(), 0);
+        store
+    };
+
+    // Wait until all the references to the store have been dropped, this helps ensure we can
+    // re-open the store later.
+    loop {
+        store = if let Err(store_arc) = Arc::try_unwrap(store) {
+            sleep(Duration::from_millis(500)).await;
+            store_arc
+        } else {
+            break;
+        }
+    }
+
+    // On startup, the store should garbage collect all the temporary states.
+    let store = get_store(&db_path);
+    assert_eq!(store.iter_temporary_state_roots().count(), 0);
+}
+
 #[tokio::test]
 async fn weak_subjectivity_sync_easy() {
     let num_initial_slots = E::slots_per_epoch() * 11;
@@ -2201,6 +2323,35 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
         assert_eq!(state.update_tree_hash_cache().unwrap(), state_root);
     }
 
+    // Blocks at or below the anchor slot conflict with the checkpoint, whether they arrive via
+    // gossip or via sync.
+    let anchor_blocks = chain_dump
+        .iter()
+        .filter(|snapshot| {
+            let slot = snapshot.beacon_block.slot();
+            slot != 0 && slot <= wss_block.slot()
+        })
+        .collect::<Vec<_>>();
+    assert!(!anchor_blocks.is_empty());
+    for snapshot in anchor_blocks {
+        let full_block = Arc::new(
+            harness
+                .chain
+                .get_block(&snapshot.beacon_block_root)
+                .await
+                .unwrap()
+                .unwrap(),
+        );
+        assert!(matches!(
+            GossipVerifiedBlock::new(full_block.clone(), &beacon_chain),
+            Err(BlockError::WeakSubjectivityConflict)
+        ));
+        assert!(matches!(
+            SignatureVerifiedBlock::new(full_block, snapshot.beacon_block_root, &beacon_chain),
+            Err(BlockError::WeakSubjectivityConflict)
+        ));
+    }
+
     // Forwards iterator from 0 should fail as we lack blocks.
     assert!(matches!(
         beacon_chain.forwards_iter_block_roots(Slot::new(0)),
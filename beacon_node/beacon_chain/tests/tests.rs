@@ -2,6 +2,7 @@
 
 use beacon_chain::{
     attestation_verification::Error as AttnError,
+    beacon_proposer_cache::compute_proposer_duties_from_head,
     test_utils::{
         AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
         OP_POOL_DB_KEY,
@@ -872,3 +873,61 @@ async fn block_roots_skip_slot_behaviour() {
         "WhenSlotSkipped::Prev should return None on a future slot"
     );
 }
+
+#[tokio::test]
+async fn prime_proposer_shuffling() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let slots_per_epoch = MinimalEthSpec::slots_per_epoch();
+
+    // Finish the first epoch so that the head is the decision block for the next epoch.
+    let decision_block = harness
+        .extend_chain(
+            slots_per_epoch as usize - 1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    let next_epoch = harness
+        .chain
+        .head_snapshot()
+        .beacon_block
+        .slot()
+        .epoch(slots_per_epoch)
+        + 1;
+
+    assert!(
+        !harness
+            .chain
+            .prime_proposer_shuffling(Hash256::repeat_byte(42), next_epoch)
+            .unwrap(),
+        "should not prime the cache for an unknown block"
+    );
+
+    assert!(
+        harness
+            .chain
+            .prime_proposer_shuffling(decision_block, next_epoch)
+            .unwrap(),
+        "should prime the cache for a known decision block"
+    );
+    assert!(
+        !harness
+            .chain
+            .prime_proposer_shuffling(decision_block, next_epoch)
+            .unwrap(),
+        "should not prime the cache twice"
+    );
+
+    let (expected_proposers, expected_decision_block, _, _) =
+        compute_proposer_duties_from_head(next_epoch, &harness.chain).unwrap();
+    assert_eq!(expected_decision_block, decision_block);
+    assert_eq!(
+        harness
+            .chain
+            .beacon_proposer_cache
+            .lock()
+            .get_epoch::<MinimalEthSpec>(decision_block, next_epoch)
+            .map(|proposers| proposers.to_vec()),
+        Some(expected_proposers)
+    );
+}
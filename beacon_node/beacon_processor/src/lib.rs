@@ -444,6 +444,7 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
             },
             ReadyWork::RpcBlock(QueuedRpcBlock {
                 beacon_block_root: _,
+                retries: _,
                 process_fn,
                 ignore_fn: _,
             }) => Self {
@@ -141,6 +141,9 @@ pub struct QueuedGossipBlock {
 /// It is queued for later import.
 pub struct QueuedRpcBlock {
     pub beacon_block_root: Hash256,
+    /// The number of times this block has been requeued whilst the same block was being imported
+    /// from another source.
+    pub retries: usize,
     /// Processes/imports the block.
     pub process_fn: AsyncFn,
     /// Ignores the block.
@@ -119,6 +119,19 @@ pub enum EngineError {
     Auth,
 }
 
+impl EngineError {
+    /// Returns `true` if the engine could not be reached, rather than responding with an error.
+    pub fn is_offline(&self) -> bool {
+        match self {
+            EngineError::Offline => true,
+            EngineError::Api {
+                error: EngineApiError::HttpClient(e),
+            } => e.inner().is_connect(),
+            EngineError::Api { .. } | EngineError::BuilderApi { .. } | EngineError::Auth => false,
+        }
+    }
+}
+
 /// An execution engine.
 pub struct Engine {
     pub api: HttpJsonRpc,
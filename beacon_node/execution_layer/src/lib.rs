@@ -539,6 +539,15 @@ impl<T: EthSpec> ExecutionLayer<T> {
         synced
     }
 
+    /// Returns `true` if the execution engine was offline at the time of the last request or
+    /// upcheck.
+    ///
+    /// The state is refreshed by the watchdog routine, so this will return `false` once the engine
+    /// recovers even if no other requests are made in the meantime.
+    pub async fn is_offline(&self) -> bool {
+        self.engine().is_offline().await
+    }
+
     /// Return `true` if the execution layer is offline or returning errors on `newPayload`.
     ///
     /// This function should never be used to prevent any operation in the beacon node, but can
@@ -4412,6 +4412,15 @@ pub fn serve<T: BeaconChainTypes>(
                                 api_types::EventTopic::LateHead => {
                                     event_handler.subscribe_late_head()
                                 }
+                                api_types::EventTopic::BlockEquivocation => {
+                                    event_handler.subscribe_block_equivocation()
+                                }
+                                api_types::EventTopic::OptimisticBlockRejected => {
+                                    event_handler.subscribe_optimistic_block_rejected()
+                                }
+                                api_types::EventTopic::DelayedEquivocatingBlock => {
+                                    event_handler.subscribe_delayed_equivocating_block()
+                                }
                                 api_types::EventTopic::BlockReward => {
                                     event_handler.subscribe_block_reward()
                                 }
@@ -79,7 +79,7 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
     /* if we can form a `GossipVerifiedBlock`, we've passed our basic gossip checks */
     let gossip_verified_block = match block.into_gossip_verified_block(&chain) {
         Ok(b) => b,
-        Err(BlockError::BlockIsAlreadyKnown) => {
+        Err(BlockError::BlockIsAlreadyKnown | BlockError::BlockAtFinalizedSlot { .. }) => {
             // Allow the status code for duplicate blocks to be overridden based on config.
             return Ok(warp::reply::with_status(
                 warp::reply::json(&ErrorMessage {
@@ -98,7 +98,7 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
                 "slot" => beacon_block.slot(),
                 "error" => ?e
             );
-            return Err(warp_utils::reject::custom_bad_request(e.to_string()));
+            return response_for_block_error(&e, e.to_string());
         }
     };
 
@@ -209,14 +209,34 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
                     "Invalid block provided to HTTP API";
                     "reason" => &msg
                 );
-                Err(warp_utils::reject::custom_bad_request(format!(
-                    "Invalid block: {e}"
-                )))
+                response_for_block_error(&e, format!("Invalid block: {e}"))
             }
         }
     }
 }
 
+/// Returns the response to the submitter of a block which failed verification with `e`.
+///
+/// The status code is given by `BlockRejectionClass::http_status_hint`.
+fn response_for_block_error<E: EthSpec>(
+    e: &BlockError<E>,
+    msg: String,
+) -> Result<Response, Rejection> {
+    match StatusCode::from_u16(e.rejection_class().http_status_hint()) {
+        Ok(StatusCode::BAD_REQUEST) => Err(warp_utils::reject::custom_bad_request(msg)),
+        Ok(status) if status.is_success() => Ok(warp::reply::with_status(
+            warp::reply::json(&ErrorMessage {
+                code: status.as_u16(),
+                message: msg,
+                stacktraces: vec![],
+            }),
+            status,
+        )
+        .into_response()),
+        _ => Err(warp_utils::reject::custom_server_error(msg)),
+    }
+}
+
 /// Handles a request from the HTTP API for blinded blocks. This converts blinded blocks into full
 /// blocks before publishing.
 pub async fn publish_blinded_block<T: BeaconChainTypes>(
@@ -378,3 +398,53 @@ fn late_block_logging<T: BeaconChainTypes, P: AbstractExecPayload<T::EthSpec>>(
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::BlockRejectionClass;
+    use types::MainnetEthSpec;
+
+    async fn status_for(e: BlockError<MainnetEthSpec>) -> StatusCode {
+        match response_for_block_error(&e, e.to_string()) {
+            Ok(response) => response.status(),
+            Err(rejection) => warp_utils::reject::handle_rejection(rejection)
+                .await
+                .unwrap()
+                .into_response()
+                .status(),
+        }
+    }
+
+    #[tokio::test]
+    async fn block_error_status_follows_rejection_class() {
+        let cases = vec![
+            (
+                BlockError::ProposalSignatureInvalid,
+                BlockRejectionClass::Invalid,
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                BlockError::GenesisBlock,
+                BlockRejectionClass::NotRelevant,
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                BlockError::BlockIsAlreadyKnown,
+                BlockRejectionClass::AlreadyKnown,
+                StatusCode::ACCEPTED,
+            ),
+            (
+                BlockError::BeaconChainError(BeaconChainError::UnableToPublish),
+                BlockRejectionClass::Internal,
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+
+        for (e, class, status) in cases {
+            assert_eq!(e.rejection_class(), class);
+            assert_eq!(class.http_status_hint(), status.as_u16());
+            assert_eq!(status_for(e).await, status);
+        }
+    }
+}
@@ -30,6 +30,10 @@ use types::{ForkContext, ForkName};
 /// another 500ms for "fudge factor".
 pub const DUPLICATE_CACHE_TIME: Duration = Duration::from_secs(33 * 12 + 1);
 
+/// The default number of times an RPC block will be requeued whilst the same block is being
+/// imported from another source, before it is ignored.
+pub const DEFAULT_MAX_RPC_BLOCK_REQUEUES: usize = 3;
+
 /// The maximum size of gossip messages.
 pub fn gossip_max_size(is_merge_enabled: bool, gossip_max_size: usize) -> usize {
     if is_merge_enabled {
@@ -150,6 +154,10 @@ pub struct Config {
     /// Configures if/where invalid blocks should be stored.
     pub invalid_block_storage: Option<PathBuf>,
 
+    /// The number of times an RPC block will be requeued whilst the same block is being imported
+    /// from another source, before it is ignored.
+    pub max_rpc_block_requeues: usize,
+
     /// Configuration for the inbound rate limiter (requests received by this node).
     pub inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
 }
@@ -368,6 +376,7 @@ impl Default for Config {
             enable_light_client_server: false,
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
+            max_rpc_block_requeues: DEFAULT_MAX_RPC_BLOCK_REQUEUES,
             inbound_rate_limiter_config: None,
         }
     }
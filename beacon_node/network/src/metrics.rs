@@ -2,7 +2,7 @@ use beacon_chain::{
     attestation_verification::Error as AttnError,
     light_client_finality_update_verification::Error as LightClientFinalityUpdateError,
     light_client_optimistic_update_verification::Error as LightClientOptimisticUpdateError,
-    sync_committee_verification::Error as SyncCommitteeError,
+    sync_committee_verification::Error as SyncCommitteeError, BlockError,
 };
 use fnv::FnvHashMap;
 pub use lighthouse_metrics::*;
@@ -108,6 +108,24 @@ lazy_static! {
         "beacon_processor_rpc_block_imported_total",
         "Total number of gossip blocks imported to fork choice, etc."
     );
+    pub static ref BEACON_PROCESSOR_RPC_BLOCK_REQUEUES_EXHAUSTED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_rpc_block_requeues_exhausted_total",
+        "Total number of RPC blocks ignored after being requeued too many times whilst the same \
+        block was being imported from another source."
+    );
+    pub static ref BEACON_PROCESSOR_RPC_BLOCK_EQUIVOCATION_DELAYED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_rpc_block_equivocation_delayed_total",
+        "Total number of RPC blocks requeued to deny them proposer boost, since a different block \
+        from the same proposer and slot had already been observed."
+    );
+    pub static ref BEACON_PROCESSOR_RPC_BLOCK_REPROCESS_QUEUE_DROPPED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_rpc_block_reprocess_queue_dropped_total",
+        "Total number of messages about RPC blocks dropped because the reprocessing queue was full."
+    );
+    pub static ref BEACON_PROCESSOR_RPC_BLOCK_ALREADY_KNOWN_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_rpc_block_already_known_total",
+        "Total number of RPC blocks rejected without processing because they were already imported."
+    );
     // Chain segments.
     pub static ref BEACON_PROCESSOR_CHAIN_SEGMENT_SUCCESS_TOTAL: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_chain_segment_success_total",
@@ -121,6 +139,10 @@ lazy_static! {
         "beacon_processor_chain_segment_failed_total",
         "Total number of chain segments that failed processing."
     );
+    pub static ref BEACON_PROCESSOR_CHAIN_SEGMENT_EL_OFFLINE_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_chain_segment_el_offline_total",
+        "Total number of chain segments that were not processed because the execution layer was offline."
+    );
     pub static ref BEACON_PROCESSOR_BACKFILL_CHAIN_SEGMENT_FAILED_TOTAL: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_backfill_chain_segment_failed_total",
         "Total number of backfill chain segments that failed processing."
@@ -196,6 +218,18 @@ lazy_static! {
             "Gossipsub light_client_optimistic_update errors per error type",
             &["type"]
         );
+    pub static ref GOSSIP_BLOCK_ERRORS_PER_TYPE: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "gossipsub_block_errors_per_type",
+            "Gossipsub block errors per error type",
+            &["type"]
+        );
+    pub static ref BEACON_PROCESSOR_CHAIN_SEGMENT_ERRORS_PER_TYPE: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "beacon_processor_chain_segment_errors_per_type",
+            "Chain segment processing errors per error type",
+            &["type"]
+        );
 
 
     /*
@@ -316,6 +350,17 @@ pub fn register_sync_committee_error(error: &SyncCommitteeError) {
     inc_counter_vec(&GOSSIP_SYNC_COMMITTEE_ERRORS_PER_TYPE, &[error.as_ref()]);
 }
 
+pub fn register_gossip_block_error<E: EthSpec>(error: &BlockError<E>) {
+    inc_counter_vec(&GOSSIP_BLOCK_ERRORS_PER_TYPE, &[error.variant_name()]);
+}
+
+pub fn register_chain_segment_error<E: EthSpec>(error: &BlockError<E>) {
+    inc_counter_vec(
+        &BEACON_PROCESSOR_CHAIN_SEGMENT_ERRORS_PER_TYPE,
+        &[error.variant_name()],
+    );
+}
+
 pub fn update_gossip_metrics<T: EthSpec>(
     gossipsub: &Gossipsub,
     network_globals: &Arc<NetworkGlobals<T>>,
@@ -1,6 +1,8 @@
 use crate::{
     metrics,
-    network_beacon_processor::{InvalidBlockStorage, NetworkBeaconProcessor},
+    network_beacon_processor::{
+        sync_methods::peer_action_from_penalty, InvalidBlockStorage, NetworkBeaconProcessor,
+    },
     service::NetworkMessage,
     sync::SyncMessage,
 };
@@ -695,6 +697,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             Some(peer_client.to_string()),
         );
 
+        if let Err(e) = &verification_result {
+            metrics::register_gossip_block_error(e);
+        }
+
         let verified_block = match verification_result {
             Ok(verified_block) => {
                 if block_delay >= self.chain.slot_clock.unagg_attestation_production_delay() {
@@ -715,6 +721,14 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "slot" => verified_block.block.slot(),
                     "root" => ?verified_block.block_root
                 );
+                if verified_block.is_merge_transition_candidate() == Some(true) {
+                    info!(
+                        self.log,
+                        "Merge transition block received";
+                        "slot" => verified_block.block.slot(),
+                        "root" => ?verified_block.block_root,
+                    );
+                }
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Accept);
 
                 // Log metrics to keep track of propagation delay times.
@@ -754,7 +768,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 self.send_sync_message(SyncMessage::UnknownBlock(peer_id, block, block_root));
                 return None;
             }
-            Err(e @ BlockError::BeaconChainError(_)) => {
+            Err(e @ BlockError::BeaconChainError(_))
+            | Err(e @ BlockError::ParentPayloadUnavailable { .. })
+            | Err(e @ BlockError::SlotProcessingFailed { .. })
+            | Err(e @ BlockError::PreStateSlotTooHigh { .. }) => {
                 debug!(
                     self.log,
                     "Gossip block beacon chain error";
@@ -763,10 +780,66 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
                 return None;
             }
+            Err(e @ BlockError::ProposerFiltered { .. }) => {
+                debug!(
+                    self.log,
+                    "Gossip block refused by proposer filter";
+                    "error" => ?e,
+                );
+                // The block is refused due to local policy, so the peer is not penalized.
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+                return None;
+            }
+            Err(e @ BlockError::TooManySkipSlots { .. }) => {
+                debug!(
+                    self.log,
+                    "Rejected long skip slot block";
+                    "error" => ?e,
+                );
+                // The block is not necessarily invalid, but it's not worth the work to verify.
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+                return None;
+            }
+            Err(e @ BlockError::ProposerRateLimited { .. }) => {
+                debug!(
+                    self.log,
+                    "Gossip block exceeded proposer rate limit";
+                    "error" => ?e,
+                );
+                // The peer is forwarding spam, penalize them without rejecting the (possibly
+                // valid) block.
+                self.gossip_penalize_peer(
+                    peer_id,
+                    PeerAction::MidToleranceError,
+                    "gossip_block_mid",
+                );
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+                return None;
+            }
+            Err(e @ BlockError::NotFinalizedDescendant { .. }) => {
+                debug!(self.log, "Gossip block conflicts with finality. Ignoring the block";
+                            "error" => %e,
+                            "slot" => block.slot());
+                // A recent block may have been valid until finalization advanced, only penalize
+                // the peer for older blocks.
+                if let Some(penalty) = e.penalize_gossip_peer(
+                    block.slot(),
+                    self.chain.slot().unwrap_or_else(|_| block.slot()),
+                    self.chain.config.not_finalized_descendant_grace_slots,
+                ) {
+                    self.gossip_penalize_peer(
+                        peer_id,
+                        peer_action_from_penalty(penalty),
+                        "gossip_block_not_finalized_descendant",
+                    );
+                }
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+                return None;
+            }
             Err(e @ BlockError::FutureSlot { .. })
             | Err(e @ BlockError::WouldRevertFinalizedSlot { .. })
             | Err(e @ BlockError::BlockIsAlreadyKnown)
-            | Err(e @ BlockError::NotFinalizedDescendant { .. }) => {
+            | Err(e @ BlockError::BlockAtFinalizedSlot { .. }) => {
                 debug!(self.log, "Could not verify block for gossip. Ignoring the block";
                             "error" => %e);
                 // Prevent recurring behaviour by penalizing the peer slightly.
@@ -793,11 +866,12 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             | Err(e @ BlockError::PerBlockProcessingError(_))
             | Err(e @ BlockError::NonLinearParentRoots)
             | Err(e @ BlockError::BlockIsNotLaterThanParent { .. })
-            | Err(e @ BlockError::InvalidSignature)
+            | Err(e @ BlockError::InvalidSignature { .. })
             | Err(e @ BlockError::WeakSubjectivityConflict)
             | Err(e @ BlockError::InconsistentFork(_))
             | Err(e @ BlockError::ExecutionPayloadError(_))
             | Err(e @ BlockError::ParentExecutionPayloadInvalid { .. })
+            | Err(e @ BlockError::RecentlyRejected { .. })
             | Err(e @ BlockError::GenesisBlock) => {
                 warn!(self.log, "Could not verify block for gossip. Rejecting the block";
                             "error" => %e);
@@ -1,6 +1,6 @@
 use crate::{
     service::NetworkMessage,
-    sync::{manager::BlockProcessType, SyncMessage},
+    sync::{manager::BlockProcessType, BatchProcessResult, SyncMessage},
 };
 use beacon_chain::{
     builder::Witness, eth1_chain::CachingEth1Backend, test_utils::BeaconChainHarness, BeaconChain,
@@ -14,7 +14,7 @@ use beacon_processor::{
 use environment::null_logger;
 use lighthouse_network::{
     rpc::{BlocksByRangeRequest, BlocksByRootRequest, LightClientBootstrapRequest, StatusMessage},
-    Client, MessageId, NetworkGlobals, PeerId, PeerRequestId,
+    Client, MessageId, NetworkConfig, NetworkGlobals, PeerId, PeerRequestId,
 };
 use slog::{debug, Logger};
 use slot_clock::ManualSlotClock;
@@ -25,6 +25,8 @@ use store::MemoryStore;
 use task_executor::test_utils::TestRuntime;
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
 use types::*;
 
 pub use sync_methods::ChainSegmentProcessId;
@@ -57,6 +59,9 @@ pub struct NetworkBeaconProcessor<T: BeaconChainTypes> {
     pub reprocess_tx: mpsc::Sender<ReprocessQueueMessage>,
     pub network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     pub invalid_block_storage: InvalidBlockStorage,
+    /// The number of times an RPC block will be requeued whilst the same block is being imported
+    /// from another source, before it is ignored.
+    pub max_rpc_block_requeues: usize,
     pub executor: TaskExecutor,
     pub log: Logger,
 }
@@ -385,6 +390,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             block,
             seen_timestamp,
             process_type,
+            0,
         );
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
@@ -393,26 +399,26 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     }
 
     /// Create a new work event to import `blocks` as a beacon chain segment.
+    ///
+    /// If `cancellation_token` is cancelled, processing stops between blocks. This is only
+    /// supported for range sync batches.
     pub fn send_chain_segment(
         self: &Arc<Self>,
         process_id: ChainSegmentProcessId,
         blocks: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+        cancellation_token: Option<CancellationToken>,
     ) -> Result<(), Error<T::EthSpec>> {
         let is_backfill = matches!(&process_id, ChainSegmentProcessId::BackSyncBatchId { .. });
         let processor = self.clone();
         let process_fn = async move {
-            let notify_execution_layer = if processor
-                .network_globals
-                .sync_state
-                .read()
-                .is_syncing_finalized()
-            {
-                NotifyExecutionLayer::No
-            } else {
-                NotifyExecutionLayer::Yes
-            };
+            let notify_execution_layer = processor.chain_segment_notify_execution_layer();
             processor
-                .process_chain_segment(process_id, blocks, notify_execution_layer)
+                .process_chain_segment(
+                    process_id,
+                    blocks,
+                    notify_execution_layer,
+                    cancellation_token,
+                )
                 .await;
         };
         let process_fn = Box::pin(process_fn);
@@ -431,6 +437,47 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         })
     }
 
+    /// Create a new work event to import `blocks` on behalf of an administrative tool.
+    ///
+    /// The result of processing is sent to the returned receiver, rather than to the sync manager.
+    pub fn send_admin_import(
+        self: &Arc<Self>,
+        label: String,
+        blocks: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+    ) -> Result<oneshot::Receiver<BatchProcessResult>, Error<T::EthSpec>> {
+        let (result_tx, result_rx) = oneshot::channel();
+        let processor = self.clone();
+        let process_fn = async move {
+            let notify_execution_layer = processor.chain_segment_notify_execution_layer();
+            processor
+                .process_admin_import(label, blocks, notify_execution_layer, result_tx)
+                .await;
+        };
+
+        self.try_send(BeaconWorkEvent {
+            drop_during_sync: false,
+            work: Work::ChainSegment(Box::pin(process_fn)),
+        })?;
+
+        Ok(result_rx)
+    }
+
+    /// Returns whether the execution layer should be notified of the payloads in a chain segment.
+    ///
+    /// The EL is not notified whilst syncing finalized blocks.
+    fn chain_segment_notify_execution_layer(&self) -> NotifyExecutionLayer {
+        if self
+            .network_globals
+            .sync_state
+            .read()
+            .is_syncing_finalized()
+        {
+            NotifyExecutionLayer::No
+        } else {
+            NotifyExecutionLayer::Yes
+        }
+    }
+
     /// Create a new work event to process `StatusMessage`s from the RPC network.
     pub fn send_status_message(
         self: &Arc<Self>,
@@ -574,6 +621,7 @@ impl<E: EthSpec> NetworkBeaconProcessor<TestBeaconChainType<E>> {
             reprocess_tx: work_reprocessing_tx,
             network_globals,
             invalid_block_storage: InvalidBlockStorage::Disabled,
+            max_rpc_block_requeues: NetworkConfig::default().max_rpc_block_requeues,
             executor: runtime.task_executor.clone(),
             log,
         };
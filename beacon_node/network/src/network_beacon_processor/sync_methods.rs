@@ -8,24 +8,34 @@ use crate::sync::{
     ChainId,
 };
 use beacon_chain::{
-    observed_block_producers::Error as ObserveError, validator_monitor::get_block_delay_ms,
-    BeaconChainError, BeaconChainTypes, BlockError, ChainSegmentResult, HistoricalBlockError,
-    NotifyExecutionLayer,
+    events::{EventKind, SseDelayedEquivocatingBlock},
+    observed_block_producers::Error as ObserveError,
+    prepare_chain_segment,
+    validator_monitor::get_block_delay_ms,
+    BeaconChainError, BeaconChainTypes, BlockError, ChainSegmentBlockOutcome, ChainSegmentResult,
+    HistoricalBlockError, NotifyExecutionLayer, PeerPenalty,
 };
 use beacon_processor::{
     work_reprocessing_queue::{QueuedRpcBlock, ReprocessQueueMessage},
     AsyncFn, BlockingFn, DuplicateCache,
 };
 use lighthouse_network::PeerAction;
+use serde::{Deserialize, Serialize};
 use slog::{debug, error, info, warn};
 use slot_clock::SlotClock;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
-use tokio::sync::mpsc;
-use types::{Epoch, Hash256, SignedBeaconBlock};
+use tokio::sync::{mpsc, mpsc::error::TrySendError, oneshot};
+use tokio_util::sync::CancellationToken;
+use types::{Epoch, EthSpec, ExecPayload, ExecutionBlockHash, Hash256, SignedBeaconBlock, Slot};
+
+/// The maximum time to wait for space in a full reprocessing queue before dropping a message.
+const REPROCESS_QUEUE_SEND_TIMEOUT: Duration = Duration::from_millis(500);
 
 /// Id associated to a batch processing request, either a sync batch or a parent lookup.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Implements `Serialize` and `Deserialize` so that the in-flight batches may be persisted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ChainSegmentProcessId {
     /// Processing Id of a range syncing batch.
     RangeBatchId(ChainId, Epoch),
@@ -33,6 +43,12 @@ pub enum ChainSegmentProcessId {
     BackSyncBatchId(Epoch),
     /// Processing Id of the parent lookup of a block.
     ParentLookup(Hash256),
+    /// Processing Id of blocks imported by an administrative tool, rather than by sync. The
+    /// `label` is only used for logging.
+    ///
+    /// The result of processing is returned to the caller of
+    /// `NetworkBeaconProcessor::send_admin_import`, the sync manager is not informed.
+    AdminImport { label: String },
 }
 
 /// Returned when a chain segment import fails.
@@ -41,6 +57,55 @@ struct ChainSegmentFailed {
     message: String,
     /// Used to penalize peers.
     peer_action: Option<PeerAction>,
+    /// The root of the first block in the segment which is known to be invalid, if any.
+    failed_block_root: Option<Hash256>,
+}
+
+impl ChainSegmentFailed {
+    /// Returns the slot of the block identified by `self.failed_block_root`, if it is amongst
+    /// `blocks`.
+    fn failed_block_slot<E: EthSpec>(&self, blocks: &[Arc<SignedBeaconBlock<E>>]) -> Option<Slot> {
+        let failed_block_root = self.failed_block_root?;
+        blocks
+            .iter()
+            .find(|block| block.canonical_root() == failed_block_root)
+            .map(|block| block.slot())
+    }
+}
+
+/// Maps the outcome of a failed chain segment import to a `BatchProcessResult`.
+fn failed_batch_result(
+    imported_blocks: usize,
+    failed_block_root: Option<Hash256>,
+    failed_block_slot: Option<Slot>,
+    peer_action: Option<PeerAction>,
+) -> BatchProcessResult {
+    if imported_blocks > 0 {
+        BatchProcessResult::PartialSuccess {
+            imported_blocks,
+            failed_block_root,
+            failed_block_slot,
+            penalty: peer_action,
+        }
+    } else {
+        match peer_action {
+            Some(penalty) => BatchProcessResult::FaultyFailure {
+                failed_block_root,
+                penalty,
+            },
+            None => BatchProcessResult::NonFaultyFailure,
+        }
+    }
+}
+
+/// Maps the penalty chosen by `BlockError::penalize_peer` to a `PeerAction`.
+pub(super) fn peer_action_from_penalty(penalty: PeerPenalty) -> PeerAction {
+    match penalty {
+        PeerPenalty::Fatal => PeerAction::Fatal,
+        PeerPenalty::LowToleranceError => PeerAction::LowToleranceError,
+        PeerPenalty::MidToleranceError => PeerAction::MidToleranceError,
+        PeerPenalty::HighToleranceError => PeerAction::HighToleranceError,
+    }
 }
 
 impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
@@ -54,6 +119,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
         seen_timestamp: Duration,
         process_type: BlockProcessType,
+        retries: usize,
     ) -> AsyncFn {
         let process_fn = async move {
             let reprocess_tx = self.reprocess_tx.clone();
@@ -65,6 +131,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 process_type,
                 reprocess_tx,
                 duplicate_cache,
+                retries,
             )
             .await;
         };
@@ -78,6 +145,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
         seen_timestamp: Duration,
         process_type: BlockProcessType,
+        retries: usize,
     ) -> (AsyncFn, BlockingFn) {
         // An async closure which will import the block.
         let process_fn = self.clone().generate_rpc_beacon_block_process_fn(
@@ -85,6 +153,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             block,
             seen_timestamp,
             process_type.clone(),
+            retries,
         );
         // A closure which will ignore the block.
         let ignore_fn = move || {
@@ -98,6 +167,9 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     }
 
     /// Attempt to process a block received from a direct RPC request.
+    ///
+    /// `retries` is the number of times the block has already been requeued because the same
+    /// block was being imported from another source.
     #[allow(clippy::too_many_arguments)]
     pub async fn process_rpc_block(
         self: Arc<NetworkBeaconProcessor<T>>,
@@ -107,16 +179,57 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         process_type: BlockProcessType,
         reprocess_tx: mpsc::Sender<ReprocessQueueMessage>,
         duplicate_cache: DuplicateCache,
+        retries: usize,
     ) {
+        // Check if the block has already been imported, saving a trip through `process_block`.
+        //
+        // Only blocks in fork choice are considered. A block which is still being imported on
+        // another thread is not yet in fork choice and is handled by the duplicate cache below.
+        // This is the same condition that `process_block` uses to return `BlockIsAlreadyKnown`.
+        if self.chain.block_is_known_to_fork_choice(&block_root) {
+            debug!(
+                self.log,
+                "RPC block is already known";
+                "block_root" => %block_root,
+                "slot" => block.slot(),
+            );
+            metrics::inc_counter(&metrics::BEACON_PROCESSOR_RPC_BLOCK_ALREADY_KNOWN_TOTAL);
+            // Sync handles these results
+            self.send_sync_message(SyncMessage::BlockProcessed {
+                process_type,
+                result: crate::sync::manager::BlockProcessResult::Err(
+                    BlockError::BlockIsAlreadyKnown,
+                ),
+            });
+            return;
+        }
+
         // Check if the block is already being imported through another source
         let handle = match duplicate_cache.check_and_insert(block_root) {
             Some(handle) => handle,
+            None if retries >= self.max_rpc_block_requeues => {
+                warn!(
+                    self.log,
+                    "Ignoring RPC block after repeated requeues";
+                    "msg" => "the same block is still being processed from another source",
+                    "block_root" => %block_root,
+                    "attempts" => retries + 1,
+                );
+                metrics::inc_counter(&metrics::BEACON_PROCESSOR_RPC_BLOCK_REQUEUES_EXHAUSTED_TOTAL);
+                // Sync handles these results
+                self.send_sync_message(SyncMessage::BlockProcessed {
+                    process_type,
+                    result: crate::sync::manager::BlockProcessResult::Ignored,
+                });
+                return;
+            }
             None => {
                 debug!(
                     self.log,
                     "Gossip block is being processed";
                     "action" => "sending rpc block to reprocessing queue",
                     "block_root" => %block_root,
+                    "retries" => retries,
                 );
 
                 // Send message to work reprocess queue to retry the block
@@ -124,17 +237,26 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     block_root,
                     block,
                     seen_timestamp,
-                    process_type,
+                    process_type.clone(),
+                    retries + 1,
                 );
                 let reprocess_msg = ReprocessQueueMessage::RpcBlock(QueuedRpcBlock {
                     beacon_block_root: block_root,
+                    retries: retries + 1,
                     process_fn,
                     ignore_fn,
                 });
 
-                if reprocess_tx.try_send(reprocess_msg).is_err() {
-                    error!(self.log, "Failed to inform block import"; "source" => "rpc", "block_root" => %block_root)
-                };
+                if !self
+                    .send_to_reprocess_queue(&reprocess_tx, reprocess_msg, block_root)
+                    .await
+                {
+                    // Sync must not be left waiting for a block which will never be processed.
+                    self.send_sync_message(SyncMessage::BlockProcessed {
+                        process_type,
+                        result: crate::sync::manager::BlockProcessResult::Ignored,
+                    });
+                }
                 return;
             }
         };
@@ -181,27 +303,43 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 "proposer" => block.message().proposer_index(),
                 "slot" => block.slot()
             );
+            metrics::inc_counter(&metrics::BEACON_PROCESSOR_RPC_BLOCK_EQUIVOCATION_DELAYED_TOTAL);
+            if let Some(event_handler) = self.chain.event_handler.as_ref() {
+                if event_handler.has_delayed_equivocating_block_subscribers() {
+                    event_handler.register(EventKind::DelayedEquivocatingBlock(
+                        SseDelayedEquivocatingBlock {
+                            slot: block.slot(),
+                            block: block_root,
+                            proposer_index: block.message().proposer_index(),
+                        },
+                    ));
+                }
+            }
 
             // Send message to work reprocess queue to retry the block
             let (process_fn, ignore_fn) = self.clone().generate_rpc_beacon_block_fns(
                 block_root,
                 block,
                 seen_timestamp,
-                process_type,
+                process_type.clone(),
+                retries,
             );
             let reprocess_msg = ReprocessQueueMessage::RpcBlock(QueuedRpcBlock {
                 beacon_block_root: block_root,
+                retries,
                 process_fn,
                 ignore_fn,
             });
 
-            if reprocess_tx.try_send(reprocess_msg).is_err() {
-                error!(
-                    self.log,
-                    "Failed to inform block import";
-                    "source" => "rpc",
-                    "block_root" => %block_root
-                );
+            if !self
+                .send_to_reprocess_queue(&reprocess_tx, reprocess_msg, block_root)
+                .await
+            {
+                // Sync must not be left waiting for a block which will never be processed.
+                self.send_sync_message(SyncMessage::BlockProcessed {
+                    process_type,
+                    result: crate::sync::manager::BlockProcessResult::Ignored,
+                });
             }
             return;
         }
@@ -224,9 +362,9 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 block_root: hash,
                 parent_root,
             };
-            if reprocess_tx.try_send(reprocess_msg).is_err() {
-                error!(self.log, "Failed to inform block import"; "source" => "rpc", "block_root" => %hash)
-            };
+            // Sync is informed of the result below, regardless of whether this succeeds.
+            self.send_to_reprocess_queue(&reprocess_tx, reprocess_msg, hash)
+                .await;
             if matches!(process_type, BlockProcessType::SingleBlock { .. }) {
                 self.chain.block_times_cache.write().set_time_observed(
                     hash,
@@ -249,6 +387,50 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         drop(handle);
     }
 
+    /// Send `reprocess_msg` regarding the RPC block with `block_root` to the reprocessing queue.
+    ///
+    /// If the queue is full, wait up to `REPROCESS_QUEUE_SEND_TIMEOUT` for space to become
+    /// available. Returns `false` if the message was dropped.
+    async fn send_to_reprocess_queue(
+        &self,
+        reprocess_tx: &mpsc::Sender<ReprocessQueueMessage>,
+        reprocess_msg: ReprocessQueueMessage,
+        block_root: Hash256,
+    ) -> bool {
+        let result = match reprocess_tx.try_send(reprocess_msg) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(reprocess_msg)) => {
+                match tokio::time::timeout(
+                    REPROCESS_QUEUE_SEND_TIMEOUT,
+                    reprocess_tx.send(reprocess_msg),
+                )
+                .await
+                {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(_)) => Err("queue closed"),
+                    Err(_) => Err("queue full"),
+                }
+            }
+            Err(TrySendError::Closed(_)) => Err("queue closed"),
+        };
+
+        if let Err(reason) = result {
+            error!(
+                self.log,
+                "Failed to send to reprocessing queue";
+                "source" => "rpc",
+                "block_root" => %block_root,
+                "reason" => reason,
+            );
+            metrics::inc_counter(
+                &metrics::BEACON_PROCESSOR_RPC_BLOCK_REPROCESS_QUEUE_DROPPED_TOTAL,
+            );
+            false
+        } else {
+            true
+        }
+    }
+
     /// Attempt to import the chain segment (`blocks`) to the beacon chain, informing the sync
     /// thread if more blocks are needed to process it.
     pub async fn process_chain_segment(
@@ -256,8 +438,87 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         sync_type: ChainSegmentProcessId,
         downloaded_blocks: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
         notify_execution_layer: NotifyExecutionLayer,
+        cancellation_token: Option<CancellationToken>,
+    ) {
+        let result = self
+            .process_chain_segment_blocks(
+                &sync_type,
+                downloaded_blocks,
+                notify_execution_layer,
+                cancellation_token,
+            )
+            .await;
+
+        match sync_type {
+            // Administrative imports are not driven by the sync manager, the result is returned
+            // via `Self::process_admin_import` instead.
+            ChainSegmentProcessId::AdminImport { .. } => {}
+            ChainSegmentProcessId::RangeBatchId(..)
+            | ChainSegmentProcessId::BackSyncBatchId(_)
+            | ChainSegmentProcessId::ParentLookup(_) => {
+                self.send_sync_message(SyncMessage::BatchProcessed { sync_type, result })
+            }
+        }
+    }
+
+    /// Attempt to import blocks supplied by an administrative tool, returning the result to the
+    /// caller via `result_tx` rather than to the sync manager.
+    pub async fn process_admin_import(
+        &self,
+        label: String,
+        blocks: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        result_tx: oneshot::Sender<BatchProcessResult>,
     ) {
-        let result = match sync_type {
+        let result = self
+            .process_chain_segment_blocks(
+                &ChainSegmentProcessId::AdminImport { label },
+                blocks,
+                notify_execution_layer,
+                None,
+            )
+            .await;
+
+        if result_tx.send(result).is_err() {
+            debug!(
+                self.log,
+                "Admin import result dropped";
+                "reason" => "receiver dropped"
+            );
+        }
+    }
+
+    /// Processes `downloaded_blocks` in the manner appropriate for `sync_type`.
+    ///
+    /// The `cancellation_token` is only respected for range sync batches, which are cancelled
+    /// when their chain is removed.
+    async fn process_chain_segment_blocks(
+        &self,
+        sync_type: &ChainSegmentProcessId,
+        downloaded_blocks: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        cancellation_token: Option<CancellationToken>,
+    ) -> BatchProcessResult {
+        // Backfill sync does not verify execution payloads, so it can proceed whilst the EL is
+        // offline.
+        if !matches!(sync_type, ChainSegmentProcessId::BackSyncBatchId(_))
+            && self
+                .execution_layer_offline_for(&downloaded_blocks, notify_execution_layer)
+                .await
+        {
+            // Fail early rather than paying the cost of signature verification and state
+            // processing for a segment which cannot be imported.
+            debug!(self.log,
+                "Execution layer offline";
+                "outcome" => "pausing sync",
+                "sync_type" => ?sync_type,
+                "blocks" => downloaded_blocks.len(),
+            );
+            metrics::inc_counter(&metrics::BEACON_PROCESSOR_CHAIN_SEGMENT_EL_OFFLINE_TOTAL);
+            return BatchProcessResult::NonFaultyFailure;
+        }
+
+        match sync_type {
             // this a request from the range sync
             ChainSegmentProcessId::RangeBatchId(chain_id, epoch) => {
                 let start_slot = downloaded_blocks.first().map(|b| b.slot().as_u64());
@@ -265,7 +526,12 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 let sent_blocks = downloaded_blocks.len();
 
                 match self
-                    .process_blocks(downloaded_blocks.iter(), notify_execution_layer)
+                    .process_blocks(
+                        downloaded_blocks.iter(),
+                        notify_execution_layer,
+                        cancellation_token,
+                        false,
+                    )
                     .await
                 {
                     (_, Ok(_)) => {
@@ -287,15 +553,15 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             "chain" => chain_id,
                             "last_block_slot" => end_slot,
                             "imported_blocks" => imported_blocks,
+                            "failed_block_root" => ?e.failed_block_root,
                             "error" => %e.message,
                             "service" => "sync");
-                        match e.peer_action {
-                            Some(penalty) => BatchProcessResult::FaultyFailure {
-                                imported_blocks: imported_blocks > 0,
-                                penalty,
-                            },
-                            None => BatchProcessResult::NonFaultyFailure,
-                        }
+                        failed_batch_result(
+                            imported_blocks,
+                            e.failed_block_root,
+                            e.failed_block_slot(&downloaded_blocks),
+                            e.peer_action,
+                        )
                     }
                 }
             }
@@ -305,7 +571,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 let end_slot = downloaded_blocks.last().map(|b| b.slot().as_u64());
                 let sent_blocks = downloaded_blocks.len();
 
-                match self.process_backfill_blocks(downloaded_blocks) {
+                match self.process_backfill_blocks(&downloaded_blocks) {
                     (_, Ok(_)) => {
                         debug!(self.log, "Backfill batch processed";
                             "batch_epoch" => epoch,
@@ -317,20 +583,21 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             was_non_empty: sent_blocks > 0,
                         }
                     }
-                    (_, Err(e)) => {
+                    (imported_blocks, Err(e)) => {
                         debug!(self.log, "Backfill batch processing failed";
                             "batch_epoch" => epoch,
                             "first_block_slot" => start_slot,
                             "last_block_slot" => end_slot,
+                            "imported_blocks" => imported_blocks,
+                            "failed_block_root" => ?e.failed_block_root,
                             "error" => %e.message,
                             "service" => "sync");
-                        match e.peer_action {
-                            Some(penalty) => BatchProcessResult::FaultyFailure {
-                                imported_blocks: false,
-                                penalty,
-                            },
-                            None => BatchProcessResult::NonFaultyFailure,
-                        }
+                        failed_batch_result(
+                            imported_blocks,
+                            e.failed_block_root,
+                            e.failed_block_slot(&downloaded_blocks),
+                            e.peer_action,
+                        )
                     }
                 }
             }
@@ -341,21 +608,32 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "chain_hash" => %chain_head,
                     "blocks" => downloaded_blocks.len()
                 );
-                // parent blocks are ordered from highest slot to lowest, so we need to process in
-                // reverse
-                match self
-                    .process_blocks(downloaded_blocks.iter().rev(), notify_execution_layer)
-                    .await
-                {
+                // Parent blocks are ordered from highest slot to lowest. Sort them by slot and
+                // ensure they form a chain before paying for any verification.
+                let result = match prepare_chain_segment(downloaded_blocks) {
+                    Ok(chain_segment) => {
+                        self.process_blocks(
+                            chain_segment.iter().map(|(_, block)| block),
+                            notify_execution_layer,
+                            None,
+                            false,
+                        )
+                        .await
+                    }
+                    Err(error) => (0, self.handle_failed_chain_segment(error)),
+                };
+                match result {
                     (imported_blocks, Err(e)) => {
-                        debug!(self.log, "Parent lookup failed"; "error" => %e.message);
-                        match e.peer_action {
-                            Some(penalty) => BatchProcessResult::FaultyFailure {
-                                imported_blocks: imported_blocks > 0,
-                                penalty,
-                            },
-                            None => BatchProcessResult::NonFaultyFailure,
-                        }
+                        debug!(self.log, "Parent lookup failed";
+                            "imported_blocks" => imported_blocks,
+                            "failed_block_root" => ?e.failed_block_root,
+                            "error" => %e.message);
+                        failed_batch_result(
+                            imported_blocks,
+                            e.failed_block_root,
+                            None,
+                            e.peer_action,
+                        )
                     }
                     (imported_blocks, Ok(_)) => {
                         debug!(self.log, "Parent lookup processed successfully");
@@ -365,23 +643,133 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     }
                 }
             }
-        };
+            // this is a request from an administrative tool, which may contain several forks
+            ChainSegmentProcessId::AdminImport { label } => {
+                let start_slot = downloaded_blocks.first().map(|b| b.slot().as_u64());
+                let end_slot = downloaded_blocks.last().map(|b| b.slot().as_u64());
+                let sent_blocks = downloaded_blocks.len();
 
-        self.send_sync_message(SyncMessage::BatchProcessed { sync_type, result });
+                match self
+                    .process_blocks(downloaded_blocks.iter(), notify_execution_layer, None, true)
+                    .await
+                {
+                    (_, Ok(_)) => {
+                        info!(self.log, "Admin import processed";
+                            "label" => label,
+                            "first_block_slot" => start_slot,
+                            "last_block_slot" => end_slot,
+                            "processed_blocks" => sent_blocks);
+                        BatchProcessResult::Success {
+                            was_non_empty: sent_blocks > 0,
+                        }
+                    }
+                    (imported_blocks, Err(e)) => {
+                        warn!(self.log, "Admin import failed";
+                            "label" => label,
+                            "first_block_slot" => start_slot,
+                            "last_block_slot" => end_slot,
+                            "imported_blocks" => imported_blocks,
+                            "failed_block_root" => ?e.failed_block_root,
+                            "error" => %e.message);
+                        failed_batch_result(
+                            imported_blocks,
+                            e.failed_block_root,
+                            None,
+                            e.peer_action,
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if processing `blocks` would require the execution layer, but it is known to
+    /// be offline.
+    async fn execution_layer_offline_for(
+        &self,
+        blocks: &[Arc<SignedBeaconBlock<T::EthSpec>>],
+        notify_execution_layer: NotifyExecutionLayer,
+    ) -> bool {
+        if matches!(notify_execution_layer, NotifyExecutionLayer::No) {
+            return false;
+        }
+
+        // Pre-merge blocks do not require the execution layer.
+        let has_execution_payload = blocks.iter().any(|block| {
+            block
+                .message()
+                .execution_payload()
+                .map_or(false, |payload| {
+                    payload.block_hash() != ExecutionBlockHash::zero()
+                })
+        });
+        if !has_execution_payload {
+            return false;
+        }
+
+        match self.chain.execution_layer.as_ref() {
+            Some(execution_layer) => execution_layer.is_offline().await,
+            None => false,
+        }
     }
 
     /// Helper function to process blocks batches which only consumes the chain and blocks to process.
+    ///
+    /// Returns the number of imported blocks. If a block failed import its root is included in
+    /// the error.
+    ///
+    /// If `cancellation_token` is cancelled, processing stops between blocks and an error without
+    /// a peer action is returned.
+    ///
+    /// If `allow_independent_forks` is `true`, blocks which form several forks diverging from
+    /// imported blocks are verified concurrently (see
+    /// `BeaconChain::process_chain_segments_concurrently`). Otherwise the blocks must form a single
+    /// chain, as is required of the responses to sync requests.
     async fn process_blocks<'a>(
         &self,
         downloaded_blocks: impl Iterator<Item = &'a Arc<SignedBeaconBlock<T::EthSpec>>>,
         notify_execution_layer: NotifyExecutionLayer,
+        cancellation_token: Option<CancellationToken>,
+        allow_independent_forks: bool,
     ) -> (usize, Result<(), ChainSegmentFailed>) {
         let blocks: Vec<Arc<_>> = downloaded_blocks.cloned().collect();
-        match self
-            .chain
-            .process_chain_segment(blocks, notify_execution_layer)
-            .await
-        {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+
+        let chain_segments = if allow_independent_forks {
+            self.chain.partition_chain_segment(&blocks)
+        } else {
+            None
+        };
+        let result = match chain_segments {
+            Some(chain_segments) => {
+                debug!(
+                    self.log,
+                    "Processing independent chain segments concurrently";
+                    "segments" => chain_segments.len(),
+                    "blocks" => blocks.len(),
+                );
+                self.chain
+                    .process_chain_segments_concurrently(
+                        chain_segments,
+                        notify_execution_layer,
+                        Some(progress_tx),
+                        cancellation_token,
+                    )
+                    .await
+            }
+            None => {
+                self.chain
+                    .process_chain_segment_with_progress(
+                        blocks,
+                        notify_execution_layer,
+                        Some(progress_tx),
+                        cancellation_token,
+                    )
+                    .await
+            }
+        };
+
+        match result {
             ChainSegmentResult::Successful { imported_blocks } => {
                 metrics::inc_counter(&metrics::BEACON_PROCESSOR_CHAIN_SEGMENT_SUCCESS_TOTAL);
                 if imported_blocks > 0 {
@@ -394,19 +782,61 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 error,
             } => {
                 metrics::inc_counter(&metrics::BEACON_PROCESSOR_CHAIN_SEGMENT_FAILED_TOTAL);
-                let r = self.handle_failed_chain_segment(error);
+                // The failed block is only identified if each block reported before it was
+                // imported or filtered out, otherwise blocks prior to the failed block may need
+                // to be processed again. Failures of a whole batch leave it unknown.
+                let mut failed_block_root = None;
+                while let Ok((block_root, outcome)) = progress_rx.try_recv() {
+                    match outcome {
+                        ChainSegmentBlockOutcome::Imported
+                        | ChainSegmentBlockOutcome::NotImported(_) => continue,
+                        ChainSegmentBlockOutcome::Failed => failed_block_root = Some(block_root),
+                        ChainSegmentBlockOutcome::BatchFailed
+                        | ChainSegmentBlockOutcome::NotProcessed => {}
+                    }
+                    break;
+                }
+                let r = self
+                    .handle_failed_chain_segment(error)
+                    .map_err(|e| ChainSegmentFailed {
+                        failed_block_root,
+                        ..e
+                    });
                 if imported_blocks > 0 {
                     self.chain.recompute_head_at_current_slot().await;
                 }
                 (imported_blocks, r)
             }
+            ChainSegmentResult::Cancelled { imported_blocks } => {
+                debug!(
+                    self.log,
+                    "Chain segment processing cancelled";
+                    "imported_blocks" => imported_blocks,
+                );
+                if imported_blocks > 0 {
+                    self.chain.recompute_head_at_current_slot().await;
+                }
+                (
+                    imported_blocks,
+                    Err(ChainSegmentFailed {
+                        message: "Chain segment processing cancelled".to_string(),
+                        // The blocks are not at fault.
+                        peer_action: None,
+                        failed_block_root: None,
+                    }),
+                )
+            }
         }
     }
 
     /// Helper function to process backfill block batches which only consumes the chain and blocks to process.
+    ///
+    /// If a block is known to be invalid, the blocks which follow it have been verified against
+    /// the anchor and are imported on their own. The number of imported blocks is returned
+    /// alongside the error in that case.
     fn process_backfill_blocks(
         &self,
-        blocks: Vec<Arc<SignedBeaconBlock<T::EthSpec>>>,
+        blocks: &[Arc<SignedBeaconBlock<T::EthSpec>>],
     ) -> (usize, Result<(), ChainSegmentFailed>) {
         let blinded_blocks = blocks
             .iter()
@@ -440,10 +870,23 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                                 "expected_root" => ?expected_block_root
                             );
 
+                            // A block which doesn't match the anchor may be valid, with the blocks
+                            // between it and the anchor missing from a previous batch. Only
+                            // blame the block when its successor in this batch was valid.
+                            let failed_block_root = self
+                                .chain
+                                .store
+                                .get_anchor_info()
+                                .map_or(false, |anchor| {
+                                    anchor.oldest_block_parent != expected_block_root
+                                })
+                                .then_some(block_root);
+
                             ChainSegmentFailed {
                                 message: String::from("mismatched_block_root"),
                                 // The peer is faulty if they send blocks with bad roots.
                                 peer_action: Some(PeerAction::LowToleranceError),
+                                failed_block_root,
                             }
                         }
                         HistoricalBlockError::InvalidSignature
@@ -458,6 +901,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                                 message: "invalid_signature".into(),
                                 // The peer is faulty if they bad signatures.
                                 peer_action: Some(PeerAction::LowToleranceError),
+                                failed_block_root: None,
                             }
                         }
                         HistoricalBlockError::ValidatorPubkeyCacheTimeout => {
@@ -471,6 +915,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                                 message: "pubkey_cache_timeout".into(),
                                 // This is an internal error, do not penalize the peer.
                                 peer_action: None,
+                                failed_block_root: None,
                             }
                         }
                         HistoricalBlockError::NoAnchorInfo => {
@@ -481,6 +926,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                                 // There is no need to do a historical sync, this is not a fault of
                                 // the peer.
                                 peer_action: None,
+                                failed_block_root: None,
                             }
                         }
                         HistoricalBlockError::IndexOutOfBounds => {
@@ -493,6 +939,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                                 message: String::from("logic_error"),
                                 // This should never occur, don't penalize the peer.
                                 peer_action: None,
+                                failed_block_root: None,
                             }
                         }
                         HistoricalBlockError::BlockOutOfRange { .. } => {
@@ -505,6 +952,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                                 message: String::from("unexpected_error"),
                                 // This should never occur, don't penalize the peer.
                                 peer_action: None,
+                                failed_block_root: None,
                             }
                         }
                     },
@@ -514,10 +962,48 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             message: format!("{:?}", other),
                             // This is an internal error, don't penalize the peer.
                             peer_action: None,
+                            failed_block_root: None,
                         }
                     }
                 };
-                (0, Err(err))
+                let imported_blocks =
+                    err.failed_block_slot(blocks)
+                        .map_or(0, |failed_block_slot| {
+                            self.import_backfill_blocks_after(blocks, failed_block_slot)
+                        });
+                (imported_blocks, Err(err))
+            }
+        }
+    }
+
+    /// Imports the `blocks` with slots greater than `failed_block_slot`, returning the number of
+    /// imported blocks.
+    ///
+    /// This must only be used when the block at `failed_block_slot` failed to chain onto its
+    /// successor in `blocks`, so that the successors are known to chain onto the anchor.
+    fn import_backfill_blocks_after(
+        &self,
+        blocks: &[Arc<SignedBeaconBlock<T::EthSpec>>],
+        failed_block_slot: Slot,
+    ) -> usize {
+        let verified_blocks = blocks
+            .iter()
+            .filter(|block| block.slot() > failed_block_slot)
+            .map(|full_block| Arc::new(full_block.clone_as_blinded()))
+            .collect::<Vec<_>>();
+        if verified_blocks.is_empty() {
+            return 0;
+        }
+        match self.chain.import_historical_block_batch(verified_blocks) {
+            Ok(imported_blocks) => imported_blocks,
+            Err(e) => {
+                debug!(
+                    self.log,
+                    "Failed to import blocks after invalid backfill block";
+                    "failed_block_slot" => failed_block_slot,
+                    "error" => ?e,
+                );
+                0
             }
         }
     }
@@ -527,16 +1013,21 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         &self,
         error: BlockError<T::EthSpec>,
     ) -> Result<(), ChainSegmentFailed> {
+        metrics::register_chain_segment_error(&error);
+
+        // The peer scoring for each error is defined alongside `BlockError`.
+        let peer_action = error.penalize_peer().map(peer_action_from_penalty);
+
         match error {
             BlockError::ParentUnknown(block) => {
                 // blocks should be sequential and all parents should exist
                 Err(ChainSegmentFailed {
                     message: format!("Block has an unknown parent: {}", block.parent_root()),
-                    // Peers are faulty if they send non-sequential blocks.
-                    peer_action: Some(PeerAction::LowToleranceError),
+                    peer_action,
+                    failed_block_root: None,
                 })
             }
-            BlockError::BlockIsAlreadyKnown => {
+            BlockError::BlockIsAlreadyKnown | BlockError::BlockAtFinalizedSlot { .. } => {
                 // This can happen for many reasons. Head sync's can download multiples and parent
                 // lookups can download blocks before range sync
                 Ok(())
@@ -544,6 +1035,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             BlockError::FutureSlot {
                 present_slot,
                 block_slot,
+                tolerance,
             } => {
                 if present_slot + FUTURE_SLOT_TOLERANCE >= block_slot {
                     // The block is too far in the future, drop it.
@@ -552,6 +1044,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         "msg" => "block for future slot rejected, check your time",
                         "present_slot" => present_slot,
                         "block_slot" => block_slot,
+                        "tolerance" => ?tolerance,
                         "FUTURE_SLOT_TOLERANCE" => FUTURE_SLOT_TOLERANCE,
                     );
                 } else {
@@ -560,6 +1053,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         self.log, "Block is slightly ahead of our slot clock. Ignoring.";
                         "present_slot" => present_slot,
                         "block_slot" => block_slot,
+                        "tolerance" => ?tolerance,
                         "FUTURE_SLOT_TOLERANCE" => FUTURE_SLOT_TOLERANCE,
                     );
                 }
@@ -569,8 +1063,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         "Block with slot {} is higher than the current slot {}",
                         block_slot, present_slot
                     ),
-                    // Peers are faulty if they send blocks from the future.
-                    peer_action: Some(PeerAction::LowToleranceError),
+                    peer_action,
+                    failed_block_root: None,
                 })
             }
             BlockError::WouldRevertFinalizedSlot { .. } => {
@@ -590,8 +1084,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
                 Err(ChainSegmentFailed {
                     message: format!("Internal error whilst processing block: {:?}", e),
-                    // Do not penalize peers for internal errors.
-                    peer_action: None,
+                    peer_action,
+                    failed_block_root: None,
                 })
             }
             ref err @ BlockError::ExecutionPayloadError(ref epe) => {
@@ -605,8 +1099,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     );
                     Err(ChainSegmentFailed {
                         message: format!("Execution layer offline. Reason: {:?}", err),
-                        // Do not penalize peers for internal errors.
-                        peer_action: None,
+                        peer_action,
+                        failed_block_root: None,
                     })
                 } else {
                     debug!(self.log,
@@ -618,7 +1112,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             "Peer sent a block containing invalid execution payload. Reason: {:?}",
                             err
                         ),
-                        peer_action: Some(PeerAction::LowToleranceError),
+                        peer_action,
+                        failed_block_root: None,
                     })
                 }
             }
@@ -631,10 +1126,39 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 );
                 Err(ChainSegmentFailed {
                     message: format!("Peer sent invalid block. Reason: {err:?}"),
-                    // We need to penalise harshly in case this represents an actual attack. In case
-                    // of a faulty EL it will usually require manual intervention to fix anyway, so
-                    // it's not too bad if we drop most of our peers.
-                    peer_action: Some(PeerAction::LowToleranceError),
+                    peer_action,
+                    failed_block_root: None,
+                })
+            }
+            ref err @ BlockError::ProposalSignatureInvalid
+            | ref err @ BlockError::InvalidSignature { .. } => {
+                // Internal errors are not penalized since they may be our own fault, however a
+                // block with an invalid signature can only be the fault of the peer that sent it.
+                debug!(
+                    self.log, "Invalid block signature received";
+                    "outcome" => %err,
+                );
+
+                Err(ChainSegmentFailed {
+                    message: format!("Peer sent block with invalid signature. Reason: {:?}", err),
+                    peer_action,
+                    failed_block_root: None,
+                })
+            }
+            BlockError::ParentPayloadUnavailable { parent_root } => {
+                warn!(
+                    self.log,
+                    "Unable to process chain segment";
+                    "msg" => "the execution payload of the parent is missing from the database",
+                    "parent_root" => ?parent_root,
+                );
+                Err(ChainSegmentFailed {
+                    message: format!(
+                        "Execution payload unavailable for parent block {:?}",
+                        parent_root
+                    ),
+                    peer_action,
+                    failed_block_root: None,
                 })
             }
             other => {
@@ -646,8 +1170,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
                 Err(ChainSegmentFailed {
                     message: format!("Peer sent invalid block. Reason: {:?}", other),
-                    // Do not penalize peers for internal errors.
-                    peer_action: None,
+                    peer_action,
+                    failed_block_root: None,
                 })
             }
         }
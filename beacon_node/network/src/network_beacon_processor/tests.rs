@@ -2,28 +2,32 @@
 #![cfg(test)]
 
 use crate::{
+    metrics,
     network_beacon_processor::{
         ChainSegmentProcessId, DuplicateCache, InvalidBlockStorage, NetworkBeaconProcessor,
     },
     service::NetworkMessage,
-    sync::{manager::BlockProcessType, SyncMessage},
+    sync::{
+        manager::{BatchProcessResult, BlockProcessResult, BlockProcessType},
+        SyncMessage,
+    },
 };
 use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
 };
-use beacon_chain::BeaconChain;
+use beacon_chain::{BeaconChain, BlockError, NotifyExecutionLayer};
 use beacon_processor::{work_reprocessing_queue::*, *};
 use lighthouse_network::{
     discv5::enr::{CombinedKey, EnrBuilder},
     rpc::methods::{MetaData, MetaDataV2},
     types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield},
-    Client, MessageId, NetworkGlobals, PeerId,
+    Client, MessageId, NetworkConfig, NetworkGlobals, PeerAction, PeerId,
 };
 use slot_clock::SlotClock;
 use std::iter::Iterator;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use types::{
     Attestation, AttesterSlashing, Epoch, EthSpec, Hash256, MainnetEthSpec, ProposerSlashing,
     SignedAggregateAndProof, SignedBeaconBlock, SignedVoluntaryExit, SubnetId,
@@ -55,7 +59,7 @@ struct TestRig {
     beacon_processor_tx: BeaconProcessorSend<E>,
     work_journal_rx: mpsc::Receiver<&'static str>,
     _network_rx: mpsc::UnboundedReceiver<NetworkMessage<E>>,
-    _sync_rx: mpsc::UnboundedReceiver<SyncMessage<E>>,
+    sync_rx: mpsc::UnboundedReceiver<SyncMessage<E>>,
     duplicate_cache: DuplicateCache,
     network_beacon_processor: Arc<NetworkBeaconProcessor<T>>,
     _harness: BeaconChainHarness<T>,
@@ -81,16 +85,48 @@ impl TestRig {
     }
 
     pub async fn new_parametric(chain_length: u64, enable_backfill_rate_limiting: bool) -> Self {
+        Self::new_inner(chain_length, enable_backfill_rate_limiting, false).await
+    }
+
+    /// As for `new`, but the chain is merged from genesis and backed by a mock execution layer.
+    pub async fn new_post_merge(chain_length: u64) -> Self {
+        Self::new_inner(
+            chain_length,
+            BeaconProcessorConfig::default().enable_backfill_rate_limiting,
+            true,
+        )
+        .await
+    }
+
+    async fn new_inner(
+        chain_length: u64,
+        enable_backfill_rate_limiting: bool,
+        post_merge: bool,
+    ) -> Self {
         // This allows for testing voluntary exits without building out a massive chain.
         let mut spec = E::default_spec();
         spec.shard_committee_period = 2;
+        if post_merge {
+            spec.altair_fork_epoch = Some(Epoch::new(0));
+            spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+        }
 
-        let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        let mut builder = BeaconChainHarness::builder(MainnetEthSpec)
             .spec(spec)
             .deterministic_keypairs(VALIDATOR_COUNT)
             .fresh_ephemeral_store()
-            .chain_config(<_>::default())
-            .build();
+            .chain_config(<_>::default());
+        if post_merge {
+            builder = builder.mock_execution_layer();
+        }
+        let harness = builder.build();
+
+        if post_merge {
+            harness
+                .execution_block_generator()
+                .move_to_terminal_block()
+                .unwrap();
+        }
 
         harness.advance_slot();
 
@@ -184,7 +220,7 @@ impl TestRig {
             work_reprocessing_rx,
         } = BeaconProcessorChannels::new(&beacon_processor_config);
 
-        let (sync_tx, _sync_rx) = mpsc::unbounded_channel();
+        let (sync_tx, sync_rx) = mpsc::unbounded_channel();
 
         // Default metadata
         let meta_data = MetaData::V2(MetaDataV2 {
@@ -210,6 +246,7 @@ impl TestRig {
             reprocess_tx: work_reprocessing_tx.clone(),
             network_globals: network_globals.clone(),
             invalid_block_storage: InvalidBlockStorage::Disabled,
+            max_rpc_block_requeues: NetworkConfig::default().max_rpc_block_requeues,
             executor: executor.clone(),
             log: log.clone(),
         };
@@ -245,7 +282,7 @@ impl TestRig {
             beacon_processor_tx,
             work_journal_rx,
             _network_rx,
-            _sync_rx,
+            sync_rx,
             duplicate_cache,
             network_beacon_processor,
             _harness: harness,
@@ -301,6 +338,7 @@ impl TestRig {
             .send_chain_segment(
                 ChainSegmentProcessId::BackSyncBatchId(Epoch::default()),
                 Vec::default(),
+                None,
             )
             .unwrap();
     }
@@ -918,6 +956,77 @@ async fn test_rpc_block_reprocessing() {
     assert_eq!(next_block_root, rig.head_root());
 }
 
+/// Ensure that an rpc block is ignored, rather than requeued indefinitely, whilst the duplicate
+/// cache handle is held by another task.
+#[tokio::test]
+async fn test_rpc_block_requeues_exhausted() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+    let next_block_root = rig.next_block.canonical_root();
+    let handle = rig.duplicate_cache.check_and_insert(next_block_root);
+    let exhausted_total = || {
+        metrics::BEACON_PROCESSOR_RPC_BLOCK_REQUEUES_EXHAUSTED_TOTAL
+            .as_ref()
+            .unwrap()
+            .get()
+    };
+
+    let exhausted_before = exhausted_total();
+    let max_rpc_block_requeues = rig.network_beacon_processor.max_rpc_block_requeues;
+    rig.network_beacon_processor
+        .clone()
+        .process_rpc_block(
+            next_block_root,
+            rig.next_block.clone(),
+            Duration::default(),
+            BlockProcessType::SingleBlock { id: 1 },
+            rig.network_beacon_processor.reprocess_tx.clone(),
+            rig.duplicate_cache.clone(),
+            max_rpc_block_requeues,
+        )
+        .await;
+
+    match rig.sync_rx.try_recv() {
+        Ok(SyncMessage::BlockProcessed {
+            result: BlockProcessResult::Ignored,
+            ..
+        }) => {}
+        other => panic!("expected an ignored block, got {:?}", other),
+    }
+    assert_eq!(exhausted_total(), exhausted_before + 1);
+
+    // The block was not requeued.
+    drop(handle);
+    tokio::time::sleep(QUEUED_RPC_BLOCK_DELAY).await;
+    rig.assert_no_events_for(Duration::from_millis(100)).await;
+    assert_ne!(next_block_root, rig.head_root());
+}
+
+/// Ensure that an rpc block which has already been imported is rejected without being processed
+/// again.
+#[tokio::test]
+async fn test_rpc_block_already_known() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+    let next_block_root = rig.next_block.canonical_root();
+
+    rig.enqueue_single_lookup_rpc_block();
+    rig.assert_event_journal(&[RPC_BLOCK, WORKER_FREED, NOTHING_TO_DO])
+        .await;
+    assert_eq!(next_block_root, rig.head_root());
+    while rig.sync_rx.try_recv().is_ok() {}
+
+    rig.enqueue_single_lookup_rpc_block();
+    rig.assert_event_journal(&[RPC_BLOCK, WORKER_FREED, NOTHING_TO_DO])
+        .await;
+
+    match rig.sync_rx.try_recv() {
+        Ok(SyncMessage::BlockProcessed {
+            result: BlockProcessResult::Err(BlockError::BlockIsAlreadyKnown),
+            ..
+        }) => {}
+        other => panic!("expected an already known block, got {:?}", other),
+    }
+}
+
 /// Ensure that backfill batches get rate-limited and processing is scheduled at specified intervals.
 #[tokio::test]
 async fn test_backfill_sync_processing() {
@@ -960,3 +1069,160 @@ async fn test_backfill_sync_processing_rate_limiting_disabled() {
     )
     .await;
 }
+
+/// Ensure that `ChainSegmentProcessId`s survive a round-trip through their serialized form.
+#[test]
+fn chain_segment_process_id_round_trip() {
+    let ids = vec![
+        ChainSegmentProcessId::RangeBatchId(7, Epoch::new(42)),
+        ChainSegmentProcessId::BackSyncBatchId(Epoch::new(3)),
+        ChainSegmentProcessId::ParentLookup(Hash256::repeat_byte(1)),
+        ChainSegmentProcessId::AdminImport {
+            label: "era_import".into(),
+        },
+    ];
+
+    for id in ids {
+        let json = serde_json::to_string(&id).unwrap();
+        let decoded: ChainSegmentProcessId = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, id);
+    }
+}
+
+/// Ensure that a range sync batch containing several forks is rejected, whilst the same blocks are
+/// imported when they are supplied by an administrative tool.
+#[tokio::test]
+async fn range_batch_with_independent_forks_is_rejected() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+    let head = rig.chain.head_snapshot();
+
+    // Two forks which both build upon the head.
+    let next_slot = rig.chain.slot().unwrap() + 1;
+    let (fork_b, _) = rig
+        ._harness
+        .make_block(head.beacon_state.clone(), next_slot)
+        .await;
+    let blocks = vec![rig.next_block.clone(), Arc::new(fork_b)];
+    rig.chain.slot_clock.set_slot(next_slot.as_u64());
+
+    rig.network_beacon_processor
+        .process_chain_segment(
+            ChainSegmentProcessId::RangeBatchId(1, next_slot.epoch(E::slots_per_epoch())),
+            blocks.clone(),
+            NotifyExecutionLayer::Yes,
+            None,
+        )
+        .await;
+    match rig.sync_rx.try_recv() {
+        Ok(SyncMessage::BatchProcessed {
+            result:
+                BatchProcessResult::FaultyFailure {
+                    penalty: PeerAction::LowToleranceError,
+                    ..
+                },
+            ..
+        }) => {}
+        other => panic!("expected a faulty failure, got {:?}", other),
+    }
+    for block in &blocks {
+        assert!(
+            !rig.chain
+                .canonical_head
+                .fork_choice_read_lock()
+                .contains_block(&block.canonical_root()),
+            "no blocks should be imported from a non-linear range batch"
+        );
+    }
+
+    let (result_tx, result_rx) = oneshot::channel();
+    rig.network_beacon_processor
+        .process_admin_import(
+            "independent_forks".into(),
+            blocks.clone(),
+            NotifyExecutionLayer::Yes,
+            result_tx,
+        )
+        .await;
+    assert!(matches!(
+        result_rx.await.unwrap(),
+        BatchProcessResult::Success {
+            was_non_empty: true
+        }
+    ));
+    for block in &blocks {
+        assert!(rig
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .contains_block(&block.canonical_root()));
+    }
+}
+
+/// Ensure that range sync batches are not processed whilst the execution layer is offline, and
+/// that they are processed once it recovers.
+#[tokio::test]
+async fn range_batch_paused_whilst_execution_layer_offline() {
+    let mut rig = TestRig::new_post_merge(SMALL_CHAIN).await;
+    let execution_layer = rig.chain.execution_layer.clone().unwrap();
+    let mock_server = &rig._harness.mock_execution_layer.as_ref().unwrap().server;
+    let blocks = vec![rig.next_block.clone()];
+    let block_root = rig.next_block.canonical_root();
+    let sync_type =
+        ChainSegmentProcessId::RangeBatchId(1, rig.next_block.slot().epoch(E::slots_per_epoch()));
+    let el_offline_total = || {
+        metrics::BEACON_PROCESSOR_CHAIN_SEGMENT_EL_OFFLINE_TOTAL
+            .as_ref()
+            .unwrap()
+            .get()
+    };
+
+    mock_server.set_syncing_response(Err("offline".into()));
+    execution_layer.upcheck().await;
+    assert!(execution_layer.is_offline().await);
+
+    let offline_before = el_offline_total();
+    rig.network_beacon_processor
+        .process_chain_segment(
+            sync_type.clone(),
+            blocks.clone(),
+            NotifyExecutionLayer::Yes,
+            None,
+        )
+        .await;
+    match rig.sync_rx.try_recv() {
+        Ok(SyncMessage::BatchProcessed {
+            result: BatchProcessResult::NonFaultyFailure,
+            ..
+        }) => {}
+        other => panic!("expected a non-faulty failure, got {:?}", other),
+    }
+    assert_eq!(el_offline_total(), offline_before + 1);
+    assert!(!rig
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&block_root));
+
+    mock_server.set_syncing_response(Ok(false));
+    execution_layer.upcheck().await;
+    assert!(!execution_layer.is_offline().await);
+
+    rig.network_beacon_processor
+        .process_chain_segment(sync_type, blocks, NotifyExecutionLayer::Yes, None)
+        .await;
+    match rig.sync_rx.try_recv() {
+        Ok(SyncMessage::BatchProcessed {
+            result: BatchProcessResult::Success {
+                was_non_empty: true,
+            },
+            ..
+        }) => {}
+        other => panic!("expected a success, got {:?}", other),
+    }
+    assert_eq!(el_offline_total(), offline_before + 1);
+    assert!(rig
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&block_root));
+}
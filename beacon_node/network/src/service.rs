@@ -316,6 +316,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             network_senders.network_send(),
             executor.clone(),
             invalid_block_storage,
+            config.max_rpc_block_requeues,
             beacon_processor_send,
             beacon_processor_reprocess_tx,
             network_log.clone(),
@@ -538,7 +538,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
 
         if let Err(e) = network
             .beacon_processor()
-            .send_chain_segment(process_id, blocks)
+            .send_chain_segment(process_id, blocks, None)
         {
             crit!(self.log, "Failed to send backfill segment to processor."; "msg" => "process_batch",
                 "error" => %e, "batch" => self.processing_target);
@@ -641,10 +641,19 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
                     self.process_completed_batches(network)
                 }
             }
-            BatchProcessResult::FaultyFailure {
-                imported_blocks,
-                penalty,
+            BatchProcessResult::FaultyFailure { penalty, .. }
+            | BatchProcessResult::PartialSuccess {
+                penalty: Some(penalty),
+                ..
             } => {
+                if let BatchProcessResult::PartialSuccess {
+                    failed_block_slot: Some(slot),
+                    ..
+                } = result
+                {
+                    // Blocks after the failed block have been imported, only request the rest.
+                    batch.end_at_slot(*slot);
+                }
                 match batch.processing_completed(BatchProcessingResult::FaultyFailure) {
                     Err(e) => {
                         // Batch was in the wrong state
@@ -673,10 +682,19 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
 
                     Ok(BatchOperationOutcome::Continue) => {
                         // chain can continue. Check if it can be progressed
-                        if *imported_blocks {
-                            // At least one block was successfully verified and imported, then we can be sure all
-                            // previous batches are valid and we only need to download the current failed
-                            // batch.
+                        if matches!(
+                            result,
+                            BatchProcessResult::PartialSuccess { .. }
+                                | BatchProcessResult::FaultyFailure {
+                                    failed_block_root: Some(_),
+                                    ..
+                                }
+                        ) {
+                            // At least one block was successfully verified, either because it was
+                            // imported or because the invalid block is known and its successor
+                            // chained onto the previous batch. Then we can be sure all previous
+                            // batches are valid and we only need to download the remainder of the
+                            // current failed batch.
                             self.advance_chain(network, batch_id);
                         }
                         // Handle this invalid batch, that is within the re-process retries limit.
@@ -685,7 +703,16 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
                     }
                 }
             }
-            BatchProcessResult::NonFaultyFailure => {
+            BatchProcessResult::NonFaultyFailure
+            | BatchProcessResult::PartialSuccess { penalty: None, .. } => {
+                if let BatchProcessResult::PartialSuccess {
+                    failed_block_slot: Some(slot),
+                    ..
+                } = result
+                {
+                    // Blocks after the failed block have been imported, only request the rest.
+                    batch.end_at_slot(*slot);
+                }
                 if let Err(e) = batch.processing_completed(BatchProcessingResult::NonFaultyFailure)
                 {
                     self.fail_sync(BackFillError::BatchInvalidState(batch_id, e.0))?;
@@ -448,7 +448,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             BlockProcessResult::Err(e) => {
                 trace!(self.log, "Single block processing failed"; "block" => %root, "error" => %e);
                 match e {
-                    BlockError::BlockIsAlreadyKnown => {
+                    BlockError::BlockIsAlreadyKnown | BlockError::BlockAtFinalizedSlot { .. } => {
                         // No error here
                     }
                     BlockError::BeaconChainError(e) => {
@@ -540,7 +540,8 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                 self.request_parent(parent_lookup, cx);
             }
             BlockProcessResult::Ok
-            | BlockProcessResult::Err(BlockError::BlockIsAlreadyKnown { .. }) => {
+            | BlockProcessResult::Err(BlockError::BlockIsAlreadyKnown { .. })
+            | BlockProcessResult::Err(BlockError::BlockAtFinalizedSlot { .. }) => {
                 // Check if the beacon processor is available
                 let beacon_processor = match cx.beacon_processor_if_enabled() {
                     Some(beacon_processor) => beacon_processor,
@@ -555,7 +556,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                 let (chain_hash, blocks, hashes, request) = parent_lookup.parts_for_processing();
                 let process_id = ChainSegmentProcessId::ParentLookup(chain_hash);
 
-                match beacon_processor.send_chain_segment(process_id, blocks) {
+                match beacon_processor.send_chain_segment(process_id, blocks, None) {
                     Ok(_) => {
                         self.processing_parent_lookups
                             .insert(chain_hash, (hashes, request));
@@ -634,16 +635,18 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             BatchProcessResult::Success { .. } => {
                 // nothing to do.
             }
-            BatchProcessResult::FaultyFailure {
-                imported_blocks: _,
-                penalty,
+            BatchProcessResult::FaultyFailure { penalty, .. }
+            | BatchProcessResult::PartialSuccess {
+                penalty: Some(penalty),
+                ..
             } => {
                 self.failed_chains.insert(chain_hash);
                 for peer_id in request.used_peers {
                     cx.report_peer(peer_id, penalty, "parent_chain_failure")
                 }
             }
-            BatchProcessResult::NonFaultyFailure => {
+            BatchProcessResult::NonFaultyFailure
+            | BatchProcessResult::PartialSuccess { penalty: None, .. } => {
                 // We might request this chain again if there is need but otherwise, don't try again
             }
         }
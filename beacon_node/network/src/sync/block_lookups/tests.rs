@@ -9,6 +9,7 @@ use super::*;
 
 use beacon_chain::builder::Witness;
 use beacon_chain::eth1_chain::CachingEth1Backend;
+use beacon_chain::SignatureKind;
 use beacon_processor::WorkEvent;
 use lighthouse_network::{NetworkGlobals, Request};
 use slog::{Drain, Level};
@@ -487,7 +488,10 @@ fn test_parent_lookup_too_many_processing_attempts_must_blacklist() {
         assert!(!bl.failed_chains.contains(&block_hash));
         // send the right parent but fail processing
         bl.parent_lookup_response(id, peer_id, Some(parent.clone()), D, &mut cx);
-        bl.parent_block_processed(block_hash, BlockError::InvalidSignature.into(), &mut cx);
+        let error = BlockError::InvalidSignature {
+            kind: SignatureKind::Unknown,
+        };
+        bl.parent_block_processed(block_hash, error.into(), &mut cx);
         bl.parent_lookup_response(id, peer_id, None, D, &mut cx);
         rig.expect_penalty();
     }
@@ -140,14 +140,25 @@ pub enum BlockProcessResult<T: EthSpec> {
 #[derive(Debug)]
 pub enum BatchProcessResult {
     /// The batch was completed successfully. It carries whether the sent batch contained blocks.
-    Success {
-        was_non_empty: bool,
+    Success { was_non_empty: bool },
+    /// Some blocks of the batch were imported before processing failed. It carries the number of
+    /// imported blocks, the root and slot of the block that failed (if known) and the penalty for
+    /// the peer (if the failure was their fault). The imported blocks are not necessarily a prefix
+    /// of the batch, since independent forks within it may be processed concurrently.
+    PartialSuccess {
+        imported_blocks: usize,
+        failed_block_root: Option<Hash256>,
+        failed_block_slot: Option<Slot>,
+        penalty: Option<PeerAction>,
     },
-    /// The batch processing failed. It carries whether the processing imported any block.
+    /// The batch processing failed without importing any blocks, due to a faulty peer. It carries
+    /// the root of the block that failed (if known) and the penalty for the peer.
     FaultyFailure {
-        imported_blocks: bool,
+        failed_block_root: Option<Hash256>,
         penalty: PeerAction,
     },
+    /// The batch processing failed without importing any blocks, but the peer can't be deemed
+    /// faulty.
     NonFaultyFailure,
 }
 
@@ -579,6 +590,8 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                 ChainSegmentProcessId::ParentLookup(chain_hash) => self
                     .block_lookups
                     .parent_chain_processed(chain_hash, result, &mut self.network),
+                // Administrative imports report their results directly to the caller.
+                ChainSegmentProcessId::AdminImport { .. } => {}
             },
         }
     }
@@ -208,6 +208,27 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
         )
     }
 
+    /// Narrows the batch to start at `slot`, so that a later download does not request the blocks
+    /// before it again. This is used when the blocks prior to `slot` have already been imported.
+    ///
+    /// Has no effect if `slot` is not within the batch.
+    pub fn skip_to_slot(&mut self, slot: Slot) {
+        if slot > self.start_slot && slot < self.end_slot {
+            self.start_slot = slot;
+        }
+    }
+
+    /// Narrows the batch to end at `slot` (inclusive), so that a later download does not request
+    /// the blocks after it again. This is used by backfill sync when the blocks after `slot` have
+    /// already been imported.
+    ///
+    /// Has no effect if `slot` is not within the batch.
+    pub fn end_at_slot(&mut self, slot: Slot) {
+        if slot >= self.start_slot && slot < self.end_slot {
+            self.end_slot = slot + 1;
+        }
+    }
+
     /// After different operations over a batch, this could be in a state that allows it to
     /// continue, or in failed state. When the batch has failed, we check if it did mainly due to
     /// processing failures. In this case the batch is considered failed and faulty.
@@ -529,3 +550,50 @@ impl<T: EthSpec> std::fmt::Debug for BatchState<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{BeaconBlock, ChainSpec, MinimalEthSpec as E, Signature};
+
+    fn block_at_slot(slot: Slot) -> Arc<SignedBeaconBlock<E>> {
+        let mut block = BeaconBlock::empty(&ChainSpec::minimal());
+        *block.slot_mut() = slot;
+        Arc::new(SignedBeaconBlock::from_block(block, Signature::empty()))
+    }
+
+    #[test]
+    fn backfill_batch_ends_at_failed_block() {
+        let mut batch = BatchInfo::<E>::new(&Epoch::new(4), 2);
+        let request = batch.to_blocks_by_range_request();
+        let start_slot = Slot::new(*request.start_slot());
+        let failed_block_slot = start_slot + 5;
+
+        // Slots outside of the batch are ignored.
+        batch.end_at_slot(start_slot - 1);
+        batch.end_at_slot(start_slot + *request.count());
+        assert_eq!(batch.to_blocks_by_range_request(), request);
+
+        // Only the failed block and those before it are requested again.
+        batch.end_at_slot(failed_block_slot);
+        let retry = batch.to_blocks_by_range_request();
+        assert_eq!(*retry.start_slot(), start_slot.as_u64());
+        assert_eq!(*retry.count(), 6);
+
+        // A peer which returns blocks after the failed block has failed the download.
+        assert!(batch
+            .start_downloading_from_peer(PeerId::random(), 1)
+            .is_ok());
+        assert!(batch.add_block(block_at_slot(failed_block_slot)).is_ok());
+        assert!(batch
+            .add_block(block_at_slot(failed_block_slot + 2))
+            .is_ok());
+        match batch.download_completed() {
+            Err(Ok((expected, received, BatchOperationOutcome::Continue))) => {
+                assert_eq!(expected, failed_block_slot + 1);
+                assert_eq!(received, failed_block_slot + 2);
+            }
+            _ => panic!("download should fail"),
+        }
+    }
+}
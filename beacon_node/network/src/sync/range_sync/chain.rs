@@ -11,6 +11,7 @@ use slog::{crit, debug, o, warn};
 use std::collections::{btree_map::Entry, BTreeMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
 /// Blocks are downloaded in batches from peers. This constant specifies how many epochs worth of
@@ -101,6 +102,10 @@ pub struct SyncingChain<T: BeaconChainTypes> {
     /// Batches validated by this chain.
     validated_batches: u64,
 
+    /// Cancelled when the chain is dropped (i.e., removed), so that the processing of any of its
+    /// batches stops early.
+    cancellation_token: CancellationToken,
+
     /// The chain's log.
     log: slog::Logger,
 }
@@ -147,6 +152,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             state: ChainSyncingState::Stopped,
             current_processing_batch: None,
             validated_batches: 0,
+            cancellation_token: CancellationToken::new(),
             log: log.new(o!("chain" => id)),
         }
     }
@@ -317,7 +323,11 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         let process_id = ChainSegmentProcessId::RangeBatchId(self.id, batch_id);
         self.current_processing_batch = Some(batch_id);
 
-        if let Err(e) = beacon_processor.send_chain_segment(process_id, blocks) {
+        if let Err(e) = beacon_processor.send_chain_segment(
+            process_id,
+            blocks,
+            Some(self.cancellation_token.clone()),
+        ) {
             crit!(self.log, "Failed to send chain segment to processor."; "msg" => "process_batch",
                 "error" => %e, "batch" => self.processing_target);
             // This is unlikely to happen but it would stall syncing since the batch now has no
@@ -512,21 +522,31 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                     self.process_completed_batches(network)
                 }
             }
-            BatchProcessResult::FaultyFailure {
-                imported_blocks,
-                penalty,
+            BatchProcessResult::FaultyFailure { penalty, .. }
+            | BatchProcessResult::PartialSuccess {
+                penalty: Some(penalty),
+                ..
             } => {
                 // Penalize the peer appropiately.
                 network.report_peer(peer, *penalty, "faulty_batch");
 
+                if let BatchProcessResult::PartialSuccess {
+                    failed_block_slot: Some(slot),
+                    ..
+                } = result
+                {
+                    // Blocks prior to the failed block have been imported, only request the rest.
+                    batch.skip_to_slot(*slot);
+                }
+
                 // Check if this batch is allowed to continue
                 match batch.processing_completed(BatchProcessingResult::FaultyFailure)? {
                     BatchOperationOutcome::Continue => {
                         // Chain can continue. Check if it can be moved forward.
-                        if *imported_blocks {
+                        if matches!(result, BatchProcessResult::PartialSuccess { .. }) {
                             // At least one block was successfully verified and imported, so we can be sure all
-                            // previous batches are valid and we only need to download the current failed
-                            // batch.
+                            // previous batches are valid and we only need to download the remainder of the
+                            // current failed batch.
                             self.advance_chain(network, batch_id);
                         }
                         // Handle this invalid batch, that is within the re-process retries limit.
@@ -557,9 +577,22 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                     }
                 }
             }
-            BatchProcessResult::NonFaultyFailure => {
+            BatchProcessResult::NonFaultyFailure
+            | BatchProcessResult::PartialSuccess { penalty: None, .. } => {
+                if let BatchProcessResult::PartialSuccess {
+                    failed_block_slot: Some(slot),
+                    ..
+                } = result
+                {
+                    // Blocks prior to the failed block have been imported, only request the rest.
+                    batch.skip_to_slot(*slot);
+                }
                 batch.processing_completed(BatchProcessingResult::NonFaultyFailure)?;
-                // Simply redownload the batch.
+                if matches!(result, BatchProcessResult::PartialSuccess { .. }) {
+                    // Some blocks were imported, so all previous batches are valid.
+                    self.advance_chain(network, batch_id);
+                }
+                // Simply redownload the (remainder of the) batch.
                 self.retry_batch_download(network, batch_id)
             }
         }
@@ -1059,6 +1092,12 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
     }
 }
 
+impl<T: BeaconChainTypes> Drop for SyncingChain<T> {
+    fn drop(&mut self) {
+        self.cancellation_token.cancel();
+    }
+}
+
 impl<T: BeaconChainTypes> slog::KV for &mut SyncingChain<T> {
     fn serialize(
         &self,
@@ -391,7 +391,7 @@ mod tests {
     use std::collections::HashSet;
     use std::sync::Arc;
     use store::MemoryStore;
-    use types::{Hash256, MinimalEthSpec as E};
+    use types::{Hash256, MinimalEthSpec as E, Slot};
 
     #[derive(Debug)]
     struct FakeStorage {
@@ -724,4 +724,43 @@ mod tests {
         rig.expect_chain_segment();
         rig.expect_chain_segment();
     }
+
+    #[test]
+    fn partially_successful_batch_requests_remaining_blocks() {
+        let (mut rig, mut range) = range(false);
+
+        let (peer, local_info, head_info) = rig.head_peer();
+        range.add_peer(&mut rig.cx, local_info, peer, head_info);
+        let (request_id, request) = rig.grab_request(&peer);
+        let ((chain_id, batch_id), id) = match request_id {
+            RequestId::Sync(crate::sync::manager::RequestId::RangeSync { id }) => {
+                (rig.cx.range_sync_response(id, true).unwrap(), id)
+            }
+            other => panic!("unexpected request {:?}", other),
+        };
+
+        // Complete the download and send the batch for processing.
+        range.blocks_by_range_response(&mut rig.cx, peer, chain_id, batch_id, id, None);
+        rig.expect_chain_segment();
+
+        // The blocks before `failed_block_slot` were imported before processing failed.
+        let start_slot = *request.start_slot();
+        let failed_block_slot = Slot::new(start_slot + 3);
+        range.handle_block_process_result(
+            &mut rig.cx,
+            chain_id,
+            batch_id,
+            BatchProcessResult::PartialSuccess {
+                imported_blocks: 3,
+                failed_block_root: Some(Hash256::random()),
+                failed_block_slot: Some(failed_block_slot),
+                penalty: None,
+            },
+        );
+
+        // Only the blocks from the failed block onwards are requested again.
+        let (_, retry) = rig.grab_request(&peer);
+        assert_eq!(*retry.start_slot(), failed_block_slot.as_u64());
+        assert_eq!(*retry.count(), *request.count() - 3);
+    }
 }
@@ -505,6 +505,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Shufflings are dependent on validator count and setting this value to a large number can consume a large amount of memory.")
             .takes_value(true)
         )
+        .arg(
+            Arg::with_name("committee-state-cache-size")
+            .long("committee-state-cache-size")
+            .value_name("SIZE")
+            .help("Blocks whose parent state is in an earlier epoch require a copy of that state to be \
+            advanced through the epoch boundary before they can be verified. This flag sets the number \
+            of such advanced states to cache, so that sibling blocks can reuse them. Each state is a \
+            full beacon state, so this can consume a large amount of memory. Disabled by default.")
+            .takes_value(true)
+        )
 
         /*
          * Monitoring metrics
@@ -785,6 +795,50 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("NUM_SLOTS")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("block-max-skip-slots")
+                .long("block-max-skip-slots")
+                .help(
+                    "Refuse to process a block which skips more than this many slots after its \
+                    parent. This prevents blocks from forcing a large amount of state processing. \
+                    Set to \"none\" to disable. [default: 65536]"
+                )
+                .value_name("NUM_SLOTS")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("not-finalized-descendant-grace-slots")
+                .long("not-finalized-descendant-grace-slots")
+                .help(
+                    "Ignore, rather than penalize, gossip blocks which conflict with finality \
+                    if they are within this many slots of the current slot. Such blocks may be \
+                    valid competing blocks which arrived as finalization advanced. [default: 2]"
+                )
+                .value_name("NUM_SLOTS")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("max-concurrent-payload-verifications")
+                .long("max-concurrent-payload-verifications")
+                .help(
+                    "Maximum number of execution payloads which may be sent to the execution \
+                    engine for verification at once. Further blocks wait for an earlier \
+                    verification to complete. Must be at least 1. [default: 16]"
+                )
+                .value_name("NUM_PAYLOADS")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("validator-pubkey-cache-lock-retries")
+                .long("validator-pubkey-cache-lock-retries")
+                .help(
+                    "Number of times block verification retries reading the validator pubkey \
+                    cache after its lock times out. Retrying avoids dropping blocks because of \
+                    brief lock contention. [default: 0]"
+                )
+                .value_name("NUM_RETRIES")
+                .takes_value(true)
+        )
         /*
          * Slasher.
          */
@@ -1046,6 +1100,32 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        for ensuring the EL is given ample notice. Default: 1/3 of a slot.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("future-block-tolerance")
+                .long("future-block-tolerance")
+                .value_name("MILLISECONDS")
+                .help("The clock disparity to tolerate when rejecting blocks from future slots. \
+                       When set, the same tolerance is applied to blocks received via gossip and \
+                       to blocks prior to import. By default gossip blocks are permitted the \
+                       MAXIMUM_GOSSIP_CLOCK_DISPARITY and no disparity is permitted at import.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("gossip-proposer-rate-limit")
+                .long("gossip-proposer-rate-limit")
+                .value_name("BLOCKS")
+                .help("The maximum number of distinct blocks to accept via gossip from a single \
+                       proposer within each rate limiting window. Disabled by default.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("gossip-proposer-rate-limit-window")
+                .long("gossip-proposer-rate-limit-window")
+                .value_name("SLOTS")
+                .help("The length of the window used by --gossip-proposer-rate-limit, in slots.")
+                .requires("gossip-proposer-rate-limit")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("always-prepare-payload")
                 .long("always-prepare-payload")
@@ -1209,6 +1289,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     developers. This directory is not pruned, users should be careful to avoid \
                     filling up their disks.")
         )
+        .arg(
+            Arg::with_name("block-processing-ssz-dir")
+            .long("block-processing-ssz-dir")
+            .value_name("PATH")
+            .help("Write the pre-state, post-state and block SSZ of every processed block as files \
+                    in this directory. Filenames include the block root and parent root. This \
+                    feature is only recommended for developers. This directory is not pruned, \
+                    users should be careful to avoid filling up their disks.")
+            .takes_value(true)
+        )
         .arg(
             Arg::with_name("progressive-balances")
                 .long("progressive-balances")
@@ -162,6 +162,10 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.shuffling_cache_size = cache_size;
     }
 
+    if let Some(cache_size) = clap_utils::parse_optional(cli_args, "committee-state-cache-size")? {
+        client_config.chain.committee_state_cache_size = cache_size;
+    }
+
     /*
      * Prometheus metrics HTTP server
      */
@@ -606,6 +610,37 @@ pub fn get_config<E: EthSpec>(
         };
     }
 
+    if let Some(max_skip_slots) = cli_args.value_of("block-max-skip-slots") {
+        client_config.chain.block_max_skip_slots = match max_skip_slots {
+            "none" => None,
+            n => Some(
+                n.parse()
+                    .map_err(|_| "Invalid block-max-skip-slots".to_string())?,
+            ),
+        };
+    }
+
+    if let Some(grace_slots) =
+        clap_utils::parse_optional(cli_args, "not-finalized-descendant-grace-slots")?
+    {
+        client_config.chain.not_finalized_descendant_grace_slots = grace_slots;
+    }
+
+    if let Some(max_payload_verifications) =
+        clap_utils::parse_optional::<usize>(cli_args, "max-concurrent-payload-verifications")?
+    {
+        if max_payload_verifications == 0 {
+            return Err("max-concurrent-payload-verifications must be at least 1".to_string());
+        }
+        client_config.chain.max_concurrent_payload_verifications = max_payload_verifications;
+    }
+
+    if let Some(retries) =
+        clap_utils::parse_optional(cli_args, "validator-pubkey-cache-lock-retries")?
+    {
+        client_config.chain.validator_pubkey_cache_lock_retries = retries;
+    }
+
     client_config.chain.max_network_size = lighthouse_network::gossip_max_size(
         spec.bellatrix_fork_epoch.is_some(),
         spec.gossip_max_size as usize,
@@ -763,6 +798,17 @@ pub fn get_config<E: EthSpec>(
                     / DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR
             });
 
+    client_config.chain.future_block_tolerance =
+        clap_utils::parse_optional(cli_args, "future-block-tolerance")?.map(Duration::from_millis);
+
+    client_config.chain.gossip_proposer_rate_limit =
+        clap_utils::parse_optional(cli_args, "gossip-proposer-rate-limit")?;
+    if let Some(window_slots) =
+        clap_utils::parse_optional(cli_args, "gossip-proposer-rate-limit-window")?
+    {
+        client_config.chain.gossip_proposer_rate_limit_window_slots = window_slots;
+    }
+
     client_config.chain.always_prepare_payload = cli_args.is_present("always-prepare-payload");
 
     if let Some(timeout) =
@@ -829,6 +875,9 @@ pub fn get_config<E: EthSpec>(
         client_config.network.invalid_block_storage = Some(path);
     }
 
+    client_config.chain.block_processing_ssz_dir =
+        clap_utils::parse_optional(cli_args, "block-processing-ssz-dir")?;
+
     if let Some(progressive_balances_mode) =
         clap_utils::parse_optional(cli_args, "progressive-balances")?
     {
@@ -922,6 +922,29 @@ pub struct SseLateHead {
     pub execution_optimistic: bool,
 }
 
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseBlockEquivocation {
+    pub slot: Slot,
+    pub block: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseOptimisticBlockRejected {
+    pub slot: Slot,
+    pub block: Hash256,
+    pub execution_block_hash: Option<ExecutionBlockHash>,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseDelayedEquivocatingBlock {
+    pub slot: Slot,
+    pub block: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+}
+
 #[superstruct(
     variants(V1, V2),
     variant_attributes(derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize))
@@ -1006,6 +1029,9 @@ pub enum EventKind<T: EthSpec> {
     ChainReorg(SseChainReorg),
     ContributionAndProof(Box<SignedContributionAndProof<T>>),
     LateHead(SseLateHead),
+    BlockEquivocation(SseBlockEquivocation),
+    OptimisticBlockRejected(SseOptimisticBlockRejected),
+    DelayedEquivocatingBlock(SseDelayedEquivocatingBlock),
     #[cfg(feature = "lighthouse")]
     BlockReward(BlockReward),
     PayloadAttributes(VersionedSsePayloadAttributes),
@@ -1023,6 +1049,9 @@ impl<T: EthSpec> EventKind<T> {
             EventKind::ContributionAndProof(_) => "contribution_and_proof",
             EventKind::PayloadAttributes(_) => "payload_attributes",
             EventKind::LateHead(_) => "late_head",
+            EventKind::BlockEquivocation(_) => "block_equivocation",
+            EventKind::OptimisticBlockRejected(_) => "optimistic_block_rejected",
+            EventKind::DelayedEquivocatingBlock(_) => "delayed_equivocating_block",
             #[cfg(feature = "lighthouse")]
             EventKind::BlockReward(_) => "block_reward",
         }
@@ -1067,6 +1096,27 @@ impl<T: EthSpec> EventKind<T> {
             "late_head" => Ok(EventKind::LateHead(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Late Head: {:?}", e)),
             )?)),
+            "block_equivocation" => Ok(EventKind::BlockEquivocation(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Block Equivocation: {:?}", e))
+                })?,
+            )),
+            "optimistic_block_rejected" => Ok(EventKind::OptimisticBlockRejected(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!(
+                        "Optimistic Block Rejected: {:?}",
+                        e
+                    ))
+                })?,
+            )),
+            "delayed_equivocating_block" => Ok(EventKind::DelayedEquivocatingBlock(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!(
+                        "Delayed Equivocating Block: {:?}",
+                        e
+                    ))
+                })?,
+            )),
             "voluntary_exit" => Ok(EventKind::VoluntaryExit(
                 serde_json::from_str(data).map_err(|e| {
                     ServerError::InvalidServerSentEvent(format!("Voluntary Exit: {:?}", e))
@@ -1112,6 +1162,9 @@ pub enum EventTopic {
     ContributionAndProof,
     LateHead,
     PayloadAttributes,
+    BlockEquivocation,
+    OptimisticBlockRejected,
+    DelayedEquivocatingBlock,
     #[cfg(feature = "lighthouse")]
     BlockReward,
 }
@@ -1130,6 +1183,9 @@ impl FromStr for EventTopic {
             "contribution_and_proof" => Ok(EventTopic::ContributionAndProof),
             "payload_attributes" => Ok(EventTopic::PayloadAttributes),
             "late_head" => Ok(EventTopic::LateHead),
+            "block_equivocation" => Ok(EventTopic::BlockEquivocation),
+            "optimistic_block_rejected" => Ok(EventTopic::OptimisticBlockRejected),
+            "delayed_equivocating_block" => Ok(EventTopic::DelayedEquivocatingBlock),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventTopic::BlockReward),
             _ => Err("event topic cannot be parsed.".to_string()),
@@ -1149,6 +1205,9 @@ impl fmt::Display for EventTopic {
             EventTopic::ContributionAndProof => write!(f, "contribution_and_proof"),
             EventTopic::PayloadAttributes => write!(f, "payload_attributes"),
             EventTopic::LateHead => write!(f, "late_head"),
+            EventTopic::BlockEquivocation => write!(f, "block_equivocation"),
+            EventTopic::OptimisticBlockRejected => write!(f, "optimistic_block_rejected"),
+            EventTopic::DelayedEquivocatingBlock => write!(f, "delayed_equivocating_block"),
             #[cfg(feature = "lighthouse")]
             EventTopic::BlockReward => write!(f, "block_reward"),
         }
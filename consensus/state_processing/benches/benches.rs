@@ -0,0 +1,109 @@
+use beacon_chain::test_utils::BeaconChainHarness;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use state_processing::block_signature_verifier::{
+    BlockSignatureVerifier, PubkeyDecompressionCache,
+};
+use state_processing::signature_sets::get_pubkey_from_state;
+use state_processing::ConsensusContext;
+use std::borrow::Cow;
+use types::{
+    test_utils::generate_deterministic_keypairs, BeaconState, EthSpec, Hash256, MainnetEthSpec,
+    SignedBeaconBlock, Slot,
+};
+
+type E = MainnetEthSpec;
+
+const VALIDATOR_COUNT: usize = 64;
+const BATCH_SIZE: usize = 8;
+
+/// Produce `BATCH_SIZE` distinct blocks for the same slot (and therefore the same proposer),
+/// along with their shared pre-state.
+fn get_same_proposer_blocks() -> (BeaconState<E>, Vec<SignedBeaconBlock<E>>) {
+    let runtime = tokio::runtime::Runtime::new().expect("should start runtime");
+    runtime.block_on(async {
+        let harness = BeaconChainHarness::builder(E::default())
+            .default_spec()
+            .keypairs(generate_deterministic_keypairs(VALIDATOR_COUNT))
+            .fresh_ephemeral_store()
+            .build();
+
+        // Fill the first epoch with fully-attested blocks so that the batch carries attestations.
+        let state = harness.get_current_state();
+        let slots = (1..E::slots_per_epoch()).map(Slot::new).collect::<Vec<_>>();
+        harness
+            .add_attested_blocks_at_slots(
+                state,
+                Hash256::zero(),
+                &slots,
+                &(0..VALIDATOR_COUNT).collect::<Vec<_>>(),
+            )
+            .await;
+
+        let state = harness.get_current_state();
+        let slot = state.slot() + 1;
+        let mut pre_state = None;
+        let mut blocks = Vec::with_capacity(BATCH_SIZE);
+        for _ in 0..BATCH_SIZE {
+            let (block, block_pre_state) = harness
+                .make_block_return_pre_state(state.clone(), slot)
+                .await;
+            pre_state = Some(block_pre_state);
+            blocks.push(block);
+        }
+
+        (pre_state.expect("batch is not empty"), blocks)
+    })
+}
+
+fn block_signatures(c: &mut Criterion) {
+    let (state, blocks) = get_same_proposer_blocks();
+    let spec = E::default_spec();
+
+    c.bench_function(
+        &format!(
+            "verify {} same-proposer blocks (uncached pubkeys)",
+            BATCH_SIZE
+        ),
+        |b| {
+            b.iter(|| {
+                for block in &blocks {
+                    let result = BlockSignatureVerifier::verify_entire_block(
+                        &state,
+                        |i| get_pubkey_from_state(&state, i),
+                        |pk_bytes| pk_bytes.decompress().ok().map(Cow::Owned),
+                        block,
+                        &mut ConsensusContext::new(block.slot()),
+                        &spec,
+                    );
+                    assert_eq!(black_box(result), Ok(()));
+                }
+            })
+        },
+    );
+
+    c.bench_function(
+        &format!(
+            "verify {} same-proposer blocks (cached pubkeys)",
+            BATCH_SIZE
+        ),
+        |b| {
+            b.iter(|| {
+                // Start from a cold cache each iteration so that only reuse *within* the batch
+                // is measured.
+                let cache = PubkeyDecompressionCache::new();
+                for block in &blocks {
+                    let result = cache.verify_entire_block(
+                        &state,
+                        block,
+                        &mut ConsensusContext::new(block.slot()),
+                        &spec,
+                    );
+                    assert_eq!(black_box(result), Ok(()));
+                }
+            })
+        },
+    );
+}
+
+criterion_group!(benches, block_signatures);
+criterion_main!(benches);
@@ -46,6 +46,20 @@ impl<T: EthSpec> ConsensusContext<T> {
         }
     }
 
+    pub fn slot(&self) -> Slot {
+        self.slot
+    }
+
+    /// Returns the proposer index, if it has been set or computed.
+    pub fn cached_proposer_index(&self) -> Option<u64> {
+        self.proposer_index
+    }
+
+    /// Returns the root of the block at `self.slot`, if it has been set or computed.
+    pub fn cached_current_block_root(&self) -> Option<Hash256> {
+        self.current_block_root
+    }
+
     pub fn set_proposer_index(mut self, proposer_index: u64) -> Self {
         self.proposer_index = Some(proposer_index);
         self
@@ -4,8 +4,10 @@ use super::signature_sets::{Error as SignatureSetError, *};
 use crate::per_block_processing::errors::{AttestationInvalid, BlockOperationError};
 use crate::{ConsensusContext, ContextError};
 use bls::{verify_signature_sets, PublicKey, PublicKeyBytes, SignatureSet};
+use parking_lot::RwLock;
 use rayon::prelude::*;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use types::{
     AbstractExecPayload, BeaconState, BeaconStateError, ChainSpec, EthSpec, Hash256,
     SignedBeaconBlock,
@@ -67,19 +69,121 @@ impl From<BlockOperationError<AttestationInvalid>> for Error {
     }
 }
 
+/// The default maximum number of keys held by a `PubkeyDecompressionCache`.
+///
+/// This is comfortably more than the number of distinct proposers and attesters in a batch of
+/// blocks on a small network, whilst bounding the memory used on mainnet to a few megabytes.
+pub const DEFAULT_PUBKEY_DECOMPRESSION_CACHE_SIZE: usize = 16_384;
+
+/// Memoizes `PublicKeyBytes -> PublicKey` decompression across many calls to
+/// `BlockSignatureVerifier`.
+///
+/// Decompression is the dominant cost of building signature sets when no
+/// `ValidatorPubkeyCache` is available (e.g., in tests or tooling which verify blocks directly
+/// against a `BeaconState`). Blocks from the same epoch share their proposers and attesters, so
+/// verifying a batch of them through one cache only decompresses each key once.
+///
+/// Only successful decompressions are cached; invalid bytes are re-checked (and rejected) each
+/// time they are seen.
+///
+/// The cache is intended to live for a single batch of blocks. It is never pruned, once it holds
+/// `capacity` keys any further keys are decompressed without being cached.
+pub struct PubkeyDecompressionCache {
+    pubkeys: RwLock<HashMap<PublicKeyBytes, PublicKey>>,
+    capacity: usize,
+}
+
+impl Default for PubkeyDecompressionCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_PUBKEY_DECOMPRESSION_CACHE_SIZE)
+    }
+}
+
+impl PubkeyDecompressionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a cache which holds at most `capacity` decompressed keys.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            pubkeys: RwLock::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// Returns the number of decompressed keys held by the cache.
+    pub fn len(&self) -> usize {
+        self.pubkeys.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Decompress `bytes`, using a previously decompressed key if one exists.
+    pub fn decompress(&self, bytes: &PublicKeyBytes) -> Option<PublicKey> {
+        if let Some(pubkey) = self.pubkeys.read().get(bytes).cloned() {
+            return Some(pubkey);
+        }
+
+        let pubkey = bytes.decompress().ok()?;
+        let mut pubkeys = self.pubkeys.write();
+        if pubkeys.len() < self.capacity {
+            pubkeys.insert(*bytes, pubkey.clone());
+        }
+        Some(pubkey)
+    }
+
+    /// Equivalent to `signature_sets::get_pubkey_from_state`, but memoized.
+    pub fn get_pubkey_from_state<'a, T: EthSpec>(
+        &self,
+        state: &BeaconState<T>,
+        validator_index: usize,
+    ) -> Option<Cow<'a, PublicKey>> {
+        state
+            .validators()
+            .get(validator_index)
+            .and_then(|v| self.decompress(&v.pubkey))
+            .map(Cow::Owned)
+    }
+
+    /// Equivalent to `BlockSignatureVerifier::verify_entire_block`, using `self` for all pubkey
+    /// lookups and decompression.
+    pub fn verify_entire_block<'a, T: EthSpec, Payload: AbstractExecPayload<T>>(
+        &self,
+        state: &'a BeaconState<T>,
+        block: &'a SignedBeaconBlock<T, Payload>,
+        ctxt: &mut ConsensusContext<T>,
+        spec: &'a ChainSpec,
+    ) -> Result<()> {
+        BlockSignatureVerifier::verify_entire_block(
+            state,
+            |i| self.get_pubkey_from_state(state, i),
+            |pk_bytes| self.decompress(pk_bytes).map(Cow::Owned),
+            block,
+            ctxt,
+            spec,
+        )
+    }
+}
+
 /// Reads the BLS signatures and keys from a `SignedBeaconBlock`, storing them as a `Vec<SignatureSet>`.
 ///
 /// This allows for optimizations related to batch BLS operations (see the
 /// `Self::verify_entire_block(..)` function).
-pub struct BlockSignatureVerifier<'a, T, F, D>
+///
+/// The signature sets do not borrow from the `state` (lifetime `'s`), so the state may be dropped
+/// once `Self::into_signature_sets` has been called.
+pub struct BlockSignatureVerifier<'a, 's, T, F, D>
 where
     T: EthSpec,
     F: Fn(usize) -> Option<Cow<'a, PublicKey>> + Clone,
-    D: Fn(&'a PublicKeyBytes) -> Option<Cow<'a, PublicKey>>,
+    D: Fn(&PublicKeyBytes) -> Option<Cow<'a, PublicKey>>,
 {
     get_pubkey: F,
     decompressor: D,
-    state: &'a BeaconState<T>,
+    state: &'s BeaconState<T>,
     spec: &'a ChainSpec,
     sets: ParallelSignatureSets<'a>,
 }
@@ -95,16 +199,16 @@ impl<'a> From<Vec<SignatureSet<'a>>> for ParallelSignatureSets<'a> {
     }
 }
 
-impl<'a, T, F, D> BlockSignatureVerifier<'a, T, F, D>
+impl<'a, 's, T, F, D> BlockSignatureVerifier<'a, 's, T, F, D>
 where
     T: EthSpec,
     F: Fn(usize) -> Option<Cow<'a, PublicKey>> + Clone,
-    D: Fn(&'a PublicKeyBytes) -> Option<Cow<'a, PublicKey>>,
+    D: Fn(&PublicKeyBytes) -> Option<Cow<'a, PublicKey>>,
 {
     /// Create a new verifier without any included signatures. See the `include...` functions to
     /// add signatures, and the `verify`
     pub fn new(
-        state: &'a BeaconState<T>,
+        state: &'s BeaconState<T>,
         get_pubkey: F,
         decompressor: D,
         spec: &'a ChainSpec,
@@ -126,7 +230,7 @@ where
     ///
     /// See `Self::verify` for more detail.
     pub fn verify_entire_block<Payload: AbstractExecPayload<T>>(
-        state: &'a BeaconState<T>,
+        state: &'s BeaconState<T>,
         get_pubkey: F,
         decompressor: D,
         block: &'a SignedBeaconBlock<T, Payload>,
@@ -361,6 +465,14 @@ where
         Ok(())
     }
 
+    /// Consumes `self`, returning the signature sets that have been included so far.
+    ///
+    /// This allows the signatures of blocks which were included against different states to be
+    /// combined into a single batch.
+    pub fn into_signature_sets(self) -> ParallelSignatureSets<'a> {
+        self.sets
+    }
+
     /// Verify all the signatures that have been included in `self`, returning `true` if and only if
     /// all the signatures are valid.
     ///
@@ -402,4 +514,21 @@ impl<'a> ParallelSignatureSets<'a> {
             .map(|chunk| verify_signature_sets(chunk.iter()))
             .reduce(|| true, |current, this| current && this)
     }
+
+    /// Verify the signatures in all of `batches` at once, returning `true` if and only if all the
+    /// signatures are valid.
+    ///
+    /// Unlike `Self::verify`, the `batches` are not consumed. If verification fails, each batch
+    /// may then be verified on its own to determine which of them contains an invalid signature.
+    #[must_use]
+    pub fn verify_all(batches: impl IntoIterator<Item = &'a Self>) -> bool {
+        let sets = batches
+            .into_iter()
+            .flat_map(|batch| batch.sets.iter())
+            .collect::<Vec<_>>();
+        let num_chunks = std::cmp::max(1, sets.len() / rayon::current_num_threads());
+        sets.par_chunks(num_chunks)
+            .map(|chunk| verify_signature_sets(chunk.iter().copied()))
+            .reduce(|| true, |current, this| current && this)
+    }
 }
@@ -72,7 +72,7 @@ where
 
 /// A signature set that is valid if a block was signed by the expected block producer.
 pub fn block_proposal_signature_set<'a, T, F, Payload: AbstractExecPayload<T>>(
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     get_pubkey: F,
     signed_block: &'a SignedBeaconBlock<T, Payload>,
     block_root: Option<Hash256>,
@@ -157,7 +157,7 @@ where
 }
 
 pub fn bls_execution_change_signature_set<'a, T: EthSpec>(
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     signed_address_change: &'a SignedBlsToExecutionChange,
     spec: &'a ChainSpec,
 ) -> Result<SignatureSet<'a>> {
@@ -184,7 +184,7 @@ pub fn bls_execution_change_signature_set<'a, T: EthSpec>(
 
 /// A signature set that is valid if the block proposers randao reveal signature is correct.
 pub fn randao_signature_set<'a, T, F, Payload: AbstractExecPayload<T>>(
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     get_pubkey: F,
     block: BeaconBlockRef<'a, T, Payload>,
     verified_proposer_index: Option<u64>,
@@ -221,7 +221,7 @@ where
 
 /// Returns two signature sets, one for each `BlockHeader` included in the `ProposerSlashing`.
 pub fn proposer_slashing_signature_set<'a, T, F>(
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     get_pubkey: F,
     proposer_slashing: &'a ProposerSlashing,
     spec: &'a ChainSpec,
@@ -250,7 +250,7 @@ where
 
 /// Returns a signature set that is valid if the given `pubkey` signed the `header`.
 fn block_header_signature_set<'a, T: EthSpec>(
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     signed_header: &'a SignedBeaconBlockHeader,
     pubkey: Cow<'a, PublicKey>,
     spec: &'a ChainSpec,
@@ -269,7 +269,7 @@ fn block_header_signature_set<'a, T: EthSpec>(
 
 /// Returns the signature set for the given `indexed_attestation`.
 pub fn indexed_attestation_signature_set<'a, 'b, T, F>(
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     get_pubkey: F,
     signature: &'a AggregateSignature,
     indexed_attestation: &'b IndexedAttestation<T>,
@@ -333,7 +333,7 @@ where
 
 /// Returns the signature set for the given `attester_slashing` and corresponding `pubkeys`.
 pub fn attester_slashing_signature_sets<'a, T, F>(
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     get_pubkey: F,
     attester_slashing: &'a AttesterSlashing<T>,
     spec: &'a ChainSpec,
@@ -375,7 +375,7 @@ pub fn deposit_pubkey_signature_message(
 /// Returns a signature set that is valid if the `SignedVoluntaryExit` was signed by the indicated
 /// validator.
 pub fn exit_signature_set<'a, T, F>(
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     get_pubkey: F,
     signed_exit: &'a SignedVoluntaryExit,
     spec: &'a ChainSpec,
@@ -601,12 +601,12 @@ pub fn sync_aggregate_signature_set<'a, T, D>(
     sync_aggregate: &'a SyncAggregate<T>,
     slot: Slot,
     block_root: Hash256,
-    state: &'a BeaconState<T>,
+    state: &BeaconState<T>,
     spec: &ChainSpec,
 ) -> Result<Option<SignatureSet<'a>>>
 where
     T: EthSpec,
-    D: Fn(&'a PublicKeyBytes) -> Option<Cow<'a, PublicKey>>,
+    D: Fn(&PublicKeyBytes) -> Option<Cow<'a, PublicKey>>,
 {
     // Allow the point at infinity to count as a signature for 0 validators as per
     // `eth2_fast_aggregate_verify` from the spec.
@@ -1,5 +1,8 @@
 #![cfg(all(test, not(feature = "fake_crypto")))]
 
+use crate::per_block_processing::block_signature_verifier::{
+    self, BlockSignatureVerifier, PubkeyDecompressionCache,
+};
 use crate::per_block_processing::errors::{
     AttestationInvalid, AttesterSlashingInvalid, BlockOperationError, BlockProcessingError,
     DepositInvalid, HeaderInvalid, IndexedAttestationInvalid, IntoWithIndex,
@@ -7,12 +10,15 @@ use crate::per_block_processing::errors::{
 };
 use crate::{per_block_processing, StateProcessingStrategy};
 use crate::{
-    per_block_processing::{process_operations, verify_exit::verify_exit},
+    per_block_processing::{
+        process_operations, signature_sets::get_pubkey_from_state, verify_exit::verify_exit,
+    },
     BlockSignatureStrategy, ConsensusContext, VerifyBlockRoot, VerifySignatures,
 };
 use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
 use lazy_static::lazy_static;
 use ssz_types::Bitfield;
+use std::borrow::Cow;
 use test_utils::generate_deterministic_keypairs;
 use types::*;
 
@@ -180,6 +186,78 @@ async fn invalid_block_signature() {
     );
 }
 
+fn verify_block_signatures_uncached(
+    state: &BeaconState<MainnetEthSpec>,
+    block: &SignedBeaconBlock<MainnetEthSpec>,
+    spec: &ChainSpec,
+) -> Result<(), block_signature_verifier::Error> {
+    BlockSignatureVerifier::verify_entire_block(
+        state,
+        |i| get_pubkey_from_state(state, i),
+        |pk_bytes| pk_bytes.decompress().ok().map(Cow::Owned),
+        block,
+        &mut ConsensusContext::new(block.slot()),
+        spec,
+    )
+}
+
+#[tokio::test]
+async fn pubkey_decompression_cache_matches_uncached_verification() {
+    let spec = MainnetEthSpec::default_spec();
+    let harness = get_harness::<MainnetEthSpec>(EPOCH_OFFSET, VALIDATOR_COUNT).await;
+
+    let state = harness.get_current_state();
+    let slot = state.slot() + 1;
+    // Both blocks are built on the same state at the same slot, so they share a proposer.
+    let (block_a, pre_state) = harness
+        .make_block_return_pre_state(state.clone(), slot)
+        .await;
+    let (block_b, _) = harness.make_block_return_pre_state(state, slot).await;
+    assert_ne!(block_a.canonical_root(), block_b.canonical_root());
+
+    let cache = PubkeyDecompressionCache::new();
+    let verify_cached = |block: &SignedBeaconBlock<MainnetEthSpec>| {
+        cache.verify_entire_block(
+            &pre_state,
+            block,
+            &mut ConsensusContext::new(block.slot()),
+            &spec,
+        )
+    };
+
+    assert_eq!(
+        verify_block_signatures_uncached(&pre_state, &block_a, &spec),
+        Ok(())
+    );
+    assert_eq!(verify_cached(&block_a), Ok(()));
+    let cached_keys = cache.len();
+    assert!(cached_keys > 0);
+
+    // The second block from the same proposer should not need any further decompression.
+    assert_eq!(verify_cached(&block_b), Ok(()));
+    assert_eq!(cache.len(), cached_keys);
+
+    // A bad signature must be rejected identically with or without a warm cache.
+    let (block, _) = block_b.deconstruct();
+    let invalid_block = SignedBeaconBlock::from_block(block, Signature::empty());
+    let uncached_result = verify_block_signatures_uncached(&pre_state, &invalid_block, &spec);
+    assert!(uncached_result.is_err());
+    assert_eq!(verify_cached(&invalid_block), uncached_result);
+
+    // A full cache still verifies blocks, it just stops caching keys.
+    let bounded = PubkeyDecompressionCache::with_capacity(1);
+    assert_eq!(
+        bounded.verify_entire_block(
+            &pre_state,
+            &block_a,
+            &mut ConsensusContext::new(block_a.slot()),
+            &spec,
+        ),
+        Ok(())
+    );
+    assert_eq!(bounded.len(), 1);
+}
+
 #[tokio::test]
 async fn invalid_randao_reveal_signature() {
     let spec = MainnetEthSpec::default_spec();
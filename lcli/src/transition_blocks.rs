@@ -85,7 +85,9 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use store::HotColdDB;
-use types::{BeaconState, ChainSpec, CloneConfig, EthSpec, Hash256, SignedBeaconBlock};
+use types::{
+    BeaconState, ChainSpec, CloneConfig, EthSpec, Hash256, PublicKeyBytes, SignedBeaconBlock,
+};
 
 const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
 
@@ -354,7 +356,7 @@ fn do_transition<T: EthSpec>(
                 .map(Cow::Borrowed)
         };
 
-        let decompressor = move |pk_bytes| {
+        let decompressor = move |pk_bytes: &PublicKeyBytes| {
             // Map compressed pubkey to validator index.
             let validator_index = validator_pubkey_cache.get_index(pk_bytes)?;
             // Map validator index to pubkey (respecting guard on unknown validators).
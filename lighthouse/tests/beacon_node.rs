@@ -109,6 +109,87 @@ fn max_skip_slots_flag() {
         .with_config(|config| assert_eq!(config.chain.import_max_skip_slots, Some(10)));
 }
 
+#[test]
+fn block_max_skip_slots_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.block_max_skip_slots, Some(65536)));
+}
+
+#[test]
+fn block_max_skip_slots_flag() {
+    CommandLineTest::new()
+        .flag("block-max-skip-slots", Some("10"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.block_max_skip_slots, Some(10)));
+}
+
+#[test]
+fn block_max_skip_slots_none() {
+    CommandLineTest::new()
+        .flag("block-max-skip-slots", Some("none"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.block_max_skip_slots, None));
+}
+
+#[test]
+fn not_finalized_descendant_grace_slots_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.not_finalized_descendant_grace_slots, 2));
+}
+
+#[test]
+fn not_finalized_descendant_grace_slots_flag() {
+    CommandLineTest::new()
+        .flag("not-finalized-descendant-grace-slots", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.not_finalized_descendant_grace_slots, 0));
+}
+
+#[test]
+fn max_concurrent_payload_verifications_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.max_concurrent_payload_verifications,
+                beacon_node::beacon_chain::chain_config::DEFAULT_MAX_CONCURRENT_PAYLOAD_VERIFICATIONS
+            )
+        });
+}
+
+#[test]
+fn max_concurrent_payload_verifications_flag() {
+    CommandLineTest::new()
+        .flag("max-concurrent-payload-verifications", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.max_concurrent_payload_verifications, 4));
+}
+
+#[test]
+#[should_panic]
+fn max_concurrent_payload_verifications_zero() {
+    CommandLineTest::new()
+        .flag("max-concurrent-payload-verifications", Some("0"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn validator_pubkey_cache_lock_retries_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.validator_pubkey_cache_lock_retries, 0));
+}
+
+#[test]
+fn validator_pubkey_cache_lock_retries_flag() {
+    CommandLineTest::new()
+        .flag("validator-pubkey-cache-lock-retries", Some("3"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.validator_pubkey_cache_lock_retries, 3));
+}
+
 #[test]
 fn enable_lock_timeouts_default() {
     CommandLineTest::new()
@@ -144,6 +225,21 @@ fn shuffling_cache_set() {
         .with_config(|config| assert_eq!(config.chain.shuffling_cache_size, 500));
 }
 
+#[test]
+fn committee_state_cache_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.committee_state_cache_size, 0));
+}
+
+#[test]
+fn committee_state_cache_set() {
+    CommandLineTest::new()
+        .flag("committee-state-cache-size", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.committee_state_cache_size, 4));
+}
+
 #[test]
 fn fork_choice_before_proposal_timeout_default() {
     CommandLineTest::new()
@@ -208,6 +304,48 @@ fn prepare_payload_lookahead_shorter() {
         });
 }
 
+#[test]
+fn future_block_tolerance_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.future_block_tolerance, None));
+}
+
+#[test]
+fn future_block_tolerance_flag() {
+    CommandLineTest::new()
+        .flag("future-block-tolerance", Some("750"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.future_block_tolerance,
+                Some(Duration::from_millis(750))
+            )
+        });
+}
+
+#[test]
+fn gossip_proposer_rate_limit_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.gossip_proposer_rate_limit, None);
+            assert_eq!(config.chain.gossip_proposer_rate_limit_window_slots, 32);
+        });
+}
+
+#[test]
+fn gossip_proposer_rate_limit_flags() {
+    CommandLineTest::new()
+        .flag("gossip-proposer-rate-limit", Some("4"))
+        .flag("gossip-proposer-rate-limit-window", Some("64"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.gossip_proposer_rate_limit, Some(4));
+            assert_eq!(config.chain.gossip_proposer_rate_limit_window_slots, 64);
+        });
+}
+
 #[test]
 fn always_prepare_payload_default() {
     CommandLineTest::new()
@@ -2389,6 +2527,27 @@ fn invalid_gossip_verified_blocks_path() {
         });
 }
 
+#[test]
+fn block_processing_ssz_dir_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.block_processing_ssz_dir, None));
+}
+
+#[test]
+fn block_processing_ssz_dir_flag() {
+    let path = "/tmp/block-processing-ssz";
+    CommandLineTest::new()
+        .flag("block-processing-ssz-dir", Some(path))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.block_processing_ssz_dir,
+                Some(PathBuf::from(path))
+            )
+        });
+}
+
 #[test]
 fn progressive_balances_default() {
     CommandLineTest::new()